@@ -1,5 +1,11 @@
 // File: programs/wct-governance/src/lib.rs
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::keccak;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::system_program;
+use anchor_spl::metadata::MetadataAccount;
 use anchor_spl::token::{self, Mint, Token, TokenAccount};
 
 declare_id!("YOUR_GOVERNANCE_PROGRAM_ID");
@@ -14,7 +20,10 @@ pub mod wct_governance {
         min_proposal_tokens: u64,
         voting_period: i64,
         execution_delay: i64,
+        execution_window: i64,
         quorum_percentage: u8,
+        execution_bounty: u64,
+        guardian: Pubkey,
     ) -> Result<()> {
         let governance = &mut ctx.accounts.governance;
         
@@ -22,6 +31,8 @@ pub mod wct_governance {
         require!(quorum_percentage > 0 && quorum_percentage <= 100, GovernanceError::InvalidQuorumPercentage);
         require!(voting_period > 0, GovernanceError::InvalidVotingPeriod);
         require!(execution_delay >= 0, GovernanceError::InvalidExecutionDelay);
+        require!(execution_window >= 0, GovernanceError::InvalidExecutionWindow);
+        require!(execution_bounty <= MAX_EXECUTION_BOUNTY, GovernanceError::InvalidExecutionBounty);
         
         // Initialize governance
         governance.authority = ctx.accounts.authority.key();
@@ -30,23 +41,80 @@ pub mod wct_governance {
         governance.min_proposal_tokens = min_proposal_tokens;
         governance.voting_period = voting_period;
         governance.execution_delay = execution_delay;
+        governance.execution_window = execution_window;
         governance.quorum_percentage = quorum_percentage;
+        governance.execution_bounty = execution_bounty;
+        governance.guardian = guardian;
+        governance.paused = false;
+        governance.min_unique_voters = 0;
+        governance.staker_count = 0;
+        governance.latest_proposal_id = 0;
+        governance.treasury_epoch_cap = 0;
+        governance.treasury_epoch_duration = DEFAULT_TREASURY_EPOCH_DURATION;
+        governance.recent_treasury_withdrawals =
+            [TreasuryWithdrawalRecord::default(); Governance::MAX_RECENT_TREASURY_WITHDRAWALS];
+        governance.inactivity_window = 0;
+        governance.inactivity_decay_bps = 0;
         governance.proposal_count = 0;
         governance.total_voting_power = 0; // Will be updated as users stake
         governance.bump = *ctx.bumps.get("governance").unwrap();
         
+        // The governance token counts at full weight
+        governance.voting_mints = vec![VotingMint {
+            mint: governance.token_mint,
+            weight_bps: VotingMint::FULL_WEIGHT_BPS,
+        }];
+        
         // Initialize voting power registry
         let voting_power_registry = &mut ctx.accounts.voting_power_registry;
         voting_power_registry.governance = governance.key();
+        voting_power_registry.mint = governance.token_mint;
         voting_power_registry.total_voting_power = 0;
+        voting_power_registry.page_count = 0;
         voting_power_registry.bump = *ctx.bumps.get("voting_power_registry").unwrap();
+        voting_power_registry.staking_pool = Pubkey::default();
+        voting_power_registry.decayed_voting_power = 0;
+        
+        // Initialize the proposal ID sequence
+        let proposal_sequence = &mut ctx.accounts.proposal_sequence;
+        proposal_sequence.governance = governance.key();
+        proposal_sequence.next_proposal_id = 1;
+        proposal_sequence.bump = *ctx.bumps.get("proposal_sequence").unwrap();
+        
+        // Initialize the realm's stats
+        let governance_stats = &mut ctx.accounts.governance_stats;
+        governance_stats.governance = governance.key();
+        governance_stats.last_finalized_proposal = Pubkey::default();
+        governance_stats.bump = *ctx.bumps.get("governance_stats").unwrap();
         
         emit!(GovernanceInitializedEvent {
             governance: governance.key(),
             min_proposal_tokens,
             voting_period,
             execution_delay,
+            execution_window,
             quorum_percentage,
+            execution_bounty,
+        });
+        
+        Ok(())
+    }
+
+    // Allocate the next proposal ID to a proposer; the proposal PDA is derived from it in create_proposal
+    pub fn reserve_proposal_id(ctx: Context<ReserveProposalId>) -> Result<()> {
+        let proposal_sequence = &mut ctx.accounts.proposal_sequence;
+        let reservation = &mut ctx.accounts.reservation;
+        
+        reservation.governance = ctx.accounts.governance.key();
+        reservation.proposer = ctx.accounts.proposer.key();
+        reservation.proposal_id = proposal_sequence.next_proposal_id;
+        
+        proposal_sequence.next_proposal_id = proposal_sequence.next_proposal_id.checked_add(1).unwrap();
+        
+        emit!(ProposalIdReservedEvent {
+            governance: reservation.governance,
+            proposer: reservation.proposer,
+            proposal_id: reservation.proposal_id,
         });
         
         Ok(())
@@ -58,37 +126,113 @@ pub mod wct_governance {
         title: String,
         description: String,
         proposal_type: ProposalType,
-        execution_payload: Vec<u8>,
+        payload: ProposalPayload,
+        execution_item_count: u16,
+        council_vote: bool,
+        one_person_one_vote: bool,
+        requested_amount: u64,
+        action_hash: [u8; 32],
     ) -> Result<()> {
         let governance = &ctx.accounts.governance;
         let proposal = &mut ctx.accounts.proposal;
         let proposer = &ctx.accounts.proposer;
         let clock = Clock::get()?;
         
+        // Verify governance is not paused
+        require!(!governance.paused, GovernanceError::GovernancePaused);
+        
+        // Council proposals require a configured council collection
+        require!(
+            !council_vote || governance.council_collection != Pubkey::default(),
+            GovernanceError::CouncilNotConfigured
+        );
+        
+        // Council proposals already count one vote per seat
+        require!(
+            !(council_vote && one_person_one_vote),
+            GovernanceError::ConflictingVoteModes
+        );
+        
         // Verify user has enough tokens to create a proposal
         require!(
             ctx.accounts.proposer_token_account.amount >= governance.min_proposal_tokens,
             GovernanceError::InsufficientTokens
         );
         
+        // Reject malformed payloads before anyone votes on them
+        payload.validate(proposal_type, requested_amount, action_hash)?;
+        
+        // Categorized proposals must satisfy the category's rules and use its quorum
+        let quorum_percentage = match &ctx.accounts.category {
+            Some(category) => {
+                require!(
+                    category.proposal_type == proposal_type,
+                    GovernanceError::CategoryTypeMismatch
+                );
+                require!(
+                    requested_amount <= category.max_amount,
+                    GovernanceError::CategoryAmountExceeded
+                );
+                category.quorum_percentage
+            }
+            None => governance.quorum_percentage,
+        };
+        
         // Initialize proposal
         proposal.governance = governance.key();
         proposal.proposer = proposer.key();
-        proposal.proposal_id = governance.proposal_count + 1;
+        proposal.proposal_id = ctx.accounts.reservation.proposal_id;
         proposal.title = title;
         proposal.description = description;
         proposal.proposal_type = proposal_type;
-        proposal.execution_payload = execution_payload;
+        proposal.payload = payload;
+        proposal.item_count = execution_item_count;
+        proposal.items_added = 0;
+        proposal.items_executed = 0;
+        proposal.execution_started = false;
         proposal.created_at = clock.unix_timestamp;
         proposal.voting_ends_at = clock.unix_timestamp + governance.voting_period;
         proposal.yes_votes = 0;
         proposal.no_votes = 0;
         proposal.executed = false;
         proposal.cancelled = false;
+        proposal.outcome = ProposalOutcome::Pending;
+        proposal.council_vote = council_vote;
+        proposal.one_person_one_vote = one_person_one_vote;
+        proposal.category = ctx.accounts.category.as_ref().map(|category| category.key()).unwrap_or_default();
+        proposal.quorum_percentage = quorum_percentage;
+        proposal.requested_amount = requested_amount;
+        proposal.action_hash = action_hash;
+        proposal.voter_count = 0;
+        proposal.min_unique_voters = governance.min_unique_voters;
+        proposal.milestone_schedule = Pubkey::default();
+        
+        // Snapshot proposals are voted on by token holders proving their snapshot balance
+        match &ctx.accounts.snapshot {
+            Some(snapshot) => {
+                require!(
+                    !council_vote && !one_person_one_vote,
+                    GovernanceError::ConflictingVoteModes
+                );
+                proposal.snapshot = snapshot.key();
+            }
+            None => {
+                proposal.snapshot = Pubkey::default();
+            }
+        }
+        
+        // Fix the quorum base now, so power that registers or leaves during the vote can't move
+        // the bar the proposal is held to
+        let snapshot_total_balance = ctx.accounts.snapshot.as_ref().map(|snapshot| snapshot.total_balance);
+        proposal.quorum_base = quorum_base(governance, proposal, snapshot_total_balance);
         
         // Update governance proposal count
-        let governance_data = &mut ctx.accounts.governance.load_mut()?;
-        governance_data.proposal_count += 1;
+        let governance = &mut ctx.accounts.governance;
+        governance.proposal_count = governance.proposal_count.checked_add(1).unwrap();
+        governance.latest_proposal_id = governance.latest_proposal_id.max(proposal.proposal_id);
+        
+        let governance_stats = &mut ctx.accounts.governance_stats;
+        governance_stats.proposals_created = governance_stats.proposals_created.checked_add(1).unwrap();
         
         emit!(ProposalCreatedEvent {
             proposal: proposal.key(),
@@ -108,92 +252,55 @@ pub mod wct_governance {
         ctx: Context<CastVote>,
         vote: Vote,
     ) -> Result<()> {
-        let governance = &ctx.accounts.governance;
+        let governance = &mut ctx.accounts.governance;
         let proposal = &mut ctx.accounts.proposal;
         let voter = &ctx.accounts.voter;
-        let voting_power_registry = &ctx.accounts.voting_power_registry;
         let clock = Clock::get()?;
         
-        // Verify voting is still open
-        require!(
-            clock.unix_timestamp < proposal.voting_ends_at,
-            GovernanceError::VotingClosed
-        );
+        verify_voting_open(proposal, clock.unix_timestamp)?;
         
-        // Verify proposal is not cancelled
+        // Remaining accounts are the voter's VoterPower accounts followed by the registry of each
         require!(
-            !proposal.cancelled,
-            GovernanceError::ProposalCancelled
+            ctx.remaining_accounts.len() % 2 == 0,
+            GovernanceError::InvalidVotingPowerRegistry
         );
+        let (voter_power_accounts, registry_accounts) =
+            ctx.remaining_accounts.split_at(ctx.remaining_accounts.len() / 2);
         
-        // Verify proposal is not executed
-        require!(
-            !proposal.executed,
-            GovernanceError::ProposalAlreadyExecuted
-        );
+        // Council proposals are voted on with collection NFTs instead
+        require!(!proposal.council_vote, GovernanceError::CouncilProposal);
+        
+        // Snapshot proposals are voted on with balance proofs instead
+        require!(proposal.snapshot == Pubkey::default(), GovernanceError::SnapshotProposal);
         
-        // Get voter's voting power
-        let voter_power = get_voter_power(voting_power_registry, voter.key())?;
+        // Get voter's voting power, or a single vote per staker for one-person-one-vote proposals
+        let voter_power = if proposal.one_person_one_vote {
+            require!(
+                is_staker(governance, voter.key(), voter_power_accounts, clock.unix_timestamp)?,
+                GovernanceError::NotAStaker
+            );
+            1
+        } else {
+            get_voter_power(governance, voter.key(), voter_power_accounts, clock.unix_timestamp)?
+        };
         
         require!(voter_power > 0, GovernanceError::NoVotingPower);
         
-        // Check if the voter already voted
-        let voter_vote_account_info = &ctx.accounts.voter_vote;
+        record_vote(
+            proposal,
+            &mut ctx.accounts.voter_vote,
+            &mut ctx.accounts.voter_record,
+            governance.key(),
+            voter.key(),
+            vote,
+            voter_power,
+            *ctx.bumps.get("voter_record").unwrap(),
+            clock.unix_timestamp,
+            true,
+        )?;
         
-        if voter_vote_account_info.data_is_empty() {
-            // First time voting, create vote record
-            let voter_vote = &mut ctx.accounts.voter_vote;
-            voter_vote.voter = voter.key();
-            voter_vote.proposal = proposal.key();
-            voter_vote.vote = vote;
-            voter_vote.voting_power = voter_power;
-            
-            // Update proposal vote counts
-            match vote {
-                Vote::Yes => {
-                    proposal.yes_votes = proposal.yes_votes.checked_add(voter_power).unwrap();
-                }
-                Vote::No => {
-                    proposal.no_votes = proposal.no_votes.checked_add(voter_power).unwrap();
-                }
-                Vote::Abstain => {
-                    // Abstaining doesn't affect yes/no counts but still counts toward quorum
-                }
-            }
-        } else {
-            // Voter already voted, update their vote
-            let voter_vote = &mut ctx.accounts.voter_vote;
-            
-            // Remove previous vote
-            match voter_vote.vote {
-                Vote::Yes => {
-                    proposal.yes_votes = proposal.yes_votes.checked_sub(voter_vote.voting_power).unwrap();
-                }
-                Vote::No => {
-                    proposal.no_votes = proposal.no_votes.checked_sub(voter_vote.voting_power).unwrap();
-                }
-                Vote::Abstain => {
-                    // Abstaining doesn't affect yes/no counts
-                }
-            }
-            
-            // Update to new vote
-            voter_vote.vote = vote;
-            voter_vote.voting_power = voter_power; // Update voting power in case it changed
-            
-            // Add new vote
-            match vote {
-                Vote::Yes => {
-                    proposal.yes_votes = proposal.yes_votes.checked_add(voter_power).unwrap();
-                }
-                Vote::No => {
-                    proposal.no_votes = proposal.no_votes.checked_add(voter_power).unwrap();
-                }
-                Vote::Abstain => {
-                    // Abstaining doesn't affect yes/no counts
-                }
-            }
-        }
+        // Voting ends any inactivity decay; this vote was still counted at the decayed weight
+        restore_decayed_power(governance, voter_power_accounts, registry_accounts)?;
         
         emit!(VoteCastEvent {
             proposal: proposal.key(),
@@ -205,67 +312,451 @@ pub mod wct_governance {
         Ok(())
     }
 
-    // Execute a passed proposal
-    pub fn execute_proposal(ctx: Context<ExecuteProposal>) -> Result<()> {
+    // Delegate voting power to another wallet, optionally until an expiry timestamp
+    pub fn delegate_voting_power(
+        ctx: Context<DelegateVotingPower>,
+        delegate: Pubkey,
+        expires_at: Option<i64>,
+    ) -> Result<()> {
+        let delegation = &mut ctx.accounts.delegation;
+        let clock = Clock::get()?;
+        
+        require!(
+            delegate != ctx.accounts.delegator.key(),
+            GovernanceError::InvalidDelegate
+        );
+        
+        if let Some(expiry) = expires_at {
+            require!(expiry > clock.unix_timestamp, GovernanceError::InvalidDelegationExpiry);
+        }
+        
+        delegation.governance = ctx.accounts.governance.key();
+        delegation.delegator = ctx.accounts.delegator.key();
+        delegation.delegate = delegate;
+        delegation.expires_at = expires_at.unwrap_or(0);
+        delegation.bump = *ctx.bumps.get("delegation").unwrap();
+        
+        emit!(DelegationCreatedEvent {
+            governance: delegation.governance,
+            delegator: delegation.delegator,
+            delegate,
+            expires_at: delegation.expires_at,
+        });
+        
+        Ok(())
+    }
+
+    // Revoke a delegation immediately, closing the delegation account
+    pub fn revoke_delegation(ctx: Context<RevokeDelegation>) -> Result<()> {
+        let delegation = &ctx.accounts.delegation;
+        let clock = Clock::get()?;
+        
+        emit!(DelegationRevokedEvent {
+            governance: delegation.governance,
+            delegator: delegation.delegator,
+            delegate: delegation.delegate,
+            revoked_at: clock.unix_timestamp,
+        });
+        
+        Ok(())
+    }
+
+    // Cast a vote on behalf of a delegator. The vote is recorded under the delegator,
+    // so their power is only counted once and they can override it by voting themselves;
+    // a vote the delegator already cast directly can't be changed by the delegate.
+    pub fn cast_delegated_vote(
+        ctx: Context<CastDelegatedVote>,
+        vote: Vote,
+    ) -> Result<()> {
         let governance = &ctx.accounts.governance;
         let proposal = &mut ctx.accounts.proposal;
+        let delegation = &ctx.accounts.delegation;
         let clock = Clock::get()?;
         
-        // Verify voting is closed
+        verify_voting_open(proposal, clock.unix_timestamp)?;
+        
+        require!(!proposal.council_vote, GovernanceError::CouncilProposal);
+        require!(proposal.snapshot == Pubkey::default(), GovernanceError::SnapshotProposal);
+        
+        // Verify the delegation has not expired
         require!(
-            clock.unix_timestamp >= proposal.voting_ends_at,
-            GovernanceError::VotingStillOpen
+            delegation.expires_at == 0 || clock.unix_timestamp < delegation.expires_at,
+            GovernanceError::DelegationExpired
         );
         
-        // Verify proposal has not been executed
+        let voter_power = if proposal.one_person_one_vote {
+            require!(
+                is_staker(governance, delegation.delegator, ctx.remaining_accounts, clock.unix_timestamp)?,
+                GovernanceError::NotAStaker
+            );
+            1
+        } else {
+            get_voter_power(governance, delegation.delegator, ctx.remaining_accounts, clock.unix_timestamp)?
+        };
+        
+        require!(voter_power > 0, GovernanceError::NoVotingPower);
+        
+        record_vote(
+            proposal,
+            &mut ctx.accounts.voter_vote,
+            &mut ctx.accounts.voter_record,
+            governance.key(),
+            delegation.delegator,
+            vote,
+            voter_power,
+            *ctx.bumps.get("voter_record").unwrap(),
+            clock.unix_timestamp,
+            false,
+        )?;
+        
+        emit!(DelegatedVoteCastEvent {
+            proposal: proposal.key(),
+            delegator: delegation.delegator,
+            delegate: ctx.accounts.delegate.key(),
+            vote,
+            voting_power: voter_power,
+        });
+        
+        Ok(())
+    }
+
+    // Authorize a hot session key to vote on the owner's behalf until expiry, with capped power
+    pub fn create_voting_session(
+        ctx: Context<CreateVotingSession>,
+        session_key: Pubkey,
+        expires_at: i64,
+        max_power: u64,
+    ) -> Result<()> {
+        let voting_session = &mut ctx.accounts.voting_session;
+        let clock = Clock::get()?;
+        
         require!(
-            !proposal.executed,
-            GovernanceError::ProposalAlreadyExecuted
+            session_key != ctx.accounts.owner.key(),
+            GovernanceError::InvalidSessionKey
         );
         
-        // Verify proposal has not been cancelled
+        // Sessions must expire, and not too far in the future
         require!(
-            !proposal.cancelled,
-            GovernanceError::ProposalCancelled
+            expires_at > clock.unix_timestamp
+                && expires_at <= clock.unix_timestamp + MAX_VOTING_SESSION_DURATION,
+            GovernanceError::InvalidSessionExpiry
+        );
+        
+        require!(max_power > 0, GovernanceError::InvalidSessionPowerCap);
+        
+        voting_session.governance = ctx.accounts.governance.key();
+        voting_session.owner = ctx.accounts.owner.key();
+        voting_session.session_key = session_key;
+        voting_session.created_at = clock.unix_timestamp;
+        voting_session.expires_at = expires_at;
+        voting_session.max_power = max_power;
+        voting_session.bump = *ctx.bumps.get("voting_session").unwrap();
+        
+        emit!(VotingSessionCreatedEvent {
+            governance: voting_session.governance,
+            owner: voting_session.owner,
+            session_key,
+            expires_at,
+            max_power,
+        });
+        
+        Ok(())
+    }
+
+    // Revoke a voting session immediately, closing the session account
+    pub fn revoke_voting_session(ctx: Context<RevokeVotingSession>) -> Result<()> {
+        let voting_session = &ctx.accounts.voting_session;
+        let clock = Clock::get()?;
+        
+        emit!(VotingSessionRevokedEvent {
+            governance: voting_session.governance,
+            owner: voting_session.owner,
+            session_key: voting_session.session_key,
+            revoked_at: clock.unix_timestamp,
+        });
+        
+        Ok(())
+    }
+
+    // Cast a vote with a session key. Like delegated votes, the vote is recorded under the
+    // owner, can't change a vote the owner cast directly, and the power used is capped at the
+    // session's limit.
+    pub fn cast_session_vote(
+        ctx: Context<CastSessionVote>,
+        vote: Vote,
+    ) -> Result<()> {
+        let governance = &ctx.accounts.governance;
+        let proposal = &mut ctx.accounts.proposal;
+        let voting_session = &ctx.accounts.voting_session;
+        let clock = Clock::get()?;
+        
+        verify_voting_open(proposal, clock.unix_timestamp)?;
+        
+        require!(!proposal.council_vote, GovernanceError::CouncilProposal);
+        require!(proposal.snapshot == Pubkey::default(), GovernanceError::SnapshotProposal);
+        
+        // Verify the session has not expired
+        require!(
+            clock.unix_timestamp < voting_session.expires_at,
+            GovernanceError::VotingSessionExpired
         );
         
-        // Verify execution delay has passed
+        let voter_power = if proposal.one_person_one_vote {
+            require!(
+                is_staker(governance, voting_session.owner, ctx.remaining_accounts, clock.unix_timestamp)?,
+                GovernanceError::NotAStaker
+            );
+            1
+        } else {
+            get_voter_power(governance, voting_session.owner, ctx.remaining_accounts, clock.unix_timestamp)?
+                .min(voting_session.max_power)
+        };
+        
+        require!(voter_power > 0, GovernanceError::NoVotingPower);
+        
+        record_vote(
+            proposal,
+            &mut ctx.accounts.voter_vote,
+            &mut ctx.accounts.voter_record,
+            governance.key(),
+            voting_session.owner,
+            vote,
+            voter_power,
+            *ctx.bumps.get("voter_record").unwrap(),
+            clock.unix_timestamp,
+            false,
+        )?;
+        
+        emit!(SessionVoteCastEvent {
+            proposal: proposal.key(),
+            owner: voting_session.owner,
+            session_key: ctx.accounts.session_key.key(),
+            vote,
+            voting_power: voter_power,
+        });
+        
+        Ok(())
+    }
+
+    // Cast a vote on a snapshot proposal by proving the voter's balance in the snapshot
+    pub fn cast_snapshot_vote(
+        ctx: Context<CastSnapshotVote>,
+        vote: Vote,
+        balance: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        let governance = &ctx.accounts.governance;
+        let proposal = &mut ctx.accounts.proposal;
+        let voter = &ctx.accounts.voter;
+        let clock = Clock::get()?;
+        
+        verify_voting_open(proposal, clock.unix_timestamp)?;
+        
+        // Verify the voter's balance is part of the snapshot
+        let leaf = keccak::hashv(&[voter.key().as_ref(), &balance.to_le_bytes()]).0;
         require!(
-            clock.unix_timestamp >= proposal.voting_ends_at + governance.execution_delay,
-            GovernanceError::ExecutionDelayNotPassed
+            verify_merkle_proof(&proof, ctx.accounts.snapshot.merkle_root, leaf),
+            GovernanceError::InvalidMerkleProof
         );
         
-        // Verify proposal passed
-        let total_votes = proposal.yes_votes + proposal.no_votes;
-        let voting_power_registry = &ctx.accounts.voting_power_registry;
+        require!(balance > 0, GovernanceError::NoVotingPower);
         
-        // Check quorum
-        let quorum_threshold = (voting_power_registry.total_voting_power as u128)
-            .checked_mul(governance.quorum_percentage as u128)
-            .unwrap()
-            .checked_div(100)
-            .unwrap() as u64;
+        record_vote(
+            proposal,
+            &mut ctx.accounts.voter_vote,
+            &mut ctx.accounts.voter_record,
+            governance.key(),
+            voter.key(),
+            vote,
+            balance,
+            *ctx.bumps.get("voter_record").unwrap(),
+            clock.unix_timestamp,
+            true,
+        )?;
+        
+        emit!(VoteCastEvent {
+            proposal: proposal.key(),
+            voter: voter.key(),
+            vote,
+            voting_power: balance,
+        });
         
+        Ok(())
+    }
+
+    // Cast a one-member-one-vote council vote by holding an NFT from the council collection
+    pub fn cast_council_vote(
+        ctx: Context<CastCouncilVote>,
+        vote: Vote,
+    ) -> Result<()> {
+        let governance = &ctx.accounts.governance;
+        let proposal = &mut ctx.accounts.proposal;
+        let voter = &ctx.accounts.voter;
+        let council_vote = &mut ctx.accounts.council_vote;
+        let clock = Clock::get()?;
+        
+        verify_voting_open(proposal, clock.unix_timestamp)?;
+        
+        require!(proposal.council_vote, GovernanceError::NotCouncilProposal);
+        
+        // Verify the NFT belongs to the verified council collection
+        let collection = ctx
+            .accounts
+            .nft_metadata
+            .collection
+            .as_ref()
+            .ok_or(GovernanceError::NotCouncilMember)?;
         require!(
-            total_votes >= quorum_threshold,
-            GovernanceError::QuorumNotReached
+            collection.verified && collection.key == governance.council_collection,
+            GovernanceError::NotCouncilMember
         );
         
-        // Check if yes votes are greater than no votes
+        // Each NFT may only back one voter per proposal
         require!(
-            proposal.yes_votes > proposal.no_votes,
-            GovernanceError::ProposalNotPassed
+            council_vote.voter == Pubkey::default() || council_vote.voter == voter.key(),
+            GovernanceError::CouncilNftAlreadyVoted
         );
+        council_vote.proposal = proposal.key();
+        council_vote.nft_mint = ctx.accounts.nft_token_account.mint;
+        council_vote.voter = voter.key();
         
-        // Mark proposal as executed
-        proposal.executed = true;
+        record_vote(
+            proposal,
+            &mut ctx.accounts.voter_vote,
+            &mut ctx.accounts.voter_record,
+            governance.key(),
+            voter.key(),
+            vote,
+            1,
+            *ctx.bumps.get("voter_record").unwrap(),
+            clock.unix_timestamp,
+            true,
+        )?;
+        
+        emit!(VoteCastEvent {
+            proposal: proposal.key(),
+            voter: voter.key(),
+            vote,
+            voting_power: 1,
+        });
+        
+        Ok(())
+    }
+
+    // Execute a passed proposal
+    pub fn execute_proposal(ctx: Context<ExecuteProposal>) -> Result<()> {
+        let clock = Clock::get()?;
+        let governance = &mut ctx.accounts.governance;
+        let proposal = &mut ctx.accounts.proposal;
+        
+        verify_executable(governance, proposal, clock.unix_timestamp)?;
+        
+        // Finalize the proposal first if nobody has yet
+        if proposal.outcome == ProposalOutcome::Pending {
+            let (outcome, quorum_threshold) = tally_outcome(proposal);
+            proposal.outcome = outcome;
+            emit_outcome_event(proposal, quorum_threshold, clock.unix_timestamp);
+            write_proposal_result(
+                &mut ctx.accounts.proposal_result,
+                proposal,
+                quorum_threshold,
+                clock.unix_timestamp,
+                *ctx.bumps.get("proposal_result").unwrap(),
+            );
+            record_finalization(&mut ctx.accounts.governance_stats, proposal, clock.unix_timestamp);
+        }
+        
+        // Proposals with execution items are completed item by item via execute_item
+        if proposal.item_count > 0 {
+            require!(
+                !proposal.execution_started,
+                GovernanceError::ExecutionAlreadyStarted
+            );
+            proposal.execution_started = true;
+        } else {
+            proposal.executed = true;
+            ctx.accounts.proposal_result.executed = true;
+            ctx.accounts.proposal_result.executed_at = clock.unix_timestamp;
+        }
+        
+        // Proposals with a milestone schedule pay out tranche by tranche via release_milestone
+        if proposal.milestone_schedule != Pubkey::default() {
+            let milestone_schedule = ctx
+                .accounts
+                .milestone_schedule
+                .as_mut()
+                .ok_or(GovernanceError::MilestoneScheduleRequired)?;
+            milestone_schedule.activated = true;
+            
+            emit!(MilestoneScheduleActivatedEvent {
+                proposal: proposal.key(),
+                milestone_schedule: milestone_schedule.key(),
+                activated_at: clock.unix_timestamp,
+            });
+        }
+        
+        // Pay a treasury withdrawal out at once, unless execution items or a milestone
+        // schedule pay it out instead
+        if proposal.item_count == 0 && proposal.milestone_schedule == Pubkey::default() {
+            if let ProposalPayload::TreasuryWithdrawal { recipient, amount } = proposal.payload {
+                let recipient_token_account = ctx
+                    .accounts
+                    .recipient_token_account
+                    .as_ref()
+                    .ok_or(GovernanceError::RecipientAccountRequired)?;
+                require!(
+                    recipient_token_account.key() == recipient,
+                    GovernanceError::RecipientAccountRequired
+                );
+                
+                pay_from_treasury(
+                    governance,
+                    &ctx.accounts.treasury,
+                    recipient_token_account.to_account_info(),
+                    ctx.accounts.token_program.to_account_info(),
+                    amount,
+                    clock.unix_timestamp,
+                )?;
+                ctx.accounts.treasury.reload()?;
+            }
+        }
+        
+        // Pay the executor bounty from the treasury, capped at what the treasury holds and
+        // what the treasury cap still allows
+        let bounty = governance
+            .execution_bounty
+            .min(ctx.accounts.treasury.amount)
+            .min(treasury_allowance_remaining(governance, clock.unix_timestamp));
+        if bounty > 0 {
+            pay_from_treasury(
+                governance,
+                &ctx.accounts.treasury,
+                ctx.accounts.executor_token_account.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+                bounty,
+                clock.unix_timestamp,
+            )?;
+            
+            emit!(ExecutionBountyPaidEvent {
+                proposal: proposal.key(),
+                executor: ctx.accounts.executor.key(),
+                amount: bounty,
+            });
+        }
+        
+        if proposal.item_count > 0 {
+            emit!(ProposalExecutionStartedEvent {
+                proposal: proposal.key(),
+                executed_by: ctx.accounts.executor.key(),
+                item_count: proposal.item_count,
+            });
+            return Ok(());
+        }
         
         // Execute proposal based on type
         match proposal.proposal_type {
             ProposalType::TreasuryWithdrawal => {
-                // Handle treasury withdrawal
-                // This would typically transfer tokens from treasury to recipient
-                // For simplicity, we'll just emit an event
+                // The withdrawal was paid out above, or is paid by its milestone schedule
                 emit!(ProposalExecutedEvent {
                     proposal: proposal.key(),
                     executed_by: ctx.accounts.executor.key(),
@@ -297,305 +788,3639 @@ pub mod wct_governance {
         Ok(())
     }
 
-    // Cancel a proposal (only by the proposer or governance authority)
-    pub fn cancel_proposal(ctx: Context<CancelProposal>) -> Result<()> {
+    // Dry-run execute_proposal and the proposal's execution items without side effects.
+    // Returns 0 if execution would succeed, otherwise the error code of the first failed check:
+    // a custom code as is, or a builtin ProgramError in its u64 encoding (its index shifted left
+    // 32 bits), so builtin failures never read as success.
+    // remaining_accounts: the proposal's execution items, in order
+    pub fn simulate_execution(ctx: Context<SimulateExecution>) -> Result<u64> {
+        let governance = &ctx.accounts.governance;
+        let proposal = &ctx.accounts.proposal;
+        let clock = Clock::get()?;
+        
+        let result = verify_executable(governance, proposal, clock.unix_timestamp).and_then(|_| {
+            // A missing milestone schedule would make execute_proposal fail
+            require!(
+                proposal.milestone_schedule == Pubkey::default()
+                    || ctx.accounts.milestone_schedule.is_some(),
+                GovernanceError::MilestoneScheduleRequired
+            );
+            
+            // Check the treasury cap against a copy so nothing is consumed. Outflows from
+            // execution items are only known once they run.
+            if proposal.item_count == 0 && proposal.milestone_schedule == Pubkey::default() {
+                if let ProposalPayload::TreasuryWithdrawal { amount, .. } = proposal.payload {
+                    let mut governance_copy: Governance = (**governance).clone();
+                    consume_treasury_allowance(&mut governance_copy, amount, clock.unix_timestamp)?;
+                }
+            }
+            
+            require!(
+                !proposal.execution_started,
+                GovernanceError::ExecutionAlreadyStarted
+            );
+            require!(
+                ctx.remaining_accounts.len() == proposal.item_count as usize,
+                GovernanceError::ExecutionItemsIncomplete
+            );
+            
+            for (index, account_info) in ctx.remaining_accounts.iter().enumerate() {
+                let execution_item = Account::<ExecutionItem>::try_from(account_info)?;
+                
+                require!(
+                    execution_item.proposal == proposal.key() && execution_item.index as usize == index,
+                    GovernanceError::InvalidExecutionItemIndex
+                );
+                
+                let instruction = ExecutionInstruction::try_from_slice(&execution_item.payload)
+                    .map_err(|_| GovernanceError::InvalidExecutionPayload)?;
+                
+                let program_allowlist = ctx
+                    .accounts
+                    .program_allowlist
+                    .as_ref()
+                    .ok_or(GovernanceError::ProgramNotAllowed)?;
+                require!(
+                    program_allowlist.programs.contains(&instruction.program_id),
+                    GovernanceError::ProgramNotAllowed
+                );
+            }
+            
+            Ok(())
+        });
+        
+        Ok(match result {
+            Ok(()) => 0,
+            Err(Error::AnchorError(error)) => error.error_code_number as u64,
+            Err(Error::ProgramError(error)) => u64::from(error.program_error),
+        })
+    }
+
+    // Record a proposal's outcome once voting has closed, or mark it expired
+    // once its execution window has lapsed (permissionless)
+    pub fn finalize_proposal(ctx: Context<FinalizeProposal>) -> Result<()> {
+        let governance = &ctx.accounts.governance;
         let proposal = &mut ctx.accounts.proposal;
-        let authority = &ctx.accounts.authority;
         let clock = Clock::get()?;
         
-        // Verify proposal has not been executed
+        // Verify voting is closed
         require!(
-            !proposal.executed,
-            GovernanceError::ProposalAlreadyExecuted
+            clock.unix_timestamp >= proposal.voting_ends_at,
+            GovernanceError::VotingStillOpen
         );
         
-        // Verify proposal has not been cancelled
+        match proposal.outcome {
+            ProposalOutcome::Pending => {
+                let (outcome, quorum_threshold) = tally_outcome(proposal);
+                proposal.outcome = outcome;
+                emit_outcome_event(proposal, quorum_threshold, clock.unix_timestamp);
+                write_proposal_result(
+                    &mut ctx.accounts.proposal_result,
+                    proposal,
+                    quorum_threshold,
+                    clock.unix_timestamp,
+                    *ctx.bumps.get("proposal_result").unwrap(),
+                );
+                record_finalization(&mut ctx.accounts.governance_stats, proposal, clock.unix_timestamp);
+            }
+            ProposalOutcome::Succeeded => {
+                // Passed proposals that were never executed expire after the execution window
+                require!(
+                    !proposal.execution_started
+                        && is_execution_window_expired(governance, proposal, clock.unix_timestamp),
+                    GovernanceError::ProposalAlreadyFinalized
+                );
+                proposal.outcome = ProposalOutcome::Expired;
+                ctx.accounts.proposal_result.outcome = ProposalOutcome::Expired;
+                
+                emit!(ProposalExpiredEvent {
+                    proposal: proposal.key(),
+                    expired_at: clock.unix_timestamp,
+                });
+            }
+            _ => return err!(GovernanceError::ProposalAlreadyFinalized),
+        }
+        
+        Ok(())
+    }
+
+    // Sweep proposals whose execution window has lapsed (permissionless crank). Passed proposals
+    // become Expired; proposals nobody finalized are tallied first, the cranker paying for their
+    // result account, and expire if they passed.
+    // remaining_accounts: (proposal, proposal_result) pairs; ineligible pairs are skipped.
+    pub fn sweep_expired_proposals<'info>(
+        ctx: Context<'_, '_, '_, 'info, SweepExpiredProposals<'info>>,
+    ) -> Result<()> {
+        let governance = &ctx.accounts.governance;
+        let clock = Clock::get()?;
+        
         require!(
-            !proposal.cancelled,
-            GovernanceError::ProposalCancelled
+            ctx.remaining_accounts.len() % 2 == 0,
+            GovernanceError::InvalidSweepAccounts
         );
         
-        // Verify cancellation is authorized
+        let mut expired_count: u32 = 0;
+        let mut finalized_count: u32 = 0;
+        for pair in ctx.remaining_accounts.chunks(2) {
+            let mut proposal = Account::<Proposal>::try_from(&pair[0])?;
+            
+            require!(
+                proposal.governance == governance.key(),
+                GovernanceError::InvalidSweepAccounts
+            );
+            
+            if proposal.cancelled
+                || proposal.executed
+                || proposal.execution_started
+                || !is_execution_window_expired(governance, &proposal, clock.unix_timestamp)
+            {
+                continue;
+            }
+            
+            let mut proposal_result = match proposal.outcome {
+                ProposalOutcome::Succeeded => {
+                    let proposal_result = Account::<ProposalResult>::try_from(&pair[1])?;
+                    require!(
+                        proposal_result.proposal == proposal.key(),
+                        GovernanceError::InvalidSweepAccounts
+                    );
+                    proposal_result
+                }
+                ProposalOutcome::Pending => {
+                    // Never finalized, so its result account does not exist yet
+                    let proposal_key = proposal.key();
+                    let (result_key, result_bump) = Pubkey::find_program_address(
+                        &[b"proposal_result".as_ref(), proposal_key.as_ref()],
+                        ctx.program_id,
+                    );
+                    require!(
+                        pair[1].key() == result_key && pair[1].data_is_empty(),
+                        GovernanceError::InvalidSweepAccounts
+                    );
+                    
+                    let space = 8 + ProposalResult::LEN;
+                    system_program::create_account(
+                        CpiContext::new_with_signer(
+                            ctx.accounts.system_program.to_account_info(),
+                            system_program::CreateAccount {
+                                from: ctx.accounts.cranker.to_account_info(),
+                                to: pair[1].clone(),
+                            },
+                            &[&[b"proposal_result".as_ref(), proposal_key.as_ref(), &[result_bump]]],
+                        ),
+                        Rent::get()?.minimum_balance(space),
+                        space as u64,
+                        ctx.program_id,
+                    )?;
+                    
+                    let (outcome, quorum_threshold) = tally_outcome(&proposal);
+                    proposal.outcome = outcome;
+                    emit_outcome_event(&proposal, quorum_threshold, clock.unix_timestamp);
+                    record_finalization(&mut ctx.accounts.governance_stats, &proposal, clock.unix_timestamp);
+                    
+                    let mut proposal_result = Account::<ProposalResult>::try_from_unchecked(&pair[1])?;
+                    write_proposal_result(
+                        &mut proposal_result,
+                        &proposal,
+                        quorum_threshold,
+                        clock.unix_timestamp,
+                        result_bump,
+                    );
+                    finalized_count += 1;
+                    proposal_result
+                }
+                _ => continue,
+            };
+            
+            // A passed proposal can no longer be executed
+            if proposal.outcome == ProposalOutcome::Succeeded {
+                proposal.outcome = ProposalOutcome::Expired;
+                proposal_result.outcome = ProposalOutcome::Expired;
+                
+                emit!(ProposalExpiredEvent {
+                    proposal: proposal.key(),
+                    expired_at: clock.unix_timestamp,
+                });
+                
+                expired_count += 1;
+            }
+            
+            proposal.exit(ctx.program_id)?;
+            proposal_result.exit(ctx.program_id)?;
+        }
+        
+        emit!(ProposalsSweptEvent {
+            governance: governance.key(),
+            cranker: ctx.accounts.cranker.key(),
+            expired_count,
+            finalized_count,
+        });
+        
+        Ok(())
+    }
+
+    // Credit a voter's soulbound participation badge once the proposal they voted on has been
+    // finalized and until the claim window closes (permissionless, payer funds the badge on
+    // first claim)
+    pub fn claim_participation_badge(ctx: Context<ClaimParticipationBadge>) -> Result<()> {
+        let proposal = &ctx.accounts.proposal;
+        let voter_vote = &mut ctx.accounts.voter_vote;
+        let badge = &mut ctx.accounts.participation_badge;
+        let clock = Clock::get()?;
+        
+        // Verify the proposal has been finalized
         require!(
-            authority.key() == proposal.proposer || authority.key() == ctx.accounts.governance.authority,
-            GovernanceError::UnauthorizedCancellation
+            proposal.outcome != ProposalOutcome::Pending,
+            GovernanceError::ProposalNotFinalized
         );
         
-        // Mark proposal as cancelled
-        proposal.cancelled = true;
+        // After the claim window the vote account may be closed, so the badge is no longer owed
+        require!(
+            clock.unix_timestamp < proposal.voting_ends_at + BADGE_CLAIM_WINDOW,
+            GovernanceError::BadgeClaimWindowClosed
+        );
         
-        emit!(ProposalCancelledEvent {
+        require!(!voter_vote.badge_claimed, GovernanceError::BadgeAlreadyClaimed);
+        voter_vote.badge_claimed = true;
+        
+        badge.governance = proposal.governance;
+        badge.voter = voter_vote.voter;
+        badge.proposals_participated = badge.proposals_participated.checked_add(1).unwrap();
+        badge.last_proposal = proposal.key();
+        badge.updated_at = clock.unix_timestamp;
+        badge.bump = *ctx.bumps.get("participation_badge").unwrap();
+        
+        emit!(ParticipationBadgeCreditedEvent {
+            governance: badge.governance,
+            voter: badge.voter,
             proposal: proposal.key(),
-            cancelled_by: authority.key(),
-            cancellation_time: clock.unix_timestamp,
+            proposals_participated: badge.proposals_participated,
         });
         
         Ok(())
     }
 
-    // Update governance parameters (only by governance authority)
-    pub fn update_governance(
-        ctx: Context<UpdateGovernance>,
-        min_proposal_tokens: Option<u64>,
-        voting_period: Option<i64>,
-        execution_delay: Option<i64>,
-        quorum_percentage: Option<u8>,
+    // Close a vote account once its proposal is settled, the grace period has passed and its
+    // participation badge was claimed or can no longer be (permissionless). The cranker keeps a
+    // small share of the rent and the voter gets the rest.
+    pub fn close_vote_account(ctx: Context<CloseVoteAccount>) -> Result<()> {
+        let proposal = &ctx.accounts.proposal;
+        let clock = Clock::get()?;
+        
+        // Verify the proposal can no longer change
+        let settled = proposal.executed
+            || proposal.cancelled
+            || matches!(
+                proposal.outcome,
+                ProposalOutcome::Defeated
+                    | ProposalOutcome::QuorumFailed
+                    | ProposalOutcome::Expired
+                    | ProposalOutcome::Vetoed
+            );
+        require!(settled, GovernanceError::ProposalNotSettled);
+        
+        // Verify the grace period has passed
+        require!(
+            clock.unix_timestamp >= proposal.voting_ends_at + VOTE_ACCOUNT_GRACE_PERIOD,
+            GovernanceError::GracePeriodNotPassed
+        );
+        
+        // The vote account is the voter's only claim to a participation badge
+        require!(
+            ctx.accounts.voter_vote.badge_claimed
+                || clock.unix_timestamp >= proposal.voting_ends_at + BADGE_CLAIM_WINDOW,
+            GovernanceError::BadgeUnclaimed
+        );
+        
+        // Pay the cranker its share; the rest goes to the voter when the account closes
+        let vote_account = ctx.accounts.voter_vote.to_account_info();
+        let cranker_share = (vote_account.lamports() as u128)
+            .checked_mul(CRANK_REWARD_BPS as u128)
+            .unwrap()
+            .checked_div(10000)
+            .unwrap() as u64;
+        **vote_account.try_borrow_mut_lamports()? -= cranker_share;
+        **ctx.accounts.cranker.to_account_info().try_borrow_mut_lamports()? += cranker_share;
+        
+        emit!(VoteAccountClosedEvent {
+            proposal: proposal.key(),
+            voter: ctx.accounts.voter.key(),
+            cranker: ctx.accounts.cranker.key(),
+            cranker_reward: cranker_share,
+        });
+        
+        Ok(())
+    }
+
+    // Add an ordered execution item to a proposal (only by the proposer, before voting starts)
+    pub fn add_execution_item(
+        ctx: Context<AddExecutionItem>,
+        index: u16,
+        payload: Vec<u8>,
     ) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal;
+        let execution_item = &mut ctx.accounts.execution_item;
+        
+        // Items must be added in order and only up to the declared count
+        require!(
+            index == proposal.items_added && index < proposal.item_count,
+            GovernanceError::InvalidExecutionItemIndex
+        );
+        
+        require!(
+            payload.len() <= ExecutionItem::MAX_PAYLOAD_LEN,
+            GovernanceError::ExecutionItemTooLarge
+        );
+        
+        // Reject payloads that don't decode to an instruction before anyone votes on them
+        ExecutionInstruction::try_from_slice(&payload)
+            .map_err(|_| GovernanceError::InvalidExecutionPayload)?;
+        
+        execution_item.proposal = proposal.key();
+        execution_item.index = index;
+        execution_item.payload = payload;
+        execution_item.executed = false;
+        execution_item.bump = *ctx.bumps.get("execution_item").unwrap();
+        
+        proposal.items_added = proposal.items_added.checked_add(1).unwrap();
+        
+        emit!(ExecutionItemAddedEvent {
+            proposal: proposal.key(),
+            execution_item: execution_item.key(),
+            index,
+        });
+        
+        Ok(())
+    }
+
+    // Execute the next execution item of a proposal whose execution has started
+    pub fn execute_item(ctx: Context<ExecuteItem>) -> Result<()> {
         let governance = &mut ctx.accounts.governance;
+        let proposal = &mut ctx.accounts.proposal;
+        let execution_item = &mut ctx.accounts.execution_item;
+        let clock = Clock::get()?;
         
-        // Update min_proposal_tokens if provided
-        if let Some(new_min_proposal_tokens) = min_proposal_tokens {
-            governance.min_proposal_tokens = new_min_proposal_tokens;
-        }
+        // Verify governance is not paused
+        require!(!governance.paused, GovernanceError::GovernancePaused);
         
-        // Update voting_period if provided
-        if let Some(new_voting_period) = voting_period {
-            require!(new_voting_period > 0, GovernanceError::InvalidVotingPeriod);
-            governance.voting_period = new_voting_period;
-        }
+        // Verify execute_proposal has approved execution
+        require!(
+            proposal.execution_started,
+            GovernanceError::ExecutionNotStarted
+        );
         
-        // Update execution_delay if provided
-        if let Some(new_execution_delay) = execution_delay {
-            require!(new_execution_delay >= 0, GovernanceError::InvalidExecutionDelay);
-            governance.execution_delay = new_execution_delay;
-        }
+        // Verify items are executed in order
+        require!(
+            execution_item.index == proposal.items_executed,
+            GovernanceError::InvalidExecutionItemIndex
+        );
         
-        // Update quorum_percentage if provided
-        if let Some(new_quorum_percentage) = quorum_percentage {
-            require!(
-                new_quorum_percentage > 0 && new_quorum_percentage <= 100,
-                GovernanceError::InvalidQuorumPercentage
-            );
-            governance.quorum_percentage = new_quorum_percentage;
+        require!(
+            !execution_item.executed,
+            GovernanceError::ExecutionItemAlreadyExecuted
+        );
+        
+        // Decode the item's instruction and verify its target program is allowlisted
+        let instruction = ExecutionInstruction::try_from_slice(&execution_item.payload)
+            .map_err(|_| GovernanceError::InvalidExecutionPayload)?;
+        
+        require!(
+            ctx.accounts.program_allowlist.programs.contains(&instruction.program_id),
+            GovernanceError::ProgramNotAllowed
+        );
+        
+        // Invoke the instruction with the governance PDA as signer
+        let ix = Instruction {
+            program_id: instruction.program_id,
+            accounts: instruction
+                .accounts
+                .iter()
+                .map(|meta| AccountMeta {
+                    pubkey: meta.pubkey,
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+            data: instruction.data,
+        };
+        
+        let governance_seeds = &[
+            b"governance".as_ref(),
+            governance.token_mint.as_ref(),
+            &[governance.bump],
+        ];
+        
+        let treasury_balance = ctx.accounts.treasury.amount;
+        invoke_signed(&ix, ctx.remaining_accounts, &[governance_seeds])?;
+        
+        // Whatever the item moved out of the treasury counts against the treasury cap
+        ctx.accounts.treasury.reload()?;
+        let treasury_outflow = treasury_balance.saturating_sub(ctx.accounts.treasury.amount);
+        consume_treasury_allowance(governance, treasury_outflow, clock.unix_timestamp)?;
+        
+        execution_item.executed = true;
+        proposal.items_executed = proposal.items_executed.checked_add(1).unwrap();
+        
+        emit!(ExecutionItemExecutedEvent {
+            proposal: proposal.key(),
+            execution_item: execution_item.key(),
+            index: execution_item.index,
+            executed_by: ctx.accounts.executor.key(),
+        });
+        
+        // The proposal is only executed once every item has completed
+        if proposal.items_executed == proposal.item_count {
+            proposal.executed = true;
+            ctx.accounts.proposal_result.executed = true;
+            ctx.accounts.proposal_result.executed_at = clock.unix_timestamp;
+            
+            emit!(ProposalExecutedEvent {
+                proposal: proposal.key(),
+                executed_by: ctx.accounts.executor.key(),
+                execution_time: clock.unix_timestamp,
+                proposal_type: proposal.proposal_type,
+            });
         }
         
-        emit!(GovernanceUpdatedEvent {
-            governance: governance.key(),
-            min_proposal_tokens: governance.min_proposal_tokens,
-            voting_period: governance.voting_period,
-            execution_delay: governance.execution_delay,
-            quorum_percentage: governance.quorum_percentage,
+        Ok(())
+    }
+
+    // Split a treasury withdrawal into tranches that are released one at a time after execution.
+    // The schedule must be attached before anyone votes so voters see the full payout plan.
+    pub fn create_milestone_schedule(
+        ctx: Context<CreateMilestoneSchedule>,
+        tranche_amounts: Vec<u64>,
+    ) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal;
+        let milestone_schedule = &mut ctx.accounts.milestone_schedule;
+        let clock = Clock::get()?;
+        
+        require!(
+            proposal.proposal_type == ProposalType::TreasuryWithdrawal,
+            GovernanceError::NotTreasuryWithdrawal
+        );
+        
+        // Tranches must go to the recipient voters approve in the payload
+        let payload_recipient = match proposal.payload {
+            ProposalPayload::TreasuryWithdrawal { recipient, .. } => recipient,
+            _ => return err!(GovernanceError::NotTreasuryWithdrawal),
+        };
+        require!(
+            ctx.accounts.recipient_token_account.key() == payload_recipient,
+            GovernanceError::RecipientAccountRequired
+        );
+        
+        // Verify nobody has voted on the proposal yet
+        require!(
+            clock.unix_timestamp < proposal.voting_ends_at && proposal.voter_count == 0,
+            GovernanceError::ProposalAlreadyVotedOn
+        );
+        
+        require!(
+            !tranche_amounts.is_empty() && tranche_amounts.len() <= MilestoneSchedule::MAX_TRANCHES,
+            GovernanceError::InvalidTrancheCount
+        );
+        
+        // Tranches must add up to exactly the amount voters are asked to approve
+        let total_amount = tranche_amounts
+            .iter()
+            .try_fold(0u64, |total, amount| total.checked_add(*amount))
+            .ok_or(GovernanceError::TrancheAmountMismatch)?;
+        require!(
+            total_amount == proposal.requested_amount,
+            GovernanceError::TrancheAmountMismatch
+        );
+        
+        milestone_schedule.proposal = proposal.key();
+        milestone_schedule.recipient = ctx.accounts.recipient_token_account.key();
+        milestone_schedule.tranche_amounts = tranche_amounts;
+        milestone_schedule.released_count = 0;
+        milestone_schedule.released_amount = 0;
+        milestone_schedule.activated = false;
+        milestone_schedule.bump = *ctx.bumps.get("milestone_schedule").unwrap();
+        
+        proposal.milestone_schedule = milestone_schedule.key();
+        
+        emit!(MilestoneScheduleCreatedEvent {
+            proposal: proposal.key(),
+            milestone_schedule: milestone_schedule.key(),
+            recipient: milestone_schedule.recipient,
+            tranche_count: milestone_schedule.tranche_amounts.len() as u8,
+            total_amount,
         });
         
         Ok(())
     }
 
-    // Register voting power (called by staking program)
-    pub fn register_voting_power(
-        ctx: Context<RegisterVotingPower>,
-        voter: Pubkey,
-        voting_power: u64,
+    // Release the next tranche of an executed milestone schedule (guardian or governance sign-off)
+    pub fn release_milestone(ctx: Context<ReleaseMilestone>) -> Result<()> {
+        let governance = &mut ctx.accounts.governance;
+        let milestone_schedule = &mut ctx.accounts.milestone_schedule;
+        let clock = Clock::get()?;
+        
+        // Verify governance is not paused
+        require!(!governance.paused, GovernanceError::GovernancePaused);
+        
+        // Verify the proposal has been executed
+        require!(milestone_schedule.activated, GovernanceError::MilestoneScheduleNotActive);
+        
+        let index = milestone_schedule.released_count as usize;
+        require!(
+            index < milestone_schedule.tranche_amounts.len(),
+            GovernanceError::AllMilestonesReleased
+        );
+        let amount = milestone_schedule.tranche_amounts[index];
+        
+        pay_from_treasury(
+            governance,
+            &ctx.accounts.treasury,
+            ctx.accounts.recipient_token_account.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            amount,
+            clock.unix_timestamp,
+        )?;
+        
+        milestone_schedule.released_count = milestone_schedule.released_count.checked_add(1).unwrap();
+        milestone_schedule.released_amount = milestone_schedule.released_amount.checked_add(amount).unwrap();
+        
+        emit!(MilestoneReleasedEvent {
+            proposal: milestone_schedule.proposal,
+            milestone_schedule: milestone_schedule.key(),
+            index: index as u8,
+            amount,
+            approved_by: ctx.accounts.approver.key(),
+            released_at: clock.unix_timestamp,
+        });
+        
+        Ok(())
+    }
+
+    // Configure the Wormhole core bridge used to relay cross-chain messages (only by governance authority)
+    pub fn configure_cross_chain(
+        ctx: Context<ConfigureCrossChain>,
+        wormhole_program: Pubkey,
+        consistency_level: u8,
     ) -> Result<()> {
-        let voting_power_registry = &mut ctx.accounts.voting_power_registry;
-        let voter_power = &mut ctx.accounts.voter_power;
+        let cross_chain_config = &mut ctx.accounts.cross_chain_config;
         
-        // If this is a new voter, initialize their power
-        if voter_power.data_is_empty() {
-            voter_power.voter = voter;
-            voter_power.voting_power = voting_power;
-            voting_power_registry.total_voting_power = voting_power_registry.total_voting_power.checked_add(voting_power).unwrap();
-        } else {
-            // Update existing voter's power
-            let old_power = voter_power.voting_power;
-            voter_power.voting_power = voting_power;
-            
-            // Update total voting power
-            voting_power_registry.total_voting_power = voting_power_registry
-                .total_voting_power
-                .checked_sub(old_power)
-                .unwrap()
-                .checked_add(voting_power)
-                .unwrap();
+        cross_chain_config.governance = ctx.accounts.governance.key();
+        cross_chain_config.wormhole_program = wormhole_program;
+        cross_chain_config.consistency_level = consistency_level;
+        cross_chain_config.bump = *ctx.bumps.get("cross_chain_config").unwrap();
+        
+        emit!(CrossChainConfiguredEvent {
+            governance: cross_chain_config.governance,
+            wormhole_program,
+            consistency_level,
+        });
+        
+        Ok(())
+    }
+
+    // Attach a message for another chain to a proposal; it is relayed once the proposal executes.
+    // Like milestone schedules, it must be attached before anyone votes.
+    pub fn attach_cross_chain_message(
+        ctx: Context<AttachCrossChainMessage>,
+        target_chain: u16,
+        target_address: [u8; 32],
+        payload: Vec<u8>,
+    ) -> Result<()> {
+        let proposal = &ctx.accounts.proposal;
+        let cross_chain_message = &mut ctx.accounts.cross_chain_message;
+        let clock = Clock::get()?;
+        
+        // Verify nobody has voted on the proposal yet
+        require!(
+            clock.unix_timestamp < proposal.voting_ends_at && proposal.voter_count == 0,
+            GovernanceError::ProposalAlreadyVotedOn
+        );
+        
+        require!(
+            payload.len() <= CrossChainMessage::MAX_PAYLOAD_LEN,
+            GovernanceError::CrossChainPayloadTooLarge
+        );
+        
+        cross_chain_message.proposal = proposal.key();
+        cross_chain_message.target_chain = target_chain;
+        cross_chain_message.target_address = target_address;
+        cross_chain_message.payload = payload;
+        cross_chain_message.relayed = false;
+        cross_chain_message.sequence = 0;
+        cross_chain_message.bump = *ctx.bumps.get("cross_chain_message").unwrap();
+        
+        emit!(CrossChainMessageAttachedEvent {
+            proposal: proposal.key(),
+            cross_chain_message: cross_chain_message.key(),
+            target_chain,
+            target_address,
+        });
+        
+        Ok(())
+    }
+
+    // Post an executed proposal's cross-chain message through the Wormhole core bridge, with the
+    // governance PDA as emitter (permissionless, payer covers the bridge fee and message rent)
+    pub fn relay_cross_chain_message(ctx: Context<RelayCrossChainMessage>) -> Result<()> {
+        let governance = &ctx.accounts.governance;
+        let proposal = &ctx.accounts.proposal;
+        let cross_chain_message = &mut ctx.accounts.cross_chain_message;
+        let chain_sequence = &mut ctx.accounts.chain_sequence;
+        
+        // Verify governance is not paused
+        require!(!governance.paused, GovernanceError::GovernancePaused);
+        
+        // Only executed, approved proposals may speak for the DAO on other chains
+        require!(
+            proposal.executed && proposal.outcome == ProposalOutcome::Succeeded,
+            GovernanceError::ProposalNotApproved
+        );
+        
+        // Each message is relayed exactly once
+        require!(!cross_chain_message.relayed, GovernanceError::CrossChainMessageAlreadyRelayed);
+        
+        // Assign the next sequence for the target chain so receivers can reject replays and gaps
+        chain_sequence.governance = governance.key();
+        chain_sequence.target_chain = cross_chain_message.target_chain;
+        chain_sequence.bump = *ctx.bumps.get("chain_sequence").unwrap();
+        let sequence = chain_sequence.next_sequence;
+        chain_sequence.next_sequence = sequence.checked_add(1).unwrap();
+        
+        // Pay the bridge fee, stored after the guardian set index, last lamports and
+        // guardian set expiration time in the bridge config
+        let bridge_data = ctx.accounts.wormhole_bridge.try_borrow_data()?;
+        require!(bridge_data.len() >= 24, GovernanceError::InvalidWormholeAccounts);
+        let fee = u64::from_le_bytes(bridge_data[16..24].try_into().unwrap());
+        drop(bridge_data);
+        if fee > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.payer.to_account_info(),
+                        to: ctx.accounts.wormhole_fee_collector.to_account_info(),
+                    },
+                ),
+                fee,
+            )?;
         }
         
-        emit!(VotingPowerUpdatedEvent {
-            voter,
-            old_voting_power: voter_power.voting_power,
-            new_voting_power: voting_power,
-            total_voting_power: voting_power_registry.total_voting_power,
+        // Envelope: version, target chain and sequence (big-endian for EVM receivers),
+        // target address, proposal, then the message payload
+        let mut envelope = Vec::with_capacity(1 + 2 + 8 + 32 + 32 + cross_chain_message.payload.len());
+        envelope.push(CrossChainMessage::ENVELOPE_VERSION);
+        envelope.extend_from_slice(&cross_chain_message.target_chain.to_be_bytes());
+        envelope.extend_from_slice(&sequence.to_be_bytes());
+        envelope.extend_from_slice(&cross_chain_message.target_address);
+        envelope.extend_from_slice(proposal.key().as_ref());
+        envelope.extend_from_slice(&cross_chain_message.payload);
+        
+        // Wormhole core bridge PostMessage: instruction index, nonce, payload, consistency level
+        let mut data = vec![WORMHOLE_POST_MESSAGE_INSTRUCTION];
+        data.extend_from_slice(&(sequence as u32).to_le_bytes());
+        data.extend_from_slice(&(envelope.len() as u32).to_le_bytes());
+        data.extend_from_slice(&envelope);
+        data.push(ctx.accounts.cross_chain_config.consistency_level);
+        
+        let ix = Instruction {
+            program_id: ctx.accounts.wormhole_program.key(),
+            accounts: vec![
+                AccountMeta::new(ctx.accounts.wormhole_bridge.key(), false),
+                AccountMeta::new(ctx.accounts.wormhole_message.key(), true),
+                AccountMeta::new_readonly(governance.key(), true),
+                AccountMeta::new(ctx.accounts.wormhole_sequence.key(), false),
+                AccountMeta::new(ctx.accounts.payer.key(), true),
+                AccountMeta::new(ctx.accounts.wormhole_fee_collector.key(), false),
+                AccountMeta::new_readonly(ctx.accounts.clock.key(), false),
+                AccountMeta::new_readonly(ctx.accounts.system_program.key(), false),
+                AccountMeta::new_readonly(ctx.accounts.rent.key(), false),
+            ],
+            data,
+        };
+        
+        let governance_seeds = &[
+            b"governance".as_ref(),
+            governance.token_mint.as_ref(),
+            &[governance.bump],
+        ];
+        
+        invoke_signed(
+            &ix,
+            &[
+                ctx.accounts.wormhole_bridge.to_account_info(),
+                ctx.accounts.wormhole_message.to_account_info(),
+                governance.to_account_info(),
+                ctx.accounts.wormhole_sequence.to_account_info(),
+                ctx.accounts.payer.to_account_info(),
+                ctx.accounts.wormhole_fee_collector.to_account_info(),
+                ctx.accounts.clock.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+                ctx.accounts.rent.to_account_info(),
+            ],
+            &[governance_seeds],
+        )?;
+        
+        cross_chain_message.relayed = true;
+        cross_chain_message.sequence = sequence;
+        
+        emit!(CrossChainMessageRelayedEvent {
+            proposal: proposal.key(),
+            cross_chain_message: cross_chain_message.key(),
+            target_chain: cross_chain_message.target_chain,
+            sequence,
+            wormhole_message: ctx.accounts.wormhole_message.key(),
         });
         
         Ok(())
     }
-}
 
-// Helper function to get voter's voting power
-fn get_voter_power(
-    voting_power_registry: &Account<VotingPowerRegistry>,
-    voter: Pubkey,
-) -> Result<u64> {
-    // In a real implementation, this would query the voter's voting power
-    // from the voting power registry
-    // For simplicity, we're returning a fixed value
-    Ok(10)
-}
+    // Create the allowlist of programs that execution items may target (only by governance authority)
+    pub fn initialize_program_allowlist(
+        ctx: Context<InitializeProgramAllowlist>,
+        programs: Vec<Pubkey>,
+    ) -> Result<()> {
+        let program_allowlist = &mut ctx.accounts.program_allowlist;
+        
+        require!(
+            programs.len() <= ProgramAllowlist::MAX_PROGRAMS,
+            GovernanceError::ProgramAllowlistFull
+        );
+        
+        program_allowlist.governance = ctx.accounts.governance.key();
+        program_allowlist.programs = programs;
+        program_allowlist.bump = *ctx.bumps.get("program_allowlist").unwrap();
+        
+        emit!(ProgramAllowlistInitializedEvent {
+            governance: program_allowlist.governance,
+            programs: program_allowlist.programs.clone(),
+        });
+        
+        Ok(())
+    }
 
-#[derive(Accounts)]
-pub struct Initialize<'info> {
+    // Add or remove an allowlisted program (only by the governance PDA, i.e. through an executed proposal)
+    pub fn set_program_allowed(
+        ctx: Context<SetProgramAllowed>,
+        program_id: Pubkey,
+        allowed: bool,
+    ) -> Result<()> {
+        let program_allowlist = &mut ctx.accounts.program_allowlist;
+        let is_listed = program_allowlist.programs.contains(&program_id);
+        
+        if allowed && !is_listed {
+            require!(
+                program_allowlist.programs.len() < ProgramAllowlist::MAX_PROGRAMS,
+                GovernanceError::ProgramAllowlistFull
+            );
+            program_allowlist.programs.push(program_id);
+        } else if !allowed && is_listed {
+            program_allowlist.programs.retain(|program| *program != program_id);
+        }
+        
+        emit!(ProgramAllowlistUpdatedEvent {
+            governance: program_allowlist.governance,
+            program_id,
+            allowed,
+        });
+        
+        Ok(())
+    }
+
+    // Veto a proposal queued behind its execution delay (only by the guardian, or by the
+    // governance PDA when a veto proposal is executed)
+    pub fn veto_proposal(ctx: Context<VetoProposal>) -> Result<()> {
+        apply_veto(
+            &ctx.accounts.governance,
+            &mut ctx.accounts.proposal,
+            ctx.accounts.proposal_result.as_mut(),
+            ctx.accounts.vetoer.key(),
+        )
+    }
+
+    // Record that a governance realm is a sub-DAO of a parent realm (requires both authorities)
+    pub fn register_sub_dao(ctx: Context<RegisterSubDao>) -> Result<()> {
+        let realm_hierarchy = &mut ctx.accounts.realm_hierarchy;
+        
+        require!(
+            ctx.accounts.parent_governance.key() != ctx.accounts.governance.key(),
+            GovernanceError::InvalidParentRealm
+        );
+        
+        realm_hierarchy.governance = ctx.accounts.governance.key();
+        realm_hierarchy.parent_governance = ctx.accounts.parent_governance.key();
+        realm_hierarchy.bump = *ctx.bumps.get("realm_hierarchy").unwrap();
+        
+        emit!(SubDaoRegisteredEvent {
+            governance: realm_hierarchy.governance,
+            parent_governance: realm_hierarchy.parent_governance,
+        });
+        
+        Ok(())
+    }
+
+    // Veto a sub-DAO proposal during its execution delay, signed by the parent governance PDA
+    // when a parent proposal is executed
+    pub fn parent_veto_proposal(ctx: Context<ParentVetoProposal>) -> Result<()> {
+        apply_veto(
+            &ctx.accounts.governance,
+            &mut ctx.accounts.proposal,
+            ctx.accounts.proposal_result.as_mut(),
+            ctx.accounts.parent_governance.key(),
+        )
+    }
+
+    // Assert that a proposal of the given type was executed and approved exactly this action.
+    // Dependent programs CPI into this before performing privileged actions and are
+    // responsible for preventing the same approval from being used twice.
+    pub fn assert_proposal_approved(
+        ctx: Context<AssertProposalApproved>,
+        proposal_type: ProposalType,
+        action_hash: [u8; 32],
+    ) -> Result<()> {
+        require_proposal_approved(&ctx.accounts.proposal, proposal_type, action_hash)
+    }
+
+    // Return how many of the most recent `window` proposals the voter voted on. Other programs,
+    // such as staking, CPI into this and read the count from return data.
+    pub fn get_participation(ctx: Context<GetParticipation>, window: u8) -> Result<u8> {
+        require!(
+            window > 0 && window as u32 <= VoterRecord::PARTICIPATION_WINDOW,
+            GovernanceError::InvalidParticipationWindow
+        );
+        
+        let latest_proposal_id = ctx.accounts.governance.latest_proposal_id;
+        Ok(ctx.accounts.voter_record.participation_count(latest_proposal_id, window))
+    }
+
+    // Cancel a proposal (only by the proposer or governance authority)
+    pub fn cancel_proposal(ctx: Context<CancelProposal>) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal;
+        let authority = &ctx.accounts.authority;
+        let clock = Clock::get()?;
+        
+        // Verify proposal has not been executed
+        require!(
+            !proposal.executed,
+            GovernanceError::ProposalAlreadyExecuted
+        );
+        
+        // Verify proposal has not been cancelled
+        require!(
+            !proposal.cancelled,
+            GovernanceError::ProposalCancelled
+        );
+        
+        // Verify cancellation is authorized
+        require!(
+            authority.key() == proposal.proposer || authority.key() == ctx.accounts.governance.authority,
+            GovernanceError::UnauthorizedCancellation
+        );
+        
+        // Mark proposal as cancelled
+        proposal.cancelled = true;
+        
+        emit!(ProposalCancelledEvent {
+            proposal: proposal.key(),
+            cancelled_by: authority.key(),
+            cancellation_time: clock.unix_timestamp,
+        });
+        
+        Ok(())
+    }
+
+    // Update governance parameters (only by governance authority)
+    pub fn update_governance(
+        ctx: Context<UpdateGovernance>,
+        min_proposal_tokens: Option<u64>,
+        voting_period: Option<i64>,
+        execution_delay: Option<i64>,
+        execution_window: Option<i64>,
+        quorum_percentage: Option<u8>,
+        execution_bounty: Option<u64>,
+        guardian: Option<Pubkey>,
+        min_unique_voters: Option<u64>,
+        treasury_epoch_cap: Option<u64>,
+        treasury_epoch_duration: Option<i64>,
+        inactivity_window: Option<u8>,
+        inactivity_decay_bps: Option<u16>,
+    ) -> Result<()> {
+        let governance = &mut ctx.accounts.governance;
+        
+        // Update min_proposal_tokens if provided
+        if let Some(new_min_proposal_tokens) = min_proposal_tokens {
+            governance.min_proposal_tokens = new_min_proposal_tokens;
+        }
+        
+        // Update voting_period if provided
+        if let Some(new_voting_period) = voting_period {
+            require!(new_voting_period > 0, GovernanceError::InvalidVotingPeriod);
+            governance.voting_period = new_voting_period;
+        }
+        
+        // Update execution_delay if provided
+        if let Some(new_execution_delay) = execution_delay {
+            require!(new_execution_delay >= 0, GovernanceError::InvalidExecutionDelay);
+            governance.execution_delay = new_execution_delay;
+        }
+        
+        // Update execution_window if provided
+        if let Some(new_execution_window) = execution_window {
+            require!(new_execution_window >= 0, GovernanceError::InvalidExecutionWindow);
+            governance.execution_window = new_execution_window;
+        }
+        
+        // Update quorum_percentage if provided
+        if let Some(new_quorum_percentage) = quorum_percentage {
+            require!(
+                new_quorum_percentage > 0 && new_quorum_percentage <= 100,
+                GovernanceError::InvalidQuorumPercentage
+            );
+            governance.quorum_percentage = new_quorum_percentage;
+        }
+        
+        // Update execution_bounty if provided
+        if let Some(new_execution_bounty) = execution_bounty {
+            require!(
+                new_execution_bounty <= MAX_EXECUTION_BOUNTY,
+                GovernanceError::InvalidExecutionBounty
+            );
+            governance.execution_bounty = new_execution_bounty;
+        }
+        
+        // Update guardian if provided
+        if let Some(new_guardian) = guardian {
+            governance.guardian = new_guardian;
+        }
+        
+        // Update min_unique_voters if provided (0 disables the requirement)
+        if let Some(new_min_unique_voters) = min_unique_voters {
+            governance.min_unique_voters = new_min_unique_voters;
+        }
+        
+        // Update treasury_epoch_cap if provided (0 disables the cap)
+        if let Some(new_treasury_epoch_cap) = treasury_epoch_cap {
+            governance.treasury_epoch_cap = new_treasury_epoch_cap;
+        }
+        
+        // Update treasury_epoch_duration if provided
+        if let Some(new_treasury_epoch_duration) = treasury_epoch_duration {
+            require!(new_treasury_epoch_duration > 0, GovernanceError::InvalidTreasuryEpochDuration);
+            governance.treasury_epoch_duration = new_treasury_epoch_duration;
+        }
+        
+        // Update inactivity_window if provided (0 disables inactivity decay)
+        if let Some(new_inactivity_window) = inactivity_window {
+            require!(
+                new_inactivity_window as u32 <= VoterRecord::PARTICIPATION_WINDOW,
+                GovernanceError::InvalidInactivityDecay
+            );
+            governance.inactivity_window = new_inactivity_window;
+        }
+        
+        // Update inactivity_decay_bps if provided
+        if let Some(new_inactivity_decay_bps) = inactivity_decay_bps {
+            require!(
+                new_inactivity_decay_bps <= VotingMint::FULL_WEIGHT_BPS,
+                GovernanceError::InvalidInactivityDecay
+            );
+            governance.inactivity_decay_bps = new_inactivity_decay_bps;
+        }
+        
+        emit!(GovernanceUpdatedEvent {
+            governance: governance.key(),
+            min_proposal_tokens: governance.min_proposal_tokens,
+            voting_period: governance.voting_period,
+            execution_delay: governance.execution_delay,
+            execution_window: governance.execution_window,
+            quorum_percentage: governance.quorum_percentage,
+            execution_bounty: governance.execution_bounty,
+            guardian: governance.guardian,
+            min_unique_voters: governance.min_unique_voters,
+            treasury_epoch_cap: governance.treasury_epoch_cap,
+            treasury_epoch_duration: governance.treasury_epoch_duration,
+            inactivity_window: governance.inactivity_window,
+            inactivity_decay_bps: governance.inactivity_decay_bps,
+        });
+        
+        Ok(())
+    }
+
+    // Pause or unpause proposal creation and execution (only by governance authority or guardian)
+    pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+        let governance = &mut ctx.accounts.governance;
+        let clock = Clock::get()?;
+        
+        governance.paused = paused;
+        
+        if paused {
+            emit!(GovernancePausedEvent {
+                governance: governance.key(),
+                paused_by: ctx.accounts.authority.key(),
+                timestamp: clock.unix_timestamp,
+            });
+        } else {
+            emit!(GovernanceUnpausedEvent {
+                governance: governance.key(),
+                unpaused_by: ctx.accounts.authority.key(),
+                timestamp: clock.unix_timestamp,
+            });
+        }
+        
+        Ok(())
+    }
+
+    // Create or update a proposal category with its own, smaller quorum (only by governance authority)
+    pub fn set_proposal_category(
+        ctx: Context<SetProposalCategory>,
+        category_id: u16,
+        name: String,
+        proposal_type: ProposalType,
+        quorum_percentage: u8,
+        max_amount: u64,
+    ) -> Result<()> {
+        let governance = &ctx.accounts.governance;
+        let category = &mut ctx.accounts.category;
+        
+        require!(
+            name.len() <= ProposalCategory::MAX_NAME_LEN,
+            GovernanceError::CategoryNameTooLong
+        );
+        
+        // Categories may only lower the quorum, never raise or remove it
+        require!(
+            quorum_percentage > 0 && quorum_percentage <= governance.quorum_percentage,
+            GovernanceError::InvalidQuorumPercentage
+        );
+        
+        category.governance = governance.key();
+        category.category_id = category_id;
+        category.name = name;
+        category.proposal_type = proposal_type;
+        category.quorum_percentage = quorum_percentage;
+        category.max_amount = max_amount;
+        category.bump = *ctx.bumps.get("category").unwrap();
+        
+        emit!(ProposalCategoryUpdatedEvent {
+            governance: governance.key(),
+            category: category.key(),
+            category_id,
+            proposal_type,
+            quorum_percentage,
+            max_amount,
+        });
+        
+        Ok(())
+    }
+
+    // Post a Merkle root of token holder balances approved by an executed proposal of type
+    // Other, whose action hash commits to the snapshot's id, root, total and slot. The root
+    // decides snapshot votes, so it is not taken on the authority's word. Anyone can post it.
+    pub fn post_balance_snapshot(
+        ctx: Context<PostBalanceSnapshot>,
+        snapshot_id: u64,
+        merkle_root: [u8; 32],
+        total_balance: u64,
+        snapshot_slot: u64,
+    ) -> Result<()> {
+        let snapshot = &mut ctx.accounts.snapshot;
+        
+        require_proposal_approved(
+            &ctx.accounts.proposal,
+            ProposalType::Other,
+            snapshot_action_hash(snapshot_id, &merkle_root, total_balance, snapshot_slot),
+        )?;
+        
+        snapshot.governance = ctx.accounts.governance.key();
+        snapshot.snapshot_id = snapshot_id;
+        snapshot.merkle_root = merkle_root;
+        snapshot.total_balance = total_balance;
+        snapshot.snapshot_slot = snapshot_slot;
+        snapshot.bump = *ctx.bumps.get("snapshot").unwrap();
+        
+        emit!(BalanceSnapshotPostedEvent {
+            governance: snapshot.governance,
+            snapshot: snapshot.key(),
+            snapshot_id,
+            merkle_root,
+            total_balance,
+            snapshot_slot,
+        });
+        
+        Ok(())
+    }
+
+    // Configure the NFT collection whose holders form the voting council (only by governance authority)
+    pub fn configure_council(
+        ctx: Context<ConfigureCouncil>,
+        council_collection: Pubkey,
+        council_size: u64,
+    ) -> Result<()> {
+        let governance = &mut ctx.accounts.governance;
+        
+        governance.council_collection = council_collection;
+        governance.council_size = council_size;
+        
+        emit!(CouncilConfiguredEvent {
+            governance: governance.key(),
+            council_collection,
+            council_size,
+        });
+        
+        Ok(())
+    }
+
+    // Accept voting power from an additional mint at the given weight, registered by the staking
+    // pool for that mint (only by governance authority)
+    pub fn add_voting_mint(
+        ctx: Context<AddVotingMint>,
+        weight_bps: u16,
+        staking_pool: Pubkey,
+    ) -> Result<()> {
+        let governance = &mut ctx.accounts.governance;
+        let mint = ctx.accounts.mint.key();
+        
+        require!(weight_bps <= VotingMint::MAX_WEIGHT_BPS, GovernanceError::InvalidVotingMintWeight);
+        
+        // Voter power only reaches a registry through its staking pool, so a mint without one
+        // could never be voted with
+        require!(staking_pool != Pubkey::default(), GovernanceError::VotingMintPoolRequired);
+        
+        require!(
+            governance.voting_mints.len() < Governance::MAX_VOTING_MINTS,
+            GovernanceError::TooManyVotingMints
+        );
+        require!(
+            !governance.voting_mints.iter().any(|voting_mint| voting_mint.mint == mint),
+            GovernanceError::VotingMintAlreadyAdded
+        );
+        
+        governance.voting_mints.push(VotingMint { mint, weight_bps });
+        
+        // Each mint gets its own registry
+        let voting_power_registry = &mut ctx.accounts.voting_power_registry;
+        voting_power_registry.governance = governance.key();
+        voting_power_registry.mint = mint;
+        voting_power_registry.total_voting_power = 0;
+        voting_power_registry.page_count = 0;
+        voting_power_registry.bump = *ctx.bumps.get("voting_power_registry").unwrap();
+        voting_power_registry.staking_pool = staking_pool;
+        voting_power_registry.decayed_voting_power = 0;
+        
+        emit!(VotingMintUpdatedEvent {
+            governance: governance.key(),
+            mint,
+            old_weight_bps: 0,
+            new_weight_bps: weight_bps,
+            total_voting_power: governance.total_voting_power,
+        });
+        
+        Ok(())
+    }
+
+    // Change the weight applied to a voting mint (only by governance authority)
+    pub fn set_voting_mint_weight(ctx: Context<SetVotingMintWeight>, weight_bps: u16) -> Result<()> {
+        let governance = &mut ctx.accounts.governance;
+        let voting_power_registry = &ctx.accounts.voting_power_registry;
+        
+        require!(weight_bps <= VotingMint::MAX_WEIGHT_BPS, GovernanceError::InvalidVotingMintWeight);
+        
+        let voting_mint = governance
+            .voting_mints
+            .iter_mut()
+            .find(|voting_mint| voting_mint.mint == voting_power_registry.mint)
+            .ok_or(GovernanceError::UnknownVotingMint)?;
+        let old_weight_bps = voting_mint.weight_bps;
+        voting_mint.weight_bps = weight_bps;
+        
+        // Re-weight this registry's contribution to the total
+        governance.total_voting_power = governance
+            .total_voting_power
+            .checked_sub(weighted_power(voting_power_registry.counted_voting_power(), old_weight_bps))
+            .unwrap()
+            .checked_add(weighted_power(voting_power_registry.counted_voting_power(), weight_bps))
+            .unwrap();
+        
+        emit!(VotingMintUpdatedEvent {
+            governance: governance.key(),
+            mint: voting_power_registry.mint,
+            old_weight_bps,
+            new_weight_bps: weight_bps,
+            total_voting_power: governance.total_voting_power,
+        });
+        
+        Ok(())
+    }
+
+    // Let a staking pool's reported total voting power stand in for a registry's total (only by
+    // governance authority, default stops reports and leaves the total to per-voter updates)
+    pub fn set_registry_staking_pool(
+        ctx: Context<SetRegistryStakingPool>,
+        staking_pool: Pubkey,
+    ) -> Result<()> {
+        let voting_power_registry = &mut ctx.accounts.voting_power_registry;
+        voting_power_registry.staking_pool = staking_pool;
+        
+        emit!(RegistryStakingPoolUpdatedEvent {
+            voting_power_registry: voting_power_registry.key(),
+            staking_pool,
+        });
+        
+        Ok(())
+    }
+
+    // Replace a registry's total with the aggregate voting power of its staking pool's open
+    // positions (signed by the staking pool)
+    pub fn report_staked_voting_power(
+        ctx: Context<ReportStakedVotingPower>,
+        total_voting_power: u64,
+    ) -> Result<()> {
+        let governance = &mut ctx.accounts.governance;
+        let voting_power_registry = &mut ctx.accounts.voting_power_registry;
+        
+        let weight_bps = governance
+            .voting_mints
+            .iter()
+            .find(|voting_mint| voting_mint.mint == voting_power_registry.mint)
+            .ok_or(GovernanceError::UnknownVotingMint)?
+            .weight_bps;
+        
+        // Re-weight this registry's contribution to the total used for quorum
+        let old_registry_total = voting_power_registry.counted_voting_power();
+        voting_power_registry.total_voting_power = total_voting_power;
+        governance.total_voting_power = governance
+            .total_voting_power
+            .checked_sub(weighted_power(old_registry_total, weight_bps))
+            .unwrap()
+            .checked_add(weighted_power(voting_power_registry.counted_voting_power(), weight_bps))
+            .unwrap();
+        
+        emit!(StakedVotingPowerReportedEvent {
+            voting_power_registry: voting_power_registry.key(),
+            staking_pool: ctx.accounts.staking_pool.key(),
+            total_voting_power,
+            governance_total_voting_power: governance.total_voting_power,
+        });
+        
+        Ok(())
+    }
+
+    // Move one staking position's contribution to a voter's power from its previously
+    // registered base power and reputation boost to its current ones. Signed by the registry's
+    // staking pool, which reads both, and the position's start, from the position.
+    pub fn register_voting_power(
+        ctx: Context<RegisterVotingPower>,
+        voter: Pubkey,
+        old_base_voting_power: u64,
+        old_reputation_boost: u64,
+        base_voting_power: u64,
+        reputation_boost: u64,
+        position_start: i64,
+    ) -> Result<()> {
+        let governance = &mut ctx.accounts.governance;
+        let voting_power_registry = &mut ctx.accounts.voting_power_registry;
+        let voter_power = &mut ctx.accounts.voter_power;
+        
+        let weight_bps = governance
+            .voting_mints
+            .iter()
+            .find(|voting_mint| voting_mint.mint == voting_power_registry.mint)
+            .ok_or(GovernanceError::UnknownVotingMint)?
+            .weight_bps;
+        
+        require!(
+            old_reputation_boost <= MAX_REPUTATION_BOOST && reputation_boost <= MAX_REPUTATION_BOOST,
+            GovernanceError::InvalidReputationBoost
+        );
+        
+        // Apply the position's reputation boost percentage to its base power, replacing what
+        // it contributed before
+        let old_position_power = boosted_position_power(old_base_voting_power, old_reputation_boost);
+        let position_power = boosted_position_power(base_voting_power, reputation_boost);
+        let old_power = voter_power.voting_power;
+        
+        // Power added by a position only counts for votes once the position has been open for
+        // the warmup, so a stake can't be opened, voted with and exited in one block. Other
+        // changes apply straight away.
+        let now = Clock::get()?.unix_timestamp;
+        let position_matures_at = position_start.checked_add(VOTING_POWER_WARMUP).unwrap();
+        voter_power.apply_position_change(
+            old_position_power,
+            position_power,
+            position_matures_at,
+            now,
+        );
+        let voting_power = voter_power.voting_power;
+        
+        // A freshly created record has no voter set and no power yet; its inactivity window
+        // starts now
+        if voter_power.voter == Pubkey::default() {
+            voter_power.activity_base_proposal_id = governance.latest_proposal_id;
+        }
+        voter_power.voter = voter;
+        voter_power.governance = governance.key();
+        voter_power.mint = voting_power_registry.mint;
+        voter_power.staking_pool = ctx.accounts.staking_pool.key();
+        voter_power.base_voting_power = voter_power
+            .base_voting_power
+            .checked_sub(old_base_voting_power)
+            .unwrap()
+            .checked_add(base_voting_power)
+            .unwrap();
+        
+        // Record the power in the voter's page entry, appending to the tail page for new voters
+        let mut page = ctx.accounts.voting_power_page.load_mut()?;
+        if voter_power.has_page_entry {
+            require!(
+                page.page_index == voter_power.page_index,
+                GovernanceError::WrongVotingPowerPage
+            );
+        } else {
+            require!(
+                page.page_index + 1 == voting_power_registry.page_count,
+                GovernanceError::WrongVotingPowerPage
+            );
+            let slot = page.free_slot().ok_or(GovernanceError::VotingPowerPageFull)?;
+            voter_power.has_page_entry = true;
+            voter_power.page_index = page.page_index;
+            voter_power.page_slot = slot as u16;
+        }
+        page.set_entry(voter_power.page_slot as usize, voter, voting_power);
+        
+        // The registry's total is reported by its staking pool; only the decayed share moves here
+        let old_registry_total = voting_power_registry.counted_voting_power();
+        
+        // An inactive voter's decay follows their new power until they vote again
+        if voter_power.decayed_at != 0 {
+            let decayed_voting_power = weighted_power(voting_power, governance.inactivity_decay_bps);
+            voting_power_registry.decayed_voting_power = voting_power_registry
+                .decayed_voting_power
+                .checked_sub(voter_power.decayed_voting_power)
+                .unwrap()
+                .checked_add(decayed_voting_power)
+                .unwrap();
+            voter_power.decayed_voting_power = decayed_voting_power;
+        }
+        
+        // Track how many voters hold power in the governance token for one-person-one-vote quorum
+        if voting_power_registry.mint == governance.token_mint {
+            if old_power == 0 && voting_power > 0 {
+                governance.staker_count = governance.staker_count.checked_add(1).unwrap();
+            } else if old_power > 0 && voting_power == 0 {
+                governance.staker_count = governance.staker_count.checked_sub(1).unwrap();
+            }
+        }
+        
+        // Update the weighted total used for quorum
+        governance.total_voting_power = governance
+            .total_voting_power
+            .checked_sub(weighted_power(old_registry_total, weight_bps))
+            .unwrap()
+            .checked_add(weighted_power(voting_power_registry.counted_voting_power(), weight_bps))
+            .unwrap();
+        
+        emit!(VotingPowerUpdatedEvent {
+            voter,
+            staking_pool: ctx.accounts.staking_pool.key(),
+            old_voting_power: old_power,
+            new_voting_power: voting_power,
+            reputation_boost,
+            total_voting_power: voting_power_registry.total_voting_power,
+        });
+        
+        Ok(())
+    }
+
+    // Append a new page to a registry's chain of voting power pages (permissionless, payer funds rent)
+    pub fn add_voting_power_page(ctx: Context<AddVotingPowerPage>) -> Result<()> {
+        let voting_power_registry = &mut ctx.accounts.voting_power_registry;
+        let page_index = voting_power_registry.page_count;
+        
+        // Link the previous tail page to the new page
+        if page_index > 0 {
+            let previous_page_loader = ctx
+                .accounts
+                .previous_page
+                .as_ref()
+                .ok_or(GovernanceError::WrongVotingPowerPage)?;
+            let mut previous_page = previous_page_loader.load_mut()?;
+            require!(
+                previous_page.registry == voting_power_registry.key()
+                    && previous_page.page_index + 1 == page_index,
+                GovernanceError::WrongVotingPowerPage
+            );
+            previous_page.next_page = ctx.accounts.voting_power_page.key();
+        }
+        
+        let mut page = ctx.accounts.voting_power_page.load_init()?;
+        page.registry = voting_power_registry.key();
+        page.next_page = Pubkey::default();
+        page.page_index = page_index;
+        
+        voting_power_registry.page_count = page_index.checked_add(1).unwrap();
+        
+        emit!(VotingPowerPageAddedEvent {
+            voting_power_registry: voting_power_registry.key(),
+            page: ctx.accounts.voting_power_page.key(),
+            page_index,
+        });
+        
+        Ok(())
+    }
+
+    // Free the page slot of a voter whose power has dropped to zero (permissionless)
+    pub fn remove_voter_entry(ctx: Context<RemoveVoterEntry>) -> Result<()> {
+        let voter_power = &mut ctx.accounts.voter_power;
+        let mut page = ctx.accounts.voting_power_page.load_mut()?;
+        
+        require!(voter_power.voting_power == 0, GovernanceError::VoterStillHasPower);
+        require!(
+            voter_power.has_page_entry && page.page_index == voter_power.page_index,
+            GovernanceError::WrongVotingPowerPage
+        );
+        
+        page.clear_entry(voter_power.page_slot as usize);
+        voter_power.has_page_entry = false;
+        
+        emit!(VoterEntryMovedEvent {
+            voter: voter_power.voter,
+            from_page_index: voter_power.page_index,
+            from_slot: voter_power.page_slot,
+            to_page_index: None,
+            to_slot: None,
+        });
+        
+        Ok(())
+    }
+
+    // Move the last entry of the tail page into a free slot of an earlier page (permissionless)
+    pub fn compact_voting_power_pages(
+        ctx: Context<CompactVotingPowerPages>,
+        target_slot: u16,
+    ) -> Result<()> {
+        let voting_power_registry = &ctx.accounts.voting_power_registry;
+        let moved_voter_power = &mut ctx.accounts.moved_voter_power;
+        let mut target_page = ctx.accounts.target_page.load_mut()?;
+        let mut tail_page = ctx.accounts.tail_page.load_mut()?;
+        
+        require!(
+            tail_page.page_index + 1 == voting_power_registry.page_count
+                && target_page.page_index < tail_page.page_index,
+            GovernanceError::WrongVotingPowerPage
+        );
+        require!(tail_page.len > 0, GovernanceError::NothingToCompact);
+        require!(
+            (target_slot as usize) < VotingPowerPage::CAPACITY
+                && target_page.entries[target_slot as usize].voter == Pubkey::default(),
+            GovernanceError::VotingPowerSlotOccupied
+        );
+        
+        // The moved voter's record must point at the tail page's last entry
+        let source_slot = tail_page.len - 1;
+        require!(
+            moved_voter_power.has_page_entry
+                && moved_voter_power.page_index == tail_page.page_index
+                && moved_voter_power.page_slot == source_slot,
+            GovernanceError::WrongVotingPowerPage
+        );
+        
+        let entry = tail_page.clear_entry(source_slot as usize);
+        target_page.set_entry(target_slot as usize, entry.voter, entry.voting_power);
+        
+        moved_voter_power.page_index = target_page.page_index;
+        moved_voter_power.page_slot = target_slot;
+        
+        emit!(VoterEntryMovedEvent {
+            voter: entry.voter,
+            from_page_index: tail_page.page_index,
+            from_slot: source_slot,
+            to_page_index: Some(target_page.page_index),
+            to_slot: Some(target_slot),
+        });
+        
+        Ok(())
+    }
+
+    // Leave part of a voter's power out of the totals used for quorum, and out of their own
+    // votes, once they have sat out the last inactivity_window proposals (permissionless).
+    // Their next cast_vote counts at the decayed weight and restores the rest.
+    pub fn decay_voting_power(ctx: Context<UpdateVoterActivity>) -> Result<()> {
+        let governance = &mut ctx.accounts.governance;
+        let voter_power = &mut ctx.accounts.voter_power;
+        let now = Clock::get()?.unix_timestamp;
+        
+        require!(
+            governance.inactivity_window > 0 && governance.inactivity_decay_bps > 0,
+            GovernanceError::InactivityDecayDisabled
+        );
+        require!(voter_power.decayed_at == 0, GovernanceError::VoterAlreadyDecayed);
+        
+        // The window must have filled with proposals since the voter registered or last voted
+        let window = governance.inactivity_window;
+        require!(
+            governance.latest_proposal_id
+                >= voter_power.activity_base_proposal_id.checked_add(window as u64).unwrap(),
+            GovernanceError::VoterNotInactive
+        );
+        
+        // Voters without a record have never voted
+        let voter_record_info = ctx.accounts.voter_record.to_account_info();
+        if !voter_record_info.data_is_empty() {
+            let voter_record = Account::<VoterRecord>::try_from(&voter_record_info)?;
+            require!(
+                voter_record.participation_count(governance.latest_proposal_id, window) == 0,
+                GovernanceError::VoterNotInactive
+            );
+        }
+        
+        let decayed_voting_power = weighted_power(voter_power.voting_power, governance.inactivity_decay_bps);
+        set_decayed_voting_power(
+            governance,
+            &mut ctx.accounts.voting_power_registry,
+            voter_power,
+            decayed_voting_power,
+        )?;
+        voter_power.decayed_at = now;
+        
+        emit!(VotingPowerDecayedEvent {
+            voter: voter_power.voter,
+            mint: voter_power.mint,
+            voting_power: voter_power.voting_power,
+            decayed_voting_power,
+            total_voting_power: governance.total_voting_power,
+        });
+        
+        Ok(())
+    }
+
+    // Count an inactive voter's full power again once they have voted since it decayed
+    // (permissionless). cast_vote restores it itself; this covers delegated and session votes.
+    pub fn restore_voting_power(ctx: Context<UpdateVoterActivity>) -> Result<()> {
+        let governance = &mut ctx.accounts.governance;
+        let voter_power = &mut ctx.accounts.voter_power;
+        
+        require!(voter_power.decayed_at != 0, GovernanceError::VoterNotDecayed);
+        
+        let voter_record_info = ctx.accounts.voter_record.to_account_info();
+        require!(!voter_record_info.data_is_empty(), GovernanceError::NoVoteSinceDecay);
+        let voter_record = Account::<VoterRecord>::try_from(&voter_record_info)?;
+        require!(
+            voter_record.last_vote_at >= voter_power.decayed_at,
+            GovernanceError::NoVoteSinceDecay
+        );
+        
+        restore_voting_power_for(governance, &mut ctx.accounts.voting_power_registry, voter_power)
+    }
+}
+
+// Highest reputation boost percentage accepted from the staking program for a position
+pub const MAX_REPUTATION_BOOST: u64 = 100;
+
+// Time a staking position must have been open before its power can vote (1 day)
+pub const VOTING_POWER_WARMUP: i64 = 24 * 60 * 60;
+
+// Default length of the trailing treasury withdrawal window (7 days)
+pub const DEFAULT_TREASURY_EPOCH_DURATION: i64 = 7 * 24 * 60 * 60;
+
+// Wormhole core bridge instruction index for PostMessage
+pub const WORMHOLE_POST_MESSAGE_INSTRUCTION: u8 = 1;
+
+// Longest a voting session may stay valid (30 days)
+pub const MAX_VOTING_SESSION_DURATION: i64 = 30 * 24 * 60 * 60;
+
+// Time after voting ends before a settled proposal's vote accounts can be closed (30 days)
+pub const VOTE_ACCOUNT_GRACE_PERIOD: i64 = 30 * 24 * 60 * 60;
+
+// Time after voting ends during which a vote can be credited to a participation badge (90 days)
+pub const BADGE_CLAIM_WINDOW: i64 = 90 * 24 * 60 * 60;
+
+// Share of a closed vote account's rent paid to the cranker, in basis points
+pub const CRANK_REWARD_BPS: u64 = 1000;
+
+// Largest execution bounty governance can set (1,000 WCT)
+pub const MAX_EXECUTION_BOUNTY: u64 = 1_000 * 1_000_000_000;
+
+// Helper function to check that a proposal of the given type was executed and approved
+// exactly this action
+fn require_proposal_approved(proposal: &Proposal, proposal_type: ProposalType, action_hash: [u8; 32]) -> Result<()> {
+    require!(
+        proposal.executed && proposal.outcome == ProposalOutcome::Succeeded,
+        GovernanceError::ProposalNotApproved
+    );
+    require!(
+        proposal.proposal_type == proposal_type,
+        GovernanceError::ProposalTypeMismatch
+    );
+    require!(
+        proposal.action_hash == action_hash,
+        GovernanceError::ActionNotAuthorized
+    );
+    Ok(())
+}
+
+// Action hash a proposal approving a balance snapshot commits to
+fn snapshot_action_hash(snapshot_id: u64, merkle_root: &[u8; 32], total_balance: u64, snapshot_slot: u64) -> [u8; 32] {
+    hashv(&[
+        b"post_balance_snapshot".as_ref(),
+        &snapshot_id.to_le_bytes(),
+        merkle_root.as_ref(),
+        &total_balance.to_le_bytes(),
+        &snapshot_slot.to_le_bytes(),
+    ])
+    .to_bytes()
+}
+
+// Helper function to verify a proposal is still accepting votes
+fn verify_voting_open(proposal: &Proposal, now: i64) -> Result<()> {
+    // Verify voting is still open
+    require!(
+        now < proposal.voting_ends_at,
+        GovernanceError::VotingClosed
+    );
+    
+    // Verify proposal is not cancelled
+    require!(
+        !proposal.cancelled,
+        GovernanceError::ProposalCancelled
+    );
+    
+    // Verify proposal is not executed
+    require!(
+        !proposal.executed,
+        GovernanceError::ProposalAlreadyExecuted
+    );
+    
+    // Verify the execution plan is complete before anyone votes on it
+    require!(
+        proposal.items_added == proposal.item_count,
+        GovernanceError::ExecutionItemsIncomplete
+    );
+    
+    Ok(())
+}
+
+// Helper function to record a vote on a proposal, replacing the voter's previous vote if any
+#[allow(clippy::too_many_arguments)]
+fn record_vote(
+    proposal: &mut Account<Proposal>,
+    voter_vote: &mut Account<VoterVote>,
+    voter_record: &mut Account<VoterRecord>,
+    governance: Pubkey,
+    voter: Pubkey,
+    vote: Vote,
+    voter_power: u64,
+    voter_record_bump: u8,
+    now: i64,
+    cast_directly: bool,
+) -> Result<()> {
+    // Check if the voter already voted (a freshly created vote record has no voter set)
+    let is_first_vote = voter_vote.voter == Pubkey::default();
+    
+    // A vote the voter cast themselves can only be changed by the voter
+    require!(
+        is_first_vote || cast_directly || !voter_vote.cast_directly,
+        GovernanceError::DirectVoteRecorded
+    );
+    
+    if is_first_vote {
+        // First time voting, create vote record
+        voter_vote.voter = voter;
+        voter_vote.proposal = proposal.key();
+        voter_vote.vote = vote;
+        voter_vote.voting_power = voter_power;
+        voter_vote.badge_claimed = false;
+        voter_vote.cast_directly = cast_directly;
+        
+        // Count the voter towards the proposal's distinct voters
+        proposal.voter_count = proposal.voter_count.checked_add(1).unwrap();
+        
+        // Count this proposal towards the voter's participation
+        voter_record.proposals_voted = voter_record.proposals_voted.checked_add(1).unwrap();
+        voter_record.record_participation(proposal.proposal_id);
+        voter_record.total_power_used = voter_record.total_power_used.checked_add(voter_power).unwrap();
+        
+        // Update proposal vote counts
+        match vote {
+            Vote::Yes => {
+                proposal.yes_votes = proposal.yes_votes.checked_add(voter_power).unwrap();
+            }
+            Vote::No => {
+                proposal.no_votes = proposal.no_votes.checked_add(voter_power).unwrap();
+            }
+            Vote::Abstain => {
+                // Abstaining doesn't affect yes/no counts but still counts toward quorum
+            }
+        }
+    } else {
+        // Voter already voted, update their vote
+        
+        // Remove previous vote
+        match voter_vote.vote {
+            Vote::Yes => {
+                proposal.yes_votes = proposal.yes_votes.checked_sub(voter_vote.voting_power).unwrap();
+            }
+            Vote::No => {
+                proposal.no_votes = proposal.no_votes.checked_sub(voter_vote.voting_power).unwrap();
+            }
+            Vote::Abstain => {
+                // Abstaining doesn't affect yes/no counts
+            }
+        }
+        
+        // Replace the power previously recorded for this proposal
+        voter_record.total_power_used = voter_record
+            .total_power_used
+            .checked_sub(voter_vote.voting_power)
+            .unwrap()
+            .checked_add(voter_power)
+            .unwrap();
+        
+        // Update to new vote
+        voter_vote.vote = vote;
+        voter_vote.voting_power = voter_power; // Update voting power in case it changed
+        voter_vote.cast_directly = voter_vote.cast_directly || cast_directly;
+        
+        // Add new vote
+        match vote {
+            Vote::Yes => {
+                proposal.yes_votes = proposal.yes_votes.checked_add(voter_power).unwrap();
+            }
+            Vote::No => {
+                proposal.no_votes = proposal.no_votes.checked_add(voter_power).unwrap();
+            }
+            Vote::Abstain => {
+                // Abstaining doesn't affect yes/no counts
+            }
+        }
+    }
+    
+    // Update voter stats
+    voter_record.voter = voter;
+    voter_record.governance = governance;
+    voter_record.last_vote_at = now;
+    voter_record.bump = voter_record_bump;
+    
+    Ok(())
+}
+
+// Helper function to verify a Merkle proof using sorted-pair keccak hashing
+fn verify_merkle_proof(proof: &[[u8; 32]], root: [u8; 32], leaf: [u8; 32]) -> bool {
+    let mut computed_hash = leaf;
+    for proof_element in proof {
+        computed_hash = if computed_hash <= *proof_element {
+            keccak::hashv(&[&computed_hash, proof_element]).0
+        } else {
+            keccak::hashv(&[proof_element, &computed_hash]).0
+        };
+    }
+    computed_hash == root
+}
+
+// Helper function to get voter's weighted voting power from the VoterPower
+// accounts passed for each voting mint
+fn get_voter_power(
+    governance: &Account<Governance>,
+    voter: Pubkey,
+    voter_power_accounts: &[AccountInfo],
+    now: i64,
+) -> Result<u64> {
+    let mut counted_mints: Vec<Pubkey> = Vec::with_capacity(voter_power_accounts.len());
+    let mut total_power: u64 = 0;
+    
+    for account_info in voter_power_accounts {
+        let voter_power = Account::<VoterPower>::try_from(account_info)?;
+        
+        require!(
+            voter_power.voter == voter && voter_power.governance == governance.key(),
+            GovernanceError::InvalidVoterPowerAccount
+        );
+        
+        // Each mint may only be counted once
+        require!(
+            !counted_mints.contains(&voter_power.mint),
+            GovernanceError::DuplicateVotingMint
+        );
+        counted_mints.push(voter_power.mint);
+        
+        let weight_bps = governance
+            .voting_mints
+            .iter()
+            .find(|voting_mint| voting_mint.mint == voter_power.mint)
+            .ok_or(GovernanceError::UnknownVotingMint)?
+            .weight_bps;
+        
+        total_power = total_power
+            .checked_add(weighted_power(voter_power.effective_voting_power(now), weight_bps))
+            .unwrap();
+    }
+    
+    Ok(total_power)
+}
+
+// Helper function to veto a proposal that is still within its execution delay
+fn apply_veto(
+    governance: &Governance,
+    proposal: &mut Account<Proposal>,
+    proposal_result: Option<&mut Account<ProposalResult>>,
+    vetoed_by: Pubkey,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    
+    // Verify voting is closed
+    require!(
+        clock.unix_timestamp >= proposal.voting_ends_at,
+        GovernanceError::VotingStillOpen
+    );
+    
+    // Verify the proposal is still within its timelock
+    require!(
+        clock.unix_timestamp < proposal.voting_ends_at + governance.execution_delay
+            && !proposal.execution_started,
+        GovernanceError::VetoWindowClosed
+    );
+    
+    require!(
+        proposal.outcome == ProposalOutcome::Pending || proposal.outcome == ProposalOutcome::Succeeded,
+        GovernanceError::ProposalAlreadyFinalized
+    );
+    
+    proposal.cancelled = true;
+    proposal.outcome = ProposalOutcome::Vetoed;
+    
+    // Keep the result oracle in sync if the proposal was already finalized
+    if let Some(proposal_result) = proposal_result {
+        proposal_result.outcome = ProposalOutcome::Vetoed;
+    }
+    
+    emit!(ProposalVetoedEvent {
+        proposal: proposal.key(),
+        vetoed_by,
+        veto_time: clock.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+// Helper function to pay tokens out of the treasury, charging them against the treasury cap
+fn pay_from_treasury<'info>(
+    governance: &mut Account<'info, Governance>,
+    treasury: &Account<'info, TokenAccount>,
+    to: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    amount: u64,
+    now: i64,
+) -> Result<()> {
+    consume_treasury_allowance(governance, amount, now)?;
+    
+    let governance_seeds = &[
+        b"governance".as_ref(),
+        governance.token_mint.as_ref(),
+        &[governance.bump],
+    ];
+    
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program,
+            token::Transfer {
+                from: treasury.to_account_info(),
+                to,
+                authority: governance.to_account_info(),
+            },
+            &[governance_seeds],
+        ),
+        amount,
+    )
+}
+
+// Helper function to get how much the treasury may still pay out within the trailing
+// treasury_epoch_duration
+fn treasury_allowance_remaining(governance: &Governance, now: i64) -> u64 {
+    if governance.treasury_epoch_cap == 0 {
+        return u64::MAX;
+    }
+    
+    let window_start = now - governance.treasury_epoch_duration;
+    let withdrawn = governance
+        .recent_treasury_withdrawals
+        .iter()
+        .filter(|record| record.withdrawn_at > window_start)
+        .fold(0u64, |total, record| total.saturating_add(record.amount));
+    governance.treasury_epoch_cap.saturating_sub(withdrawn)
+}
+
+// Helper function to count a treasury outflow against the cap over the trailing
+// treasury_epoch_duration, so no window of that length pays out more than the cap
+fn consume_treasury_allowance(governance: &mut Governance, amount: u64, now: i64) -> Result<()> {
+    if governance.treasury_epoch_cap == 0 || amount == 0 {
+        return Ok(());
+    }
+    
+    require!(
+        amount <= treasury_allowance_remaining(governance, now),
+        GovernanceError::TreasuryEpochCapExceeded
+    );
+    
+    // Records older than the window are reused; if every slot is still inside it, wait
+    let window_start = now - governance.treasury_epoch_duration;
+    let slot = governance
+        .recent_treasury_withdrawals
+        .iter()
+        .position(|record| record.amount == 0 || record.withdrawn_at <= window_start)
+        .ok_or(GovernanceError::TreasuryEpochCapExceeded)?;
+    governance.recent_treasury_withdrawals[slot] = TreasuryWithdrawalRecord {
+        amount,
+        withdrawn_at: now,
+    };
+    
+    Ok(())
+}
+
+// Helper function to verify a proposal can be executed now, tallying it if it hasn't been finalized
+fn verify_executable(governance: &Governance, proposal: &Proposal, now: i64) -> Result<()> {
+    // Verify governance is not paused
+    require!(!governance.paused, GovernanceError::GovernancePaused);
+    
+    // Verify voting is closed
+    require!(
+        now >= proposal.voting_ends_at,
+        GovernanceError::VotingStillOpen
+    );
+    
+    // Verify proposal has not been executed
+    require!(
+        !proposal.executed,
+        GovernanceError::ProposalAlreadyExecuted
+    );
+    
+    // Verify proposal has not been cancelled
+    require!(
+        !proposal.cancelled,
+        GovernanceError::ProposalCancelled
+    );
+    
+    // Verify execution delay has passed
+    require!(
+        now >= proposal.voting_ends_at + governance.execution_delay,
+        GovernanceError::ExecutionDelayNotPassed
+    );
+    
+    // Verify the execution window has not lapsed
+    require!(
+        !is_execution_window_expired(governance, proposal, now),
+        GovernanceError::ExecutionWindowExpired
+    );
+    
+    // Verify proposal passed
+    let outcome = if proposal.outcome == ProposalOutcome::Pending {
+        tally_outcome(proposal).0
+    } else {
+        proposal.outcome
+    };
+    require!(
+        outcome != ProposalOutcome::QuorumFailed,
+        GovernanceError::QuorumNotReached
+    );
+    require!(
+        outcome == ProposalOutcome::Succeeded,
+        GovernanceError::ProposalNotPassed
+    );
+    
+    Ok(())
+}
+
+// Helper function to apply a staking position's reputation boost percentage to its base power
+fn boosted_position_power(base_voting_power: u64, reputation_boost: u64) -> u64 {
+    (base_voting_power as u128)
+        .checked_mul(100 + reputation_boost as u128)
+        .unwrap()
+        .checked_div(100)
+        .unwrap() as u64
+}
+
+// Helper function to check that a voter holds staked power in the governance token, attested
+// by a staking pool, which gates one-person-one-vote proposals against sybil wallets
+fn is_staker(
+    governance: &Account<Governance>,
+    voter: Pubkey,
+    voter_power_accounts: &[AccountInfo],
+    now: i64,
+) -> Result<bool> {
+    for account_info in voter_power_accounts {
+        let voter_power = Account::<VoterPower>::try_from(account_info)?;
+        
+        require!(
+            voter_power.voter == voter && voter_power.governance == governance.key(),
+            GovernanceError::InvalidVoterPowerAccount
+        );
+        
+        // Only power a staking pool registered from a real, warmed-up position counts
+        if voter_power.mint == governance.token_mint
+            && voter_power.staking_pool != Pubkey::default()
+            && voter_power.active_voting_power(now) > 0
+        {
+            return Ok(true);
+        }
+    }
+    
+    Ok(false)
+}
+
+// Helper function to restore the decayed power of each VoterPower account passed to a vote,
+// using the registry passed at the same position
+fn restore_decayed_power(
+    governance: &mut Account<Governance>,
+    voter_power_accounts: &[AccountInfo],
+    registry_accounts: &[AccountInfo],
+) -> Result<()> {
+    for (voter_power_info, registry_info) in voter_power_accounts.iter().zip(registry_accounts) {
+        let mut voter_power = Account::<VoterPower>::try_from(voter_power_info)?;
+        if voter_power.decayed_at == 0 {
+            continue;
+        }
+        
+        let mut voting_power_registry = Account::<VotingPowerRegistry>::try_from(registry_info)?;
+        require!(
+            voting_power_registry.governance == governance.key()
+                && voting_power_registry.mint == voter_power.mint,
+            GovernanceError::InvalidVotingPowerRegistry
+        );
+        
+        restore_voting_power_for(governance, &mut voting_power_registry, &mut voter_power)?;
+        voter_power.exit(&crate::ID)?;
+        voting_power_registry.exit(&crate::ID)?;
+    }
+    
+    Ok(())
+}
+
+// Helper function to count a decayed voter's full power again and restart their inactivity window
+fn restore_voting_power_for(
+    governance: &mut Governance,
+    voting_power_registry: &mut VotingPowerRegistry,
+    voter_power: &mut VoterPower,
+) -> Result<()> {
+    let restored_voting_power = voter_power.decayed_voting_power;
+    set_decayed_voting_power(governance, voting_power_registry, voter_power, 0)?;
+    voter_power.decayed_at = 0;
+    voter_power.activity_base_proposal_id = governance.latest_proposal_id;
+    
+    emit!(VotingPowerRestoredEvent {
+        voter: voter_power.voter,
+        mint: voter_power.mint,
+        voting_power: voter_power.voting_power,
+        restored_voting_power,
+        total_voting_power: governance.total_voting_power,
+    });
+    
+    Ok(())
+}
+
+// Helper function to change how much of a voter's power their registry leaves out of the
+// governance total
+fn set_decayed_voting_power(
+    governance: &mut Governance,
+    voting_power_registry: &mut VotingPowerRegistry,
+    voter_power: &mut VoterPower,
+    decayed_voting_power: u64,
+) -> Result<()> {
+    let weight_bps = governance
+        .voting_mints
+        .iter()
+        .find(|voting_mint| voting_mint.mint == voting_power_registry.mint)
+        .ok_or(GovernanceError::UnknownVotingMint)?
+        .weight_bps;
+    
+    let old_registry_total = voting_power_registry.counted_voting_power();
+    voting_power_registry.decayed_voting_power = voting_power_registry
+        .decayed_voting_power
+        .checked_sub(voter_power.decayed_voting_power)
+        .unwrap()
+        .checked_add(decayed_voting_power)
+        .unwrap();
+    voter_power.decayed_voting_power = decayed_voting_power;
+    
+    governance.total_voting_power = governance
+        .total_voting_power
+        .checked_sub(weighted_power(old_registry_total, weight_bps))
+        .unwrap()
+        .checked_add(weighted_power(voting_power_registry.counted_voting_power(), weight_bps))
+        .unwrap();
+    
+    Ok(())
+}
+
+// Helper function to apply a voting mint's weight to raw voting power
+fn weighted_power(power: u64, weight_bps: u16) -> u64 {
+    (power as u128)
+        .checked_mul(weight_bps as u128)
+        .unwrap()
+        .checked_div(VotingMint::FULL_WEIGHT_BPS as u128)
+        .unwrap() as u64
+}
+
+// Helper function to determine a closed proposal's outcome and the quorum threshold it was held to
+fn tally_outcome(proposal: &Proposal) -> (ProposalOutcome, u64) {
+    let total_votes = proposal.yes_votes + proposal.no_votes;
+    let total_voting_power = proposal.quorum_base;
+    
+    let quorum_threshold = (total_voting_power as u128)
+        .checked_mul(proposal.quorum_percentage as u128)
+        .unwrap()
+        .checked_div(100)
+        .unwrap() as u64;
+    
+    // Too few distinct voters fails quorum even if enough power turned out
+    let outcome = if total_votes < quorum_threshold || proposal.voter_count < proposal.min_unique_voters {
+        ProposalOutcome::QuorumFailed
+    } else if proposal.yes_votes > proposal.no_votes {
+        ProposalOutcome::Succeeded
+    } else {
+        ProposalOutcome::Defeated
+    };
+    
+    (outcome, quorum_threshold)
+}
+
+// Helper function to get the total a proposal's quorum and turnout are measured against.
+// Council proposals use council seats, one-person-one-vote proposals the number of stakers,
+// and snapshot proposals the snapshot's total balance, rather than staked voting power.
+fn quorum_base(governance: &Governance, proposal: &Proposal, snapshot_total_balance: Option<u64>) -> u64 {
+    if proposal.council_vote {
+        governance.council_size
+    } else if proposal.one_person_one_vote {
+        governance.staker_count
+    } else if let Some(snapshot_total_balance) = snapshot_total_balance {
+        snapshot_total_balance
+    } else {
+        governance.total_voting_power
+    }
+}
+
+// Helper function to fold a freshly finalized proposal into the realm's stats
+fn record_finalization(
+    governance_stats: &mut Account<GovernanceStats>,
+    proposal: &Account<Proposal>,
+    finalized_at: i64,
+) {
+    match proposal.outcome {
+        ProposalOutcome::Succeeded => {
+            governance_stats.proposals_passed = governance_stats.proposals_passed.checked_add(1).unwrap();
+        }
+        ProposalOutcome::Defeated => {
+            governance_stats.proposals_defeated = governance_stats.proposals_defeated.checked_add(1).unwrap();
+        }
+        ProposalOutcome::QuorumFailed => {
+            governance_stats.proposals_quorum_failed =
+                governance_stats.proposals_quorum_failed.checked_add(1).unwrap();
+        }
+        _ => {}
+    }
+    
+    // Turnout in basis points of the proposal's quorum base
+    let base = proposal.quorum_base;
+    let turnout_bps = if base == 0 {
+        0
+    } else {
+        ((proposal.yes_votes + proposal.no_votes) as u128)
+            .checked_mul(10000)
+            .unwrap()
+            .checked_div(base as u128)
+            .unwrap()
+            .min(10000) as u64
+    };
+    
+    governance_stats.proposals_finalized = governance_stats.proposals_finalized.checked_add(1).unwrap();
+    governance_stats.total_turnout_bps = governance_stats.total_turnout_bps.checked_add(turnout_bps).unwrap();
+    governance_stats.average_turnout_bps =
+        governance_stats.total_turnout_bps / governance_stats.proposals_finalized;
+    governance_stats.last_finalized_proposal = proposal.key();
+    governance_stats.last_finalized_at = finalized_at;
+}
+
+// Helper function to emit the event matching a freshly finalized outcome
+fn emit_outcome_event(proposal: &Account<Proposal>, quorum_threshold: u64, timestamp: i64) {
+    match proposal.outcome {
+        ProposalOutcome::Succeeded => emit!(ProposalSucceededEvent {
+            proposal: proposal.key(),
+            yes_votes: proposal.yes_votes,
+            no_votes: proposal.no_votes,
+            voter_count: proposal.voter_count,
+            quorum_threshold,
+            finalized_at: timestamp,
+        }),
+        ProposalOutcome::Defeated => emit!(ProposalDefeatedEvent {
+            proposal: proposal.key(),
+            yes_votes: proposal.yes_votes,
+            no_votes: proposal.no_votes,
+            voter_count: proposal.voter_count,
+            quorum_threshold,
+            finalized_at: timestamp,
+        }),
+        ProposalOutcome::QuorumFailed => emit!(ProposalQuorumFailedEvent {
+            proposal: proposal.key(),
+            yes_votes: proposal.yes_votes,
+            no_votes: proposal.no_votes,
+            voter_count: proposal.voter_count,
+            quorum_threshold,
+            finalized_at: timestamp,
+        }),
+        ProposalOutcome::Pending | ProposalOutcome::Expired | ProposalOutcome::Vetoed => {}
+    }
+}
+
+// Helper function to record a freshly finalized proposal in its result oracle account
+fn write_proposal_result(
+    proposal_result: &mut Account<ProposalResult>,
+    proposal: &Account<Proposal>,
+    quorum_threshold: u64,
+    finalized_at: i64,
+    bump: u8,
+) {
+    proposal_result.proposal = proposal.key();
+    proposal_result.governance = proposal.governance;
+    proposal_result.proposal_id = proposal.proposal_id;
+    proposal_result.proposal_type = proposal.proposal_type;
+    proposal_result.outcome = proposal.outcome;
+    proposal_result.yes_votes = proposal.yes_votes;
+    proposal_result.no_votes = proposal.no_votes;
+    proposal_result.quorum_threshold = quorum_threshold;
+    proposal_result.executed = proposal.executed;
+    proposal_result.finalized_at = finalized_at;
+    proposal_result.executed_at = 0;
+    proposal_result.bump = bump;
+}
+
+// Helper function to check whether a proposal can no longer be executed (a zero window never expires)
+fn is_execution_window_expired(governance: &Governance, proposal: &Proposal, now: i64) -> bool {
+    governance.execution_window > 0
+        && now >= proposal.voting_ends_at + governance.execution_delay + governance.execution_window
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Governance::LEN,
+        seeds = [b"governance".as_ref(), token_mint.key().as_ref()],
+        bump
+    )]
+    pub governance: Account<'info, Governance>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + VotingPowerRegistry::LEN,
+        seeds = [
+            b"voting_power_registry".as_ref(),
+            governance.key().as_ref(),
+            token_mint.key().as_ref()
+        ],
+        bump
+    )]
+    pub voting_power_registry: Account<'info, VotingPowerRegistry>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ProposalSequence::LEN,
+        seeds = [b"proposal_sequence".as_ref(), governance.key().as_ref()],
+        bump
+    )]
+    pub proposal_sequence: Account<'info, ProposalSequence>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + GovernanceStats::LEN,
+        seeds = [b"governance_stats".as_ref(), governance.key().as_ref()],
+        bump
+    )]
+    pub governance_stats: Account<'info, GovernanceStats>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub token_mint: Account<'info, Mint>,
+    
+    /// Treasury account that holds governance-controlled funds
+    pub treasury: Account<'info, TokenAccount>,
+    
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct ReserveProposalId<'info> {
+    pub governance: Account<'info, Governance>,
+    
+    #[account(
+        mut,
+        seeds = [b"proposal_sequence".as_ref(), governance.key().as_ref()],
+        bump = proposal_sequence.bump,
+    )]
+    pub proposal_sequence: Account<'info, ProposalSequence>,
+    
+    #[account(
+        init,
+        payer = proposer,
+        space = 8 + ProposalIdReservation::LEN,
+        seeds = [
+            b"proposal_id_reservation".as_ref(),
+            governance.key().as_ref(),
+            proposer.key().as_ref()
+        ],
+        bump
+    )]
+    pub reservation: Account<'info, ProposalIdReservation>,
+    
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct CreateProposal<'info> {
+    #[account(mut)]
+    pub governance: Account<'info, Governance>,
+    
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [
+            b"proposal_id_reservation".as_ref(),
+            governance.key().as_ref(),
+            proposer.key().as_ref()
+        ],
+        bump,
+    )]
+    pub reservation: Account<'info, ProposalIdReservation>,
+    
+    #[account(
+        init,
+        payer = proposer,
+        space = 8 + Proposal::LEN,
+        seeds = [
+            b"proposal".as_ref(),
+            governance.key().as_ref(),
+            &reservation.proposal_id.to_le_bytes()
+        ],
+        bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    #[account(
+        mut,
+        seeds = [b"governance_stats".as_ref(), governance.key().as_ref()],
+        bump = governance_stats.bump,
+    )]
+    pub governance_stats: Account<'info, GovernanceStats>,
+    
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    
+    #[account(
+        constraint = proposer_token_account.mint == governance.token_mint,
+        constraint = proposer_token_account.owner == proposer.key(),
+    )]
+    pub proposer_token_account: Account<'info, TokenAccount>,
+    
+    /// Optional category whose rules and quorum apply to this proposal
+    #[account(
+        constraint = category.governance == governance.key(),
+    )]
+    pub category: Option<Account<'info, ProposalCategory>>,
+    
+    /// Optional balance snapshot that makes this a token holder vote
+    #[account(
+        constraint = snapshot.governance == governance.key(),
+    )]
+    pub snapshot: Option<Account<'info, BalanceSnapshot>>,
+    
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct CastVote<'info> {
+    #[account(mut)]
+    pub governance: Account<'info, Governance>,
+    
+    #[account(
+        mut,
+        constraint = proposal.governance == governance.key(),
+        constraint = !proposal.cancelled,
+        constraint = !proposal.executed,
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    
+    #[account(
+        init_if_needed,
+        payer = voter,
+        space = 8 + VoterVote::LEN,
+        seeds = [
+            b"voter_vote".as_ref(),
+            proposal.key().as_ref(),
+            voter.key().as_ref()
+        ],
+        bump
+    )]
+    pub voter_vote: Account<'info, VoterVote>,
+    
+    #[account(
+        init_if_needed,
+        payer = voter,
+        space = 8 + VoterRecord::LEN,
+        seeds = [
+            b"voter_record".as_ref(),
+            governance.key().as_ref(),
+            voter.key().as_ref()
+        ],
+        bump
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+    
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+    
+    // remaining_accounts: the voter's VoterPower account for each voting mint they hold power in,
+    // then the VotingPowerRegistry of each in the same order (writable if the power has decayed)
+}
+
+#[derive(Accounts)]
+pub struct DelegateVotingPower<'info> {
+    pub governance: Account<'info, Governance>,
+    
+    #[account(
+        init_if_needed,
+        payer = delegator,
+        space = 8 + Delegation::LEN,
+        seeds = [
+            b"delegation".as_ref(),
+            governance.key().as_ref(),
+            delegator.key().as_ref()
+        ],
+        bump
+    )]
+    pub delegation: Account<'info, Delegation>,
+    
+    #[account(mut)]
+    pub delegator: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeDelegation<'info> {
+    #[account(
+        mut,
+        close = delegator,
+        seeds = [
+            b"delegation".as_ref(),
+            delegation.governance.as_ref(),
+            delegator.key().as_ref()
+        ],
+        bump = delegation.bump,
+    )]
+    pub delegation: Account<'info, Delegation>,
+    
+    #[account(mut)]
+    pub delegator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CastDelegatedVote<'info> {
+    pub governance: Account<'info, Governance>,
+    
+    #[account(
+        mut,
+        constraint = proposal.governance == governance.key(),
+        constraint = !proposal.cancelled,
+        constraint = !proposal.executed,
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    #[account(
+        seeds = [
+            b"delegation".as_ref(),
+            governance.key().as_ref(),
+            delegation.delegator.as_ref()
+        ],
+        bump = delegation.bump,
+        constraint = delegation.delegate == delegate.key(),
+    )]
+    pub delegation: Account<'info, Delegation>,
+    
+    #[account(mut)]
+    pub delegate: Signer<'info>,
+    
+    #[account(
+        init_if_needed,
+        payer = delegate,
+        space = 8 + VoterVote::LEN,
+        seeds = [
+            b"voter_vote".as_ref(),
+            proposal.key().as_ref(),
+            delegation.delegator.as_ref()
+        ],
+        bump
+    )]
+    pub voter_vote: Account<'info, VoterVote>,
+    
+    #[account(
+        init_if_needed,
+        payer = delegate,
+        space = 8 + VoterRecord::LEN,
+        seeds = [
+            b"voter_record".as_ref(),
+            governance.key().as_ref(),
+            delegation.delegator.as_ref()
+        ],
+        bump
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+    
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+    
+    // remaining_accounts: the delegator's VoterPower account for each voting mint
+}
+
+#[derive(Accounts)]
+#[instruction(session_key: Pubkey)]
+pub struct CreateVotingSession<'info> {
+    pub governance: Account<'info, Governance>,
+    
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + VotingSession::LEN,
+        seeds = [
+            b"voting_session".as_ref(),
+            governance.key().as_ref(),
+            owner.key().as_ref(),
+            session_key.as_ref()
+        ],
+        bump
+    )]
+    pub voting_session: Account<'info, VotingSession>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeVotingSession<'info> {
+    #[account(
+        mut,
+        close = owner,
+        seeds = [
+            b"voting_session".as_ref(),
+            voting_session.governance.as_ref(),
+            owner.key().as_ref(),
+            voting_session.session_key.as_ref()
+        ],
+        bump = voting_session.bump,
+    )]
+    pub voting_session: Account<'info, VotingSession>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CastSessionVote<'info> {
+    pub governance: Account<'info, Governance>,
+    
+    #[account(
+        mut,
+        constraint = proposal.governance == governance.key(),
+        constraint = !proposal.cancelled,
+        constraint = !proposal.executed,
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    #[account(
+        seeds = [
+            b"voting_session".as_ref(),
+            governance.key().as_ref(),
+            voting_session.owner.as_ref(),
+            session_key.key().as_ref()
+        ],
+        bump = voting_session.bump,
+    )]
+    pub voting_session: Account<'info, VotingSession>,
+    
+    #[account(mut)]
+    pub session_key: Signer<'info>,
+    
+    #[account(
+        init_if_needed,
+        payer = session_key,
+        space = 8 + VoterVote::LEN,
+        seeds = [
+            b"voter_vote".as_ref(),
+            proposal.key().as_ref(),
+            voting_session.owner.as_ref()
+        ],
+        bump
+    )]
+    pub voter_vote: Account<'info, VoterVote>,
+    
+    #[account(
+        init_if_needed,
+        payer = session_key,
+        space = 8 + VoterRecord::LEN,
+        seeds = [
+            b"voter_record".as_ref(),
+            governance.key().as_ref(),
+            voting_session.owner.as_ref()
+        ],
+        bump
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+    
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+    
+    // remaining_accounts: the owner's VoterPower account for each voting mint
+}
+
+#[derive(Accounts)]
+pub struct CastSnapshotVote<'info> {
+    pub governance: Account<'info, Governance>,
+    
+    #[account(
+        mut,
+        constraint = proposal.governance == governance.key(),
+        constraint = !proposal.cancelled,
+        constraint = !proposal.executed,
+        constraint = proposal.snapshot == snapshot.key() @ GovernanceError::NotSnapshotProposal,
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    pub snapshot: Account<'info, BalanceSnapshot>,
+    
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    
+    #[account(
+        init_if_needed,
+        payer = voter,
+        space = 8 + VoterVote::LEN,
+        seeds = [
+            b"voter_vote".as_ref(),
+            proposal.key().as_ref(),
+            voter.key().as_ref()
+        ],
+        bump
+    )]
+    pub voter_vote: Account<'info, VoterVote>,
+    
+    #[account(
+        init_if_needed,
+        payer = voter,
+        space = 8 + VoterRecord::LEN,
+        seeds = [
+            b"voter_record".as_ref(),
+            governance.key().as_ref(),
+            voter.key().as_ref()
+        ],
+        bump
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+    
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct CastCouncilVote<'info> {
+    pub governance: Account<'info, Governance>,
+    
+    #[account(
+        mut,
+        constraint = proposal.governance == governance.key(),
+        constraint = !proposal.cancelled,
+        constraint = !proposal.executed,
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    
+    #[account(
+        init_if_needed,
+        payer = voter,
+        space = 8 + VoterVote::LEN,
+        seeds = [
+            b"voter_vote".as_ref(),
+            proposal.key().as_ref(),
+            voter.key().as_ref()
+        ],
+        bump
+    )]
+    pub voter_vote: Account<'info, VoterVote>,
+    
+    #[account(
+        init_if_needed,
+        payer = voter,
+        space = 8 + VoterRecord::LEN,
+        seeds = [
+            b"voter_record".as_ref(),
+            governance.key().as_ref(),
+            voter.key().as_ref()
+        ],
+        bump
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+    
+    #[account(
+        constraint = nft_token_account.owner == voter.key(),
+        constraint = nft_token_account.amount == 1,
+    )]
+    pub nft_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        constraint = nft_metadata.mint == nft_token_account.mint,
+    )]
+    pub nft_metadata: Account<'info, MetadataAccount>,
+    
+    #[account(
+        init_if_needed,
+        payer = voter,
+        space = 8 + CouncilVote::LEN,
+        seeds = [
+            b"council_vote".as_ref(),
+            proposal.key().as_ref(),
+            nft_token_account.mint.as_ref()
+        ],
+        bump
+    )]
+    pub council_vote: Account<'info, CouncilVote>,
+    
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteProposal<'info> {
+    #[account(mut)]
+    pub governance: Account<'info, Governance>,
+    
+    #[account(
+        mut,
+        constraint = proposal.governance == governance.key(),
+        constraint = !proposal.cancelled,
+        constraint = !proposal.executed,
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    #[account(
+        mut,
+        seeds = [b"governance_stats".as_ref(), governance.key().as_ref()],
+        bump = governance_stats.bump,
+    )]
+    pub governance_stats: Account<'info, GovernanceStats>,
+    
+    #[account(mut)]
+    pub executor: Signer<'info>,
+    
+    #[account(
+        init_if_needed,
+        payer = executor,
+        space = 8 + ProposalResult::LEN,
+        seeds = [b"proposal_result".as_ref(), proposal.key().as_ref()],
+        bump
+    )]
+    pub proposal_result: Account<'info, ProposalResult>,
+    
+    #[account(
+        mut,
+        constraint = treasury.key() == governance.treasury,
+        constraint = treasury.owner == governance.key(),
+    )]
+    pub treasury: Account<'info, TokenAccount>,
+    
+    /// Receives the execution bounty
+    #[account(
+        mut,
+        constraint = executor_token_account.mint == governance.token_mint,
+    )]
+    pub executor_token_account: Account<'info, TokenAccount>,
+    
+    /// The proposal's milestone schedule, required if it has one
+    #[account(
+        mut,
+        seeds = [b"milestone_schedule".as_ref(), proposal.key().as_ref()],
+        bump = milestone_schedule.bump,
+    )]
+    pub milestone_schedule: Option<Account<'info, MilestoneSchedule>>,
+    
+    /// Receives a treasury withdrawal paid out at once
+    #[account(mut)]
+    pub recipient_token_account: Option<Account<'info, TokenAccount>>,
+    
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SimulateExecution<'info> {
+    pub governance: Account<'info, Governance>,
+    
+    #[account(
+        constraint = proposal.governance == governance.key(),
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    /// Needed to check execution item targets
+    #[account(
+        seeds = [b"program_allowlist".as_ref(), governance.key().as_ref()],
+        bump = program_allowlist.bump,
+    )]
+    pub program_allowlist: Option<Account<'info, ProgramAllowlist>>,
+    
+    /// The proposal's milestone schedule, if it has one
+    #[account(
+        seeds = [b"milestone_schedule".as_ref(), proposal.key().as_ref()],
+        bump = milestone_schedule.bump,
+    )]
+    pub milestone_schedule: Option<Account<'info, MilestoneSchedule>>,
+    
+    // remaining_accounts: the proposal's execution items, in order
+}
+
+#[derive(Accounts)]
+pub struct FinalizeProposal<'info> {
+    pub governance: Account<'info, Governance>,
+    
+    #[account(
+        mut,
+        constraint = proposal.governance == governance.key(),
+        constraint = !proposal.cancelled,
+        constraint = !proposal.executed,
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + ProposalResult::LEN,
+        seeds = [b"proposal_result".as_ref(), proposal.key().as_ref()],
+        bump
+    )]
+    pub proposal_result: Account<'info, ProposalResult>,
+    
+    #[account(
+        mut,
+        seeds = [b"governance_stats".as_ref(), governance.key().as_ref()],
+        bump = governance_stats.bump,
+    )]
+    pub governance_stats: Account<'info, GovernanceStats>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SweepExpiredProposals<'info> {
+    pub governance: Account<'info, Governance>,
+    
+    #[account(
+        mut,
+        seeds = [b"governance_stats".as_ref(), governance.key().as_ref()],
+        bump = governance_stats.bump,
+    )]
+    pub governance_stats: Account<'info, GovernanceStats>,
+    
+    // Pays for the result accounts of proposals the sweep finalizes
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+    
+    // remaining_accounts: writable (proposal, proposal_result) pairs to sweep; for a proposal
+    // that was never finalized, its empty proposal_result PDA
+}
+
+#[derive(Accounts)]
+pub struct ClaimParticipationBadge<'info> {
+    pub proposal: Account<'info, Proposal>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"voter_vote".as_ref(),
+            proposal.key().as_ref(),
+            voter_vote.voter.as_ref()
+        ],
+        bump,
+    )]
+    pub voter_vote: Account<'info, VoterVote>,
+    
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + ParticipationBadge::LEN,
+        seeds = [
+            b"participation_badge".as_ref(),
+            proposal.governance.as_ref(),
+            voter_vote.voter.as_ref()
+        ],
+        bump
+    )]
+    pub participation_badge: Account<'info, ParticipationBadge>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseVoteAccount<'info> {
+    #[account(
+        constraint = proposal.key() == voter_vote.proposal,
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    #[account(
+        mut,
+        close = voter,
+        seeds = [
+            b"voter_vote".as_ref(),
+            proposal.key().as_ref(),
+            voter.key().as_ref()
+        ],
+        bump
+    )]
+    pub voter_vote: Account<'info, VoterVote>,
+    
+    /// Voter who paid for the vote account and receives the remaining rent
+    #[account(mut)]
+    pub voter: SystemAccount<'info>,
+    
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(index: u16)]
+pub struct AddExecutionItem<'info> {
+    #[account(
+        mut,
+        constraint = proposal.proposer == proposer.key(),
+        constraint = !proposal.cancelled,
+        constraint = !proposal.executed,
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    #[account(
+        init,
+        payer = proposer,
+        space = 8 + ExecutionItem::LEN,
+        seeds = [
+            b"execution_item".as_ref(),
+            proposal.key().as_ref(),
+            &index.to_le_bytes()
+        ],
+        bump
+    )]
+    pub execution_item: Account<'info, ExecutionItem>,
+    
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteItem<'info> {
+    #[account(mut)]
+    pub governance: Account<'info, Governance>,
+    
+    #[account(
+        mut,
+        constraint = proposal.governance == governance.key(),
+        constraint = !proposal.cancelled,
+        constraint = !proposal.executed,
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"execution_item".as_ref(),
+            proposal.key().as_ref(),
+            &execution_item.index.to_le_bytes()
+        ],
+        bump = execution_item.bump,
+        constraint = execution_item.proposal == proposal.key(),
+    )]
+    pub execution_item: Account<'info, ExecutionItem>,
+    
+    #[account(
+        seeds = [b"program_allowlist".as_ref(), governance.key().as_ref()],
+        bump = program_allowlist.bump,
+    )]
+    pub program_allowlist: Account<'info, ProgramAllowlist>,
+    
+    #[account(
+        mut,
+        seeds = [b"proposal_result".as_ref(), proposal.key().as_ref()],
+        bump = proposal_result.bump,
+    )]
+    pub proposal_result: Account<'info, ProposalResult>,
+    
+    /// Read before and after the item runs to charge its outflow against the treasury cap
+    #[account(
+        constraint = treasury.key() == governance.treasury,
+    )]
+    pub treasury: Account<'info, TokenAccount>,
+    
+    pub executor: Signer<'info>,
+    
+    // remaining_accounts: the accounts referenced by the item's instruction, plus its target program
+}
+
+#[derive(Accounts)]
+pub struct CreateMilestoneSchedule<'info> {
+    pub governance: Account<'info, Governance>,
+    
+    #[account(
+        mut,
+        constraint = proposal.governance == governance.key(),
+        constraint = proposal.proposer == proposer.key(),
+        constraint = !proposal.cancelled,
+        constraint = !proposal.executed,
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    #[account(
+        init,
+        payer = proposer,
+        space = 8 + MilestoneSchedule::LEN,
+        seeds = [b"milestone_schedule".as_ref(), proposal.key().as_ref()],
+        bump
+    )]
+    pub milestone_schedule: Account<'info, MilestoneSchedule>,
+    
+    /// Receives each released tranche; must be the payload's recipient
+    #[account(
+        constraint = recipient_token_account.mint == governance.token_mint,
+    )]
+    pub recipient_token_account: Account<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseMilestone<'info> {
+    #[account(mut)]
+    pub governance: Account<'info, Governance>,
+    
+    #[account(
+        constraint = proposal.governance == governance.key(),
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    #[account(
+        mut,
+        seeds = [b"milestone_schedule".as_ref(), proposal.key().as_ref()],
+        bump = milestone_schedule.bump,
+    )]
+    pub milestone_schedule: Account<'info, MilestoneSchedule>,
+    
+    #[account(
+        mut,
+        constraint = treasury.key() == governance.treasury,
+        constraint = treasury.owner == governance.key(),
+    )]
+    pub treasury: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = recipient_token_account.key() == milestone_schedule.recipient,
+    )]
+    pub recipient_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        constraint = approver.key() == governance.guardian || approver.key() == governance.key(),
+    )]
+    pub approver: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigureCrossChain<'info> {
+    #[account(
+        constraint = governance.authority == authority.key(),
+    )]
+    pub governance: Account<'info, Governance>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + CrossChainConfig::LEN,
+        seeds = [b"cross_chain_config".as_ref(), governance.key().as_ref()],
+        bump
+    )]
+    pub cross_chain_config: Account<'info, CrossChainConfig>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct AttachCrossChainMessage<'info> {
+    pub governance: Account<'info, Governance>,
+    
+    #[account(
+        constraint = proposal.governance == governance.key(),
+        constraint = proposal.proposer == proposer.key(),
+        constraint = !proposal.cancelled,
+        constraint = !proposal.executed,
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    #[account(
+        init,
+        payer = proposer,
+        space = 8 + CrossChainMessage::LEN,
+        seeds = [b"cross_chain_message".as_ref(), proposal.key().as_ref()],
+        bump
+    )]
+    pub cross_chain_message: Account<'info, CrossChainMessage>,
+    
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct RelayCrossChainMessage<'info> {
+    pub governance: Account<'info, Governance>,
+    
+    #[account(
+        constraint = proposal.governance == governance.key(),
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    #[account(
+        mut,
+        seeds = [b"cross_chain_message".as_ref(), proposal.key().as_ref()],
+        bump = cross_chain_message.bump,
+    )]
+    pub cross_chain_message: Account<'info, CrossChainMessage>,
+    
+    #[account(
+        seeds = [b"cross_chain_config".as_ref(), governance.key().as_ref()],
+        bump = cross_chain_config.bump,
+    )]
+    pub cross_chain_config: Account<'info, CrossChainConfig>,
+    
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + ChainSequence::LEN,
+        seeds = [
+            b"chain_sequence".as_ref(),
+            governance.key().as_ref(),
+            &cross_chain_message.target_chain.to_le_bytes()
+        ],
+        bump
+    )]
+    pub chain_sequence: Account<'info, ChainSequence>,
+    
+    /// CHECK: Wormhole core bridge program, verified against the configured address
+    #[account(
+        constraint = wormhole_program.key() == cross_chain_config.wormhole_program @ GovernanceError::InvalidWormholeAccounts,
+    )]
+    pub wormhole_program: AccountInfo<'info>,
+    
+    /// CHECK: Wormhole bridge config, validated by the core bridge
+    #[account(mut)]
+    pub wormhole_bridge: AccountInfo<'info>,
+    
+    /// Fresh Wormhole message account, created by the core bridge
+    #[account(mut)]
+    pub wormhole_message: Signer<'info>,
+    
+    /// CHECK: Wormhole sequence account for the governance emitter, validated by the core bridge
+    #[account(mut)]
+    pub wormhole_sequence: AccountInfo<'info>,
+    
+    /// CHECK: Wormhole fee collector, validated by the core bridge
+    #[account(mut)]
+    pub wormhole_fee_collector: AccountInfo<'info>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub clock: Sysvar<'info, Clock>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeProgramAllowlist<'info> {
+    #[account(
+        constraint = authority.key() == governance.authority,
+    )]
+    pub governance: Account<'info, Governance>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ProgramAllowlist::LEN,
+        seeds = [b"program_allowlist".as_ref(), governance.key().as_ref()],
+        bump
+    )]
+    pub program_allowlist: Account<'info, ProgramAllowlist>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct SetProgramAllowed<'info> {
+    /// The governance PDA must sign, which only happens when an executed proposal invokes this instruction
+    #[account(signer)]
+    pub governance: Account<'info, Governance>,
+    
+    #[account(
+        mut,
+        seeds = [b"program_allowlist".as_ref(), governance.key().as_ref()],
+        bump = program_allowlist.bump,
+    )]
+    pub program_allowlist: Account<'info, ProgramAllowlist>,
+}
+
+#[derive(Accounts)]
+pub struct AssertProposalApproved<'info> {
+    pub governance: Account<'info, Governance>,
+    
+    #[account(
+        constraint = proposal.governance == governance.key(),
+    )]
+    pub proposal: Account<'info, Proposal>,
+}
+
+#[derive(Accounts)]
+pub struct GetParticipation<'info> {
+    pub governance: Account<'info, Governance>,
+    
+    #[account(
+        seeds = [
+            b"voter_record".as_ref(),
+            governance.key().as_ref(),
+            voter_record.voter.as_ref()
+        ],
+        bump = voter_record.bump,
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+}
+
+#[derive(Accounts)]
+pub struct VetoProposal<'info> {
+    pub governance: Account<'info, Governance>,
+    
+    #[account(
+        mut,
+        constraint = proposal.governance == governance.key(),
+        constraint = !proposal.cancelled,
+        constraint = !proposal.executed,
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    #[account(
+        constraint = vetoer.key() == governance.guardian || vetoer.key() == governance.key(),
+    )]
+    pub vetoer: Signer<'info>,
+    
+    /// Result oracle account, if the proposal was already finalized
+    #[account(
+        mut,
+        seeds = [b"proposal_result".as_ref(), proposal.key().as_ref()],
+        bump = proposal_result.bump,
+    )]
+    pub proposal_result: Option<Account<'info, ProposalResult>>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterSubDao<'info> {
+    #[account(
+        constraint = governance.authority == authority.key(),
+    )]
+    pub governance: Account<'info, Governance>,
+    
+    #[account(
+        constraint = parent_governance.authority == parent_authority.key(),
+    )]
+    pub parent_governance: Account<'info, Governance>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + RealmHierarchy::LEN,
+        seeds = [b"realm_hierarchy".as_ref(), governance.key().as_ref()],
+        bump
+    )]
+    pub realm_hierarchy: Account<'info, RealmHierarchy>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub parent_authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct ParentVetoProposal<'info> {
+    pub governance: Account<'info, Governance>,
+    
+    #[account(
+        mut,
+        constraint = proposal.governance == governance.key(),
+        constraint = !proposal.cancelled,
+        constraint = !proposal.executed,
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    #[account(
+        seeds = [b"realm_hierarchy".as_ref(), governance.key().as_ref()],
+        bump = realm_hierarchy.bump,
+        constraint = realm_hierarchy.parent_governance == parent_governance.key(),
+    )]
+    pub realm_hierarchy: Account<'info, RealmHierarchy>,
+    
+    /// The parent realm's governance PDA, signing through an executed parent proposal
+    #[account(signer)]
+    pub parent_governance: Account<'info, Governance>,
+    
+    /// Result oracle account, if the proposal was already finalized
+    #[account(
+        mut,
+        seeds = [b"proposal_result".as_ref(), proposal.key().as_ref()],
+        bump = proposal_result.bump,
+    )]
+    pub proposal_result: Option<Account<'info, ProposalResult>>,
+}
+
+#[derive(Accounts)]
+pub struct CancelProposal<'info> {
+    pub governance: Account<'info, Governance>,
+    
+    #[account(
+        mut,
+        constraint = proposal.governance == governance.key(),
+        constraint = !proposal.cancelled,
+        constraint = !proposal.executed,
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    #[account(
+        constraint = authority.key() == proposal.proposer || authority.key() == governance.authority,
+    )]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateGovernance<'info> {
+    #[account(
+        mut,
+        constraint = authority.key() == governance.authority,
+    )]
+    pub governance: Account<'info, Governance>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    #[account(
+        mut,
+        constraint = authority.key() == governance.authority || authority.key() == governance.guardian,
+    )]
+    pub governance: Account<'info, Governance>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(category_id: u16)]
+pub struct SetProposalCategory<'info> {
     #[account(
-        init,
-        payer = authority,
-        space = 8 + Governance::LEN,
-        seeds = [b"governance".as_ref(), token_mint.key().as_ref()],
-        bump
+        constraint = authority.key() == governance.authority,
     )]
     pub governance: Account<'info, Governance>,
     
     #[account(
-        init,
+        init_if_needed,
         payer = authority,
-        space = 8 + VotingPowerRegistry::LEN,
-        seeds = [b"voting_power_registry".as_ref(), governance.key().as_ref()],
+        space = 8 + ProposalCategory::LEN,
+        seeds = [
+            b"proposal_category".as_ref(),
+            governance.key().as_ref(),
+            &category_id.to_le_bytes()
+        ],
         bump
     )]
-    pub voting_power_registry: Account<'info, VotingPowerRegistry>,
+    pub category: Account<'info, ProposalCategory>,
     
     #[account(mut)]
     pub authority: Signer<'info>,
     
-    pub token_mint: Account<'info, Mint>,
-    
-    /// Treasury account that holds governance-controlled funds
-    pub treasury: Account<'info, TokenAccount>,
-    
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
 
 #[derive(Accounts)]
-pub struct CreateProposal<'info> {
-    #[account(mut)]
+#[instruction(snapshot_id: u64)]
+pub struct PostBalanceSnapshot<'info> {
     pub governance: Account<'info, Governance>,
     
+    // Executed proposal approving this snapshot
+    #[account(
+        constraint = proposal.governance == governance.key(),
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
     #[account(
         init,
-        payer = proposer,
-        space = 8 + Proposal::LEN,
+        payer = payer,
+        space = 8 + BalanceSnapshot::LEN,
         seeds = [
-            b"proposal".as_ref(),
+            b"balance_snapshot".as_ref(),
             governance.key().as_ref(),
-            &(governance.proposal_count + 1).to_le_bytes()
+            &snapshot_id.to_le_bytes()
         ],
         bump
     )]
-    pub proposal: Account<'info, Proposal>,
+    pub snapshot: Account<'info, BalanceSnapshot>,
     
     #[account(mut)]
-    pub proposer: Signer<'info>,
-    
-    #[account(
-        constraint = proposer_token_account.mint == governance.token_mint,
-        constraint = proposer_token_account.owner == proposer.key(),
-    )]
-    pub proposer_token_account: Account<'info, TokenAccount>,
+    pub payer: Signer<'info>,
     
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
 
 #[derive(Accounts)]
-pub struct CastVote<'info> {
+pub struct ConfigureCouncil<'info> {
+    #[account(
+        mut,
+        constraint = authority.key() == governance.authority,
+    )]
     pub governance: Account<'info, Governance>,
     
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AddVotingMint<'info> {
     #[account(
         mut,
-        constraint = proposal.governance == governance.key(),
-        constraint = !proposal.cancelled,
-        constraint = !proposal.executed,
+        constraint = authority.key() == governance.authority,
     )]
-    pub proposal: Account<'info, Proposal>,
-    
-    #[account(mut)]
-    pub voter: Signer<'info>,
+    pub governance: Account<'info, Governance>,
     
     #[account(
-        init_if_needed,
-        payer = voter,
-        space = 8 + VoterVote::LEN,
+        init,
+        payer = authority,
+        space = 8 + VotingPowerRegistry::LEN,
         seeds = [
-            b"voter_vote".as_ref(),
-            proposal.key().as_ref(),
-            voter.key().as_ref()
+            b"voting_power_registry".as_ref(),
+            governance.key().as_ref(),
+            mint.key().as_ref()
         ],
         bump
     )]
-    pub voter_vote: Account<'info, VoterVote>,
-    
-    #[account(
-        constraint = voting_power_registry.governance == governance.key(),
-    )]
     pub voting_power_registry: Account<'info, VotingPowerRegistry>,
     
+    pub mint: Account<'info, Mint>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
 
 #[derive(Accounts)]
-pub struct ExecuteProposal<'info> {
-    pub governance: Account<'info, Governance>,
-    
+pub struct SetRegistryStakingPool<'info> {
     #[account(
-        mut,
-        constraint = proposal.governance == governance.key(),
-        constraint = !proposal.cancelled,
-        constraint = !proposal.executed,
+        constraint = authority.key() == governance.authority,
     )]
-    pub proposal: Account<'info, Proposal>,
-    
-    #[account(mut)]
-    pub executor: Signer<'info>,
+    pub governance: Account<'info, Governance>,
     
     #[account(
-        constraint = voting_power_registry.governance == governance.key(),
+        mut,
+        seeds = [
+            b"voting_power_registry".as_ref(),
+            governance.key().as_ref(),
+            voting_power_registry.mint.as_ref()
+        ],
+        bump = voting_power_registry.bump,
     )]
     pub voting_power_registry: Account<'info, VotingPowerRegistry>,
     
-    pub system_program: Program<'info, System>,
+    pub authority: Signer<'info>,
 }
 
 #[derive(Accounts)]
-pub struct CancelProposal<'info> {
+pub struct ReportStakedVotingPower<'info> {
+    #[account(mut)]
     pub governance: Account<'info, Governance>,
     
     #[account(
         mut,
-        constraint = proposal.governance == governance.key(),
-        constraint = !proposal.cancelled,
-        constraint = !proposal.executed,
+        seeds = [
+            b"voting_power_registry".as_ref(),
+            governance.key().as_ref(),
+            voting_power_registry.mint.as_ref()
+        ],
+        bump = voting_power_registry.bump,
     )]
-    pub proposal: Account<'info, Proposal>,
+    pub voting_power_registry: Account<'info, VotingPowerRegistry>,
     
+    // The staking pool PDA, signing through the staking program
     #[account(
-        constraint = authority.key() == proposal.proposer || authority.key() == governance.authority,
+        constraint = voting_power_registry.staking_pool != Pubkey::default()
+            && staking_pool.key() == voting_power_registry.staking_pool @ GovernanceError::UnauthorizedStakingPool,
     )]
-    pub authority: Signer<'info>,
-    
-    pub system_program: Program<'info, System>,
+    pub staking_pool: Signer<'info>,
 }
 
 #[derive(Accounts)]
-pub struct UpdateGovernance<'info> {
+pub struct SetVotingMintWeight<'info> {
     #[account(
         mut,
         constraint = authority.key() == governance.authority,
     )]
     pub governance: Account<'info, Governance>,
     
+    #[account(
+        seeds = [
+            b"voting_power_registry".as_ref(),
+            governance.key().as_ref(),
+            voting_power_registry.mint.as_ref()
+        ],
+        bump = voting_power_registry.bump,
+    )]
+    pub voting_power_registry: Account<'info, VotingPowerRegistry>,
+    
     pub authority: Signer<'info>,
 }
 
 #[derive(Accounts)]
+#[instruction(voter: Pubkey)]
 pub struct RegisterVotingPower<'info> {
+    #[account(mut)]
+    pub governance: Account<'info, Governance>,
+    
     #[account(
         mut,
-        seeds = [b"voting_power_registry".as_ref(), voting_power_registry.governance.as_ref()],
+        seeds = [
+            b"voting_power_registry".as_ref(),
+            governance.key().as_ref(),
+            voting_power_registry.mint.as_ref()
+        ],
         bump = voting_power_registry.bump,
     )]
     pub voting_power_registry: Account<'info, VotingPowerRegistry>,
     
     #[account(
         init_if_needed,
-        payer = authority,
+        payer = payer,
         space = 8 + VoterPower::LEN,
         seeds = [
             b"voter_power".as_ref(),
@@ -606,13 +4431,153 @@ pub struct RegisterVotingPower<'info> {
     )]
     pub voter_power: Account<'info, VoterPower>,
     
+    /// The page holding the voter's entry, or the tail page for voters without one
+    #[account(
+        mut,
+        constraint = voting_power_page.load()?.registry == voting_power_registry.key(),
+    )]
+    pub voting_power_page: AccountLoader<'info, VotingPowerPage>,
+    
+    // The staking pool PDA, signing through the staking program
+    #[account(
+        constraint = voting_power_registry.staking_pool != Pubkey::default()
+            && staking_pool.key() == voting_power_registry.staking_pool @ GovernanceError::UnauthorizedStakingPool,
+    )]
+    pub staking_pool: Signer<'info>,
+    
     #[account(mut)]
-    pub authority: Signer<'info>,
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct AddVotingPowerPage<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"voting_power_registry".as_ref(),
+            voting_power_registry.governance.as_ref(),
+            voting_power_registry.mint.as_ref()
+        ],
+        bump = voting_power_registry.bump,
+    )]
+    pub voting_power_registry: Account<'info, VotingPowerRegistry>,
+    
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + VotingPowerPage::LEN,
+        seeds = [
+            b"voting_power_page".as_ref(),
+            voting_power_registry.key().as_ref(),
+            &voting_power_registry.page_count.to_le_bytes()
+        ],
+        bump
+    )]
+    pub voting_power_page: AccountLoader<'info, VotingPowerPage>,
+    
+    /// Current tail page, required unless this is the registry's first page
+    #[account(mut)]
+    pub previous_page: Option<AccountLoader<'info, VotingPowerPage>>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
     
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
 
+#[derive(Accounts)]
+pub struct RemoveVoterEntry<'info> {
+    pub voting_power_registry: Account<'info, VotingPowerRegistry>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"voter_power".as_ref(),
+            voting_power_registry.key().as_ref(),
+            voter_power.voter.as_ref()
+        ],
+        bump,
+    )]
+    pub voter_power: Account<'info, VoterPower>,
+    
+    #[account(
+        mut,
+        constraint = voting_power_page.load()?.registry == voting_power_registry.key(),
+    )]
+    pub voting_power_page: AccountLoader<'info, VotingPowerPage>,
+}
+
+#[derive(Accounts)]
+pub struct CompactVotingPowerPages<'info> {
+    pub voting_power_registry: Account<'info, VotingPowerRegistry>,
+    
+    #[account(
+        mut,
+        constraint = target_page.load()?.registry == voting_power_registry.key(),
+    )]
+    pub target_page: AccountLoader<'info, VotingPowerPage>,
+    
+    #[account(
+        mut,
+        constraint = tail_page.load()?.registry == voting_power_registry.key(),
+    )]
+    pub tail_page: AccountLoader<'info, VotingPowerPage>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"voter_power".as_ref(),
+            voting_power_registry.key().as_ref(),
+            moved_voter_power.voter.as_ref()
+        ],
+        bump,
+    )]
+    pub moved_voter_power: Account<'info, VoterPower>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateVoterActivity<'info> {
+    #[account(mut)]
+    pub governance: Account<'info, Governance>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"voting_power_registry".as_ref(),
+            governance.key().as_ref(),
+            voting_power_registry.mint.as_ref()
+        ],
+        bump = voting_power_registry.bump,
+    )]
+    pub voting_power_registry: Account<'info, VotingPowerRegistry>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"voter_power".as_ref(),
+            voting_power_registry.key().as_ref(),
+            voter_power.voter.as_ref()
+        ],
+        bump,
+    )]
+    pub voter_power: Account<'info, VoterPower>,
+    
+    /// CHECK: The voter's VoterRecord PDA, which is empty if they have never voted
+    #[account(
+        seeds = [
+            b"voter_record".as_ref(),
+            governance.key().as_ref(),
+            voter_power.voter.as_ref()
+        ],
+        bump,
+    )]
+    pub voter_record: UncheckedAccount<'info>,
+}
+
 #[account]
 pub struct Governance {
     pub authority: Pubkey,         // Admin authority
@@ -621,14 +4586,117 @@ pub struct Governance {
     pub min_proposal_tokens: u64,  // Minimum tokens required to create a proposal
     pub voting_period: i64,        // Voting period in seconds
     pub execution_delay: i64,      // Delay between voting end and execution in seconds
+    pub execution_window: i64,     // Seconds after the delay during which execution is allowed (0 = no expiry)
     pub quorum_percentage: u8,     // Percentage of total voting power required for quorum
+    pub execution_bounty: u64,     // Tokens paid from treasury to whoever executes a passed proposal
+    pub guardian: Pubkey,          // Emergency key allowed to pause governance
+    pub paused: bool,              // Whether proposal creation and execution are paused
     pub proposal_count: u64,       // Number of proposals created
-    pub total_voting_power: u64,   // Total voting power in the system
+    pub total_voting_power: u64,   // Total weighted voting power in the system
     pub bump: u8,                  // PDA bump
+    pub voting_mints: Vec<VotingMint>, // Mints that confer voting power and their weights
+    pub council_collection: Pubkey, // NFT collection whose holders form the council
+    pub council_size: u64,         // Number of council seats used for council quorum
+    pub min_unique_voters: u64,    // Distinct voters a proposal needs to pass (0 = no minimum)
+    pub staker_count: u64,         // Voters with power in the governance token
+    pub latest_proposal_id: u64,   // Highest ID of any created proposal
+    pub treasury_epoch_cap: u64,   // Most treasury withdrawals may pay out per trailing window (0 = no cap)
+    pub treasury_epoch_duration: i64, // Length of the trailing treasury withdrawal window in seconds
+    pub recent_treasury_withdrawals: [TreasuryWithdrawalRecord; 8], // Capped withdrawals, reused once outside the window
+    pub inactivity_window: u8,     // Proposals a voter may sit out before their power decays (0 = off)
+    pub inactivity_decay_bps: u16, // Share of an inactive voter's power left out of the totals
+}
+
+impl Governance {
+    pub const MAX_VOTING_MINTS: usize = 4;
+    pub const MAX_RECENT_TREASURY_WITHDRAWALS: usize = 8;
+    pub const LEN: usize = 32 + 32 + 32 + 8 + 8 + 8 + 8 + 1 + 8 + 32 + 1 + 8 + 8 + 1
+        + 4 + VotingMint::LEN * Self::MAX_VOTING_MINTS + 32 + 8 + 8 + 8 + 8 + 8
+        + TreasuryWithdrawalRecord::LEN * Self::MAX_RECENT_TREASURY_WITHDRAWALS + 1 + 2;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct TreasuryWithdrawalRecord {
+    pub amount: u64,                   // Amount paid out (0 = free slot)
+    pub withdrawn_at: i64,             // When it was paid out
+}
+
+impl TreasuryWithdrawalRecord {
+    pub const LEN: usize = 8 + 8;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct VotingMint {
+    pub mint: Pubkey,                  // Mint whose holders receive voting power
+    pub weight_bps: u16,               // Weight applied to this mint's power (10000 = 1x)
+}
+
+impl VotingMint {
+    pub const FULL_WEIGHT_BPS: u16 = 10000;
+    pub const MAX_WEIGHT_BPS: u16 = 30000; // Most a mint's power can be weighted (3x)
+    pub const LEN: usize = 32 + 2;
+}
+
+// Compact, fixed-layout record of a finalized proposal for other programs and services
+#[account]
+pub struct ProposalResult {
+    pub proposal: Pubkey,               // Proposal account
+    pub governance: Pubkey,             // Governance account
+    pub proposal_id: u64,               // Proposal ID
+    pub proposal_type: ProposalType,    // Type of proposal
+    pub outcome: ProposalOutcome,       // Final outcome
+    pub yes_votes: u64,                 // Final "yes" tally
+    pub no_votes: u64,                  // Final "no" tally
+    pub quorum_threshold: u64,          // Quorum the proposal was held to at finalization
+    pub executed: bool,                 // Whether the proposal has been fully executed
+    pub finalized_at: i64,              // Timestamp of finalization
+    pub executed_at: i64,               // Timestamp of execution (0 if not executed)
+    pub bump: u8,                       // PDA bump
+}
+
+impl ProposalResult {
+    pub const LEN: usize = 32 + 32 + 8 + 1 + 1 + 8 + 8 + 8 + 1 + 8 + 8 + 1;
+}
+
+#[account]
+pub struct GovernanceStats {
+    pub governance: Pubkey,               // Governance account
+    pub proposals_created: u64,           // Proposals created
+    pub proposals_finalized: u64,         // Proposals whose outcome has been tallied
+    pub proposals_passed: u64,            // Finalized proposals that succeeded
+    pub proposals_defeated: u64,          // Finalized proposals that were voted down
+    pub proposals_quorum_failed: u64,     // Finalized proposals that missed quorum
+    pub total_turnout_bps: u64,           // Sum of finalized proposals' turnout in basis points
+    pub average_turnout_bps: u64,         // Average turnout across finalized proposals
+    pub last_finalized_proposal: Pubkey,  // Most recently finalized proposal
+    pub last_finalized_at: i64,           // Timestamp of the most recent finalization
+    pub bump: u8,                         // PDA bump
+}
+
+impl GovernanceStats {
+    pub const LEN: usize = 32 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 32 + 8 + 1;
+}
+
+#[account]
+pub struct ProposalSequence {
+    pub governance: Pubkey,             // Governance account
+    pub next_proposal_id: u64,          // ID handed to the next reservation
+    pub bump: u8,                       // PDA bump
+}
+
+impl ProposalSequence {
+    pub const LEN: usize = 32 + 8 + 1;
+}
+
+#[account]
+pub struct ProposalIdReservation {
+    pub governance: Pubkey,             // Governance account
+    pub proposer: Pubkey,               // Proposer the ID was allocated to
+    pub proposal_id: u64,               // Allocated proposal ID
 }
 
-impl Governance {
-    pub const LEN: usize = 32 + 32 + 32 + 8 + 8 + 8 + 1 + 8 + 8 + 1;
+impl ProposalIdReservation {
+    pub const LEN: usize = 32 + 32 + 8;
 }
 
 #[account]
@@ -639,38 +4707,309 @@ pub struct Proposal {
     pub title: String,                  // Proposal title
     pub description: String,            // Proposal description
     pub proposal_type: ProposalType,    // Type of proposal
-    pub execution_payload: Vec<u8>,     // Data for execution
+    pub payload: ProposalPayload,       // Typed action to perform on execution
     pub created_at: i64,                // Timestamp when proposal was created
     pub voting_ends_at: i64,            // Timestamp when voting ends
     pub yes_votes: u64,                 // Number of "yes" votes
     pub no_votes: u64,                  // Number of "no" votes
     pub executed: bool,                 // Whether proposal has been executed
     pub cancelled: bool,                // Whether proposal has been cancelled
+    pub item_count: u16,                // Number of ordered execution items
+    pub items_added: u16,               // Execution items added so far
+    pub items_executed: u16,            // Execution items executed so far
+    pub execution_started: bool,        // Whether execution of the items has been approved
+    pub outcome: ProposalOutcome,       // Result recorded at finalization
+    pub council_vote: bool,             // Whether only council NFT holders vote, one vote each
+    pub category: Pubkey,               // Proposal category (default if uncategorized)
+    pub quorum_percentage: u8,          // Quorum this proposal is held to, fixed at creation
+    pub requested_amount: u64,          // Amount requested, checked against the category cap
+    pub snapshot: Pubkey,               // Balance snapshot for token holder votes (default if none)
+    pub quorum_base: u64,               // Total the quorum and turnout are measured against, fixed at creation
+    pub action_hash: [u8; 32],          // Hash of the privileged action this proposal authorizes
+    pub voter_count: u64,               // Number of distinct voters
+    pub min_unique_voters: u64,         // Distinct voters required to pass, fixed at creation
+    pub one_person_one_vote: bool,      // Whether each staker counts as exactly one vote
+    pub milestone_schedule: Pubkey,     // Tranche schedule for the payout (default if paid at once)
 }
 
 impl Proposal {
-    pub const LEN: usize = 32 + 32 + 8 + 100 + 1000 + 1 + 200 + 8 + 8 + 8 + 8 + 1 + 1;
+    pub const LEN: usize = 32 + 32 + 8 + 100 + 1000 + 1 + ProposalPayload::LEN + 8 + 8 + 8 + 8 + 1 + 1 + 2 + 2 + 2 + 1 + 1 + 1
+        + 32 + 1 + 8 + 32 + 8 + 32 + 8 + 8 + 1 + 32;
+}
+
+#[account]
+pub struct BalanceSnapshot {
+    pub governance: Pubkey,             // Governance account
+    pub snapshot_id: u64,               // Snapshot identifier
+    pub merkle_root: [u8; 32],          // Root of keccak(wallet || balance) leaves
+    pub total_balance: u64,             // Sum of all balances in the snapshot
+    pub snapshot_slot: u64,             // Slot the balances were taken at
+    pub bump: u8,                       // PDA bump
+}
+
+impl BalanceSnapshot {
+    pub const LEN: usize = 32 + 8 + 32 + 8 + 8 + 1;
+}
+
+#[account]
+pub struct ProposalCategory {
+    pub governance: Pubkey,             // Governance account
+    pub category_id: u16,               // Category identifier
+    pub name: String,                   // Category name, e.g. "Grants under 10k WCT"
+    pub proposal_type: ProposalType,    // Proposal type this category applies to
+    pub quorum_percentage: u8,          // Quorum for proposals in this category
+    pub max_amount: u64,                // Largest amount a proposal in this category may request
+    pub bump: u8,                       // PDA bump
+}
+
+impl ProposalCategory {
+    pub const MAX_NAME_LEN: usize = 64;
+    pub const LEN: usize = 32 + 2 + 4 + Self::MAX_NAME_LEN + 1 + 1 + 8 + 1;
+}
+
+#[account]
+pub struct ExecutionItem {
+    pub proposal: Pubkey,               // Proposal this item belongs to
+    pub index: u16,                     // Position in the execution order
+    pub payload: Vec<u8>,               // Data for execution
+    pub executed: bool,                 // Whether this item has been executed
+    pub bump: u8,                       // PDA bump
+}
+
+impl ExecutionItem {
+    pub const MAX_PAYLOAD_LEN: usize = 512;
+    pub const LEN: usize = 32 + 2 + 4 + Self::MAX_PAYLOAD_LEN + 1 + 1;
+}
+
+#[account]
+pub struct MilestoneSchedule {
+    pub proposal: Pubkey,               // Treasury withdrawal proposal being paid out
+    pub recipient: Pubkey,              // Token account receiving each tranche
+    pub tranche_amounts: Vec<u64>,      // Amount released at each milestone, in order
+    pub released_count: u8,             // Number of tranches released so far
+    pub released_amount: u64,           // Total released so far
+    pub activated: bool,                // Whether the proposal has been executed
+    pub bump: u8,                       // PDA bump
+}
+
+impl MilestoneSchedule {
+    pub const MAX_TRANCHES: usize = 12;
+    pub const LEN: usize = 32 + 32 + 4 + 8 * Self::MAX_TRANCHES + 1 + 8 + 1 + 1;
+}
+
+#[account]
+pub struct CrossChainConfig {
+    pub governance: Pubkey,             // Governance account
+    pub wormhole_program: Pubkey,       // Wormhole core bridge program
+    pub consistency_level: u8,          // Finality required before guardians sign messages
+    pub bump: u8,                       // PDA bump
+}
+
+impl CrossChainConfig {
+    pub const LEN: usize = 32 + 32 + 1 + 1;
+}
+
+#[account]
+pub struct CrossChainMessage {
+    pub proposal: Pubkey,               // Proposal authorizing the message
+    pub target_chain: u16,              // Wormhole chain ID of the receiving chain
+    pub target_address: [u8; 32],       // Receiving contract, left-padded to 32 bytes
+    pub payload: Vec<u8>,               // Message for the receiving contract
+    pub relayed: bool,                  // Whether the message has been posted
+    pub sequence: u64,                  // Per-chain sequence assigned when relayed
+    pub bump: u8,                       // PDA bump
+}
+
+impl CrossChainMessage {
+    pub const ENVELOPE_VERSION: u8 = 1;
+    pub const MAX_PAYLOAD_LEN: usize = 512;
+    pub const LEN: usize = 32 + 2 + 32 + 4 + Self::MAX_PAYLOAD_LEN + 1 + 8 + 1;
+}
+
+#[account]
+pub struct ChainSequence {
+    pub governance: Pubkey,             // Governance account
+    pub target_chain: u16,              // Wormhole chain ID
+    pub next_sequence: u64,             // Sequence assigned to the next message for this chain
+    pub bump: u8,                       // PDA bump
+}
+
+impl ChainSequence {
+    pub const LEN: usize = 32 + 2 + 8 + 1;
+}
+
+#[account]
+pub struct ProgramAllowlist {
+    pub governance: Pubkey,             // Governance account
+    pub programs: Vec<Pubkey>,          // Programs execution items may invoke
+    pub bump: u8,                       // PDA bump
+}
+
+impl ProgramAllowlist {
+    pub const MAX_PROGRAMS: usize = 16;
+    pub const LEN: usize = 32 + 4 + 32 * Self::MAX_PROGRAMS + 1;
+}
+
+// Instruction encoded in an execution item's payload
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ExecutionInstruction {
+    pub program_id: Pubkey,
+    pub accounts: Vec<ExecutionAccountMeta>,
+    pub data: Vec<u8>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ExecutionAccountMeta {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
 }
 
 #[account]
 pub struct VotingPowerRegistry {
     pub governance: Pubkey,            // Governance account
+    pub mint: Pubkey,                  // Mint this registry tracks power for
     pub total_voting_power: u64,       // Total voting power across all voters
     pub bump: u8,                      // PDA bump
+    pub page_count: u32,               // Number of chained voting power pages
+    pub staking_pool: Pubkey,          // Staking pool whose reported total is authoritative (default = none)
+    pub decayed_voting_power: u64,     // Power of inactive voters left out of the governance total
 }
 
 impl VotingPowerRegistry {
-    pub const LEN: usize = 32 + 8 + 1;
+    pub const LEN: usize = 32 + 32 + 8 + 1 + 4 + 32 + 8;
+    
+    // Power this registry contributes to the governance total, before the mint's weight
+    pub fn counted_voting_power(&self) -> u64 {
+        self.total_voting_power.saturating_sub(self.decayed_voting_power)
+    }
+}
+
+#[account(zero_copy)]
+pub struct VotingPowerPage {
+    pub registry: Pubkey,              // Registry this page belongs to
+    pub next_page: Pubkey,             // Next page in the chain (default if this is the tail)
+    pub total_voting_power: u64,       // Sum of voting power on this page
+    pub page_index: u32,               // Position in the chain
+    pub len: u16,                      // Slots in use up to and including the last occupied one
+    pub entry_count: u16,              // Occupied slots
+    pub entries: [VoterEntry; 128],    // Voter entries; empty slots have a default voter
+}
+
+impl VotingPowerPage {
+    pub const CAPACITY: usize = 128;
+    pub const LEN: usize = 32 + 32 + 8 + 4 + 2 + 2 + VoterEntry::LEN * Self::CAPACITY;
+    
+    // First unoccupied slot, if any
+    pub fn free_slot(&self) -> Option<usize> {
+        self.entries
+            .iter()
+            .position(|entry| entry.voter == Pubkey::default())
+    }
+    
+    // Write a voter's power into a slot, keeping the page totals in sync
+    pub fn set_entry(&mut self, slot: usize, voter: Pubkey, voting_power: u64) {
+        let entry = &mut self.entries[slot];
+        if entry.voter == Pubkey::default() {
+            self.entry_count += 1;
+        }
+        self.total_voting_power = self
+            .total_voting_power
+            .checked_sub(entry.voting_power)
+            .unwrap()
+            .checked_add(voting_power)
+            .unwrap();
+        entry.voter = voter;
+        entry.voting_power = voting_power;
+        self.len = self.len.max(slot as u16 + 1);
+    }
+    
+    // Empty a slot and trim trailing empty slots, returning the removed entry
+    pub fn clear_entry(&mut self, slot: usize) -> VoterEntry {
+        let entry = self.entries[slot];
+        self.entries[slot] = VoterEntry {
+            voter: Pubkey::default(),
+            voting_power: 0,
+        };
+        self.entry_count -= 1;
+        self.total_voting_power = self.total_voting_power.checked_sub(entry.voting_power).unwrap();
+        while self.len > 0 && self.entries[self.len as usize - 1].voter == Pubkey::default() {
+            self.len -= 1;
+        }
+        entry
+    }
+}
+
+#[zero_copy]
+pub struct VoterEntry {
+    pub voter: Pubkey,                 // Voter's public key
+    pub voting_power: u64,             // Voter's boosted, unweighted voting power
+}
+
+impl VoterEntry {
+    pub const LEN: usize = 32 + 8;
 }
 
 #[account]
 pub struct VoterPower {
     pub voter: Pubkey,                // Voter's public key
-    pub voting_power: u64,            // Voter's voting power
+    pub governance: Pubkey,           // Governance account
+    pub mint: Pubkey,                 // Mint this power was registered for
+    pub base_voting_power: u64,       // Power of the voter's registered positions before boosts
+    pub voting_power: u64,            // Voter's boosted, unweighted voting power
+    pub has_page_entry: bool,         // Whether the voter occupies a registry page slot
+    pub page_index: u32,              // Page holding the voter's entry
+    pub page_slot: u16,               // Slot of the voter's entry within that page
+    pub matured_voting_power: u64,    // Power that counts before matures_at
+    pub matures_at: i64,              // When the full voting_power starts to count
+    pub decayed_voting_power: u64,    // Power left out of the registry total while inactive
+    pub decayed_at: i64,              // When inactivity decay was applied (0 = not decayed)
+    pub activity_base_proposal_id: u64, // Latest proposal ID when the voter's inactivity window started
+    pub staking_pool: Pubkey,         // Staking pool PDA that attested the power
 }
 
 impl VoterPower {
-    pub const LEN: usize = 32 + 8;
+    pub const LEN: usize = 32 + 32 + 32 + 8 + 8 + 1 + 4 + 2 + 8 + 8 + 8 + 8 + 8 + 32;
+    
+    // Power usable for votes at `now`, leaving out increases still warming up
+    pub fn active_voting_power(&self, now: i64) -> u64 {
+        if now >= self.matures_at {
+            self.voting_power
+        } else {
+            self.voting_power.min(self.matured_voting_power)
+        }
+    }
+    
+    // Power a vote carries at `now`, leaving out the share lost to inactivity decay
+    pub fn effective_voting_power(&self, now: i64) -> u64 {
+        self.active_voting_power(now).saturating_sub(self.decayed_voting_power)
+    }
+    
+    // Replace a position's contribution. An increase from a position that matures after `now`
+    // waits until then, on top of any earlier wait; power that already counted keeps counting.
+    pub fn apply_position_change(
+        &mut self,
+        old_position_power: u64,
+        position_power: u64,
+        position_matures_at: i64,
+        now: i64,
+    ) {
+        let active_power = self.active_voting_power(now);
+        self.voting_power = self
+            .voting_power
+            .checked_sub(old_position_power)
+            .unwrap()
+            .checked_add(position_power)
+            .unwrap();
+        if position_power > old_position_power && position_matures_at > now {
+            self.matured_voting_power = active_power.min(self.voting_power);
+            self.matures_at = self.matures_at.max(position_matures_at);
+        } else {
+            self.matured_voting_power = (active_power + position_power)
+                .saturating_sub(old_position_power)
+                .min(self.voting_power);
+        }
+    }
 }
 
 #[account]
@@ -679,11 +5018,857 @@ pub struct VoterVote {
     pub proposal: Pubkey,             // Proposal being voted on
     pub vote: Vote,                   // Vote choice
     pub voting_power: u64,            // Voting power at time of vote
+    pub badge_claimed: bool,          // Whether this vote has been credited to a participation badge
+    pub cast_directly: bool,          // Whether the voter cast it themselves rather than a delegate or session key
 }
 
 impl VoterVote {
-    pub const LEN: usize = 32 + 32 + 1 + 8;
+    pub const LEN: usize = 32 + 32 + 1 + 8 + 1 + 1;
+}
+
+// Soulbound record of a voter's participation in finalized proposals; being a PDA of this
+// program it cannot be transferred, and other programs can gate perks on it directly
+#[account]
+pub struct ParticipationBadge {
+    pub governance: Pubkey,           // Governance account
+    pub voter: Pubkey,                // Badge holder
+    pub proposals_participated: u64,  // Finalized proposals the holder voted on
+    pub last_proposal: Pubkey,        // Most recently credited proposal
+    pub updated_at: i64,              // Timestamp of the last credit
+    pub bump: u8,                     // PDA bump
+}
+
+impl ParticipationBadge {
+    pub const LEN: usize = 32 + 32 + 8 + 32 + 8 + 1;
+}
+
+#[account]
+pub struct VoterRecord {
+    pub voter: Pubkey,                // Voter's public key
+    pub governance: Pubkey,           // Governance account
+    pub proposals_voted: u64,         // Number of distinct proposals voted on
+    pub total_power_used: u64,        // Sum of voting power cast across proposals
+    pub last_vote_at: i64,            // Timestamp of the most recent vote
+    pub bump: u8,                     // PDA bump
+    pub recent_votes: u64,            // Bit i set if the voter voted on proposal recent_votes_base_id - i
+    pub recent_votes_base_id: u64,    // Highest proposal ID the voter voted on
+}
+
+impl VoterRecord {
+    pub const PARTICIPATION_WINDOW: u32 = 64;
+    pub const LEN: usize = 32 + 32 + 8 + 8 + 8 + 1 + 8 + 8;
+    
+    // Mark a proposal as voted on in the recent votes bitmap
+    pub fn record_participation(&mut self, proposal_id: u64) {
+        if proposal_id > self.recent_votes_base_id {
+            let shift = proposal_id - self.recent_votes_base_id;
+            self.recent_votes = if shift >= Self::PARTICIPATION_WINDOW as u64 {
+                0
+            } else {
+                self.recent_votes << shift
+            };
+            self.recent_votes_base_id = proposal_id;
+        }
+        let offset = self.recent_votes_base_id - proposal_id;
+        if offset < Self::PARTICIPATION_WINDOW as u64 {
+            self.recent_votes |= 1 << offset;
+        }
+    }
+    
+    // Count votes on proposals with IDs in (latest_proposal_id - window, latest_proposal_id]
+    pub fn participation_count(&self, latest_proposal_id: u64, window: u8) -> u8 {
+        let oldest_id = latest_proposal_id.saturating_sub(window as u64 - 1);
+        (0..Self::PARTICIPATION_WINDOW as u64)
+            .filter(|offset| self.recent_votes & (1 << offset) != 0)
+            .filter(|offset| {
+                let proposal_id = self.recent_votes_base_id - offset;
+                proposal_id >= oldest_id && proposal_id <= latest_proposal_id
+            })
+            .count() as u8
+    }
+}
+
+#[account]
+pub struct VotingSession {
+    pub governance: Pubkey,           // Governance account
+    pub owner: Pubkey,                // Cold wallet whose power the session votes with
+    pub session_key: Pubkey,          // Hot wallet allowed to vote
+    pub created_at: i64,              // Timestamp the session was created
+    pub expires_at: i64,              // Timestamp after which the session can't vote
+    pub max_power: u64,               // Most voting power a single session vote may use
+    pub bump: u8,                     // PDA bump
+}
+
+impl VotingSession {
+    pub const LEN: usize = 32 + 32 + 32 + 8 + 8 + 8 + 1;
+}
+
+#[account]
+pub struct RealmHierarchy {
+    pub governance: Pubkey,           // Sub-DAO governance account
+    pub parent_governance: Pubkey,    // Parent governance that may veto its proposals
+    pub bump: u8,                     // PDA bump
+}
+
+impl RealmHierarchy {
+    pub const LEN: usize = 32 + 32 + 1;
+}
+
+#[account]
+pub struct Delegation {
+    pub governance: Pubkey,           // Governance account
+    pub delegator: Pubkey,            // Wallet whose power is delegated
+    pub delegate: Pubkey,             // Wallet allowed to vote with it
+    pub expires_at: i64,              // Expiry timestamp (0 = no expiry)
+    pub bump: u8,                     // PDA bump
+}
+
+impl Delegation {
+    pub const LEN: usize = 32 + 32 + 32 + 8 + 1;
+}
+
+#[account]
+pub struct CouncilVote {
+    pub proposal: Pubkey,             // Proposal being voted on
+    pub nft_mint: Pubkey,             // Council NFT used to vote
+    pub voter: Pubkey,                // Voter who used the NFT
 }
 
+impl CouncilVote {
+    pub const LEN: usize = 32 + 32 + 32;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ProposalType {
+    TreasuryWithdrawal,
+    ParameterChange,
+    Other,
+}
+
+// Typed action carried by a proposal; the variant must match the proposal's type
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
-pub enum
+pub enum ProposalPayload {
+    None,
+    TreasuryWithdrawal { recipient: Pubkey, amount: u64 },
+    // Change a token's metadata; the new values are bound by the proposal's action hash
+    UpdateTokenMetadata { mint: Pubkey, action_hash: [u8; 32] },
+    // Slash a staking position; the share taken is bound by the proposal's action hash
+    SlashStake { position: Pubkey, action_hash: [u8; 32] },
+}
+
+impl ProposalPayload {
+    pub const LEN: usize = 1 + 32 + 32;
+    
+    // Check that the payload fits the proposal type and its fields are well formed
+    pub fn validate(
+        &self,
+        proposal_type: ProposalType,
+        requested_amount: u64,
+        action_hash: [u8; 32],
+    ) -> Result<()> {
+        match (proposal_type, self) {
+            (ProposalType::TreasuryWithdrawal, ProposalPayload::TreasuryWithdrawal { recipient, amount }) => {
+                require!(*recipient != Pubkey::default(), GovernanceError::InvalidProposalPayload);
+                require!(
+                    *amount > 0 && *amount == requested_amount,
+                    GovernanceError::InvalidProposalPayload
+                );
+            }
+            // Parameter changes, mints and upgrades run as the proposal's execution items
+            (ProposalType::ParameterChange, ProposalPayload::None) => {}
+            (
+                ProposalType::Other,
+                ProposalPayload::UpdateTokenMetadata { mint, action_hash: metadata_hash },
+            ) => {
+                require!(
+                    *mint != Pubkey::default() && *metadata_hash == action_hash,
+                    GovernanceError::InvalidProposalPayload
+                );
+            }
+            (
+                ProposalType::Other,
+                ProposalPayload::SlashStake { position, action_hash: slash_hash },
+            ) => {
+                require!(
+                    *position != Pubkey::default() && *slash_hash == action_hash,
+                    GovernanceError::InvalidProposalPayload
+                );
+            }
+            (ProposalType::Other, ProposalPayload::None) => {}
+            _ => return err!(GovernanceError::PayloadTypeMismatch),
+        }
+        
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ProposalOutcome {
+    Pending,
+    Succeeded,
+    Defeated,
+    QuorumFailed,
+    Expired,
+    Vetoed,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Vote {
+    Yes,
+    No,
+    Abstain,
+}
+
+#[event]
+pub struct GovernanceInitializedEvent {
+    pub governance: Pubkey,
+    pub min_proposal_tokens: u64,
+    pub voting_period: i64,
+    pub execution_delay: i64,
+    pub execution_window: i64,
+    pub quorum_percentage: u8,
+    pub execution_bounty: u64,
+}
+
+#[event]
+pub struct ProposalIdReservedEvent {
+    pub governance: Pubkey,
+    pub proposer: Pubkey,
+    pub proposal_id: u64,
+}
+
+#[event]
+pub struct ProposalCreatedEvent {
+    pub proposal: Pubkey,
+    pub governance: Pubkey,
+    pub proposer: Pubkey,
+    pub proposal_id: u64,
+    pub title: String,
+    pub proposal_type: ProposalType,
+    pub voting_ends_at: i64,
+}
+
+#[event]
+pub struct VoteCastEvent {
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    pub vote: Vote,
+    pub voting_power: u64,
+}
+
+#[event]
+pub struct DelegationCreatedEvent {
+    pub governance: Pubkey,
+    pub delegator: Pubkey,
+    pub delegate: Pubkey,
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct DelegationRevokedEvent {
+    pub governance: Pubkey,
+    pub delegator: Pubkey,
+    pub delegate: Pubkey,
+    pub revoked_at: i64,
+}
+
+#[event]
+pub struct VotingSessionCreatedEvent {
+    pub governance: Pubkey,
+    pub owner: Pubkey,
+    pub session_key: Pubkey,
+    pub expires_at: i64,
+    pub max_power: u64,
+}
+
+#[event]
+pub struct VotingSessionRevokedEvent {
+    pub governance: Pubkey,
+    pub owner: Pubkey,
+    pub session_key: Pubkey,
+    pub revoked_at: i64,
+}
+
+#[event]
+pub struct SessionVoteCastEvent {
+    pub proposal: Pubkey,
+    pub owner: Pubkey,
+    pub session_key: Pubkey,
+    pub vote: Vote,
+    pub voting_power: u64,
+}
+
+#[event]
+pub struct DelegatedVoteCastEvent {
+    pub proposal: Pubkey,
+    pub delegator: Pubkey,
+    pub delegate: Pubkey,
+    pub vote: Vote,
+    pub voting_power: u64,
+}
+
+#[event]
+pub struct ProposalExecutedEvent {
+    pub proposal: Pubkey,
+    pub executed_by: Pubkey,
+    pub execution_time: i64,
+    pub proposal_type: ProposalType,
+}
+
+#[event]
+pub struct ExecutionBountyPaidEvent {
+    pub proposal: Pubkey,
+    pub executor: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ProposalSucceededEvent {
+    pub proposal: Pubkey,
+    pub yes_votes: u64,
+    pub no_votes: u64,
+    pub voter_count: u64,
+    pub quorum_threshold: u64,
+    pub finalized_at: i64,
+}
+
+#[event]
+pub struct ProposalDefeatedEvent {
+    pub proposal: Pubkey,
+    pub yes_votes: u64,
+    pub no_votes: u64,
+    pub voter_count: u64,
+    pub quorum_threshold: u64,
+    pub finalized_at: i64,
+}
+
+#[event]
+pub struct ProposalQuorumFailedEvent {
+    pub proposal: Pubkey,
+    pub yes_votes: u64,
+    pub no_votes: u64,
+    pub voter_count: u64,
+    pub quorum_threshold: u64,
+    pub finalized_at: i64,
+}
+
+#[event]
+pub struct ProposalExpiredEvent {
+    pub proposal: Pubkey,
+    pub expired_at: i64,
+}
+
+#[event]
+pub struct ProposalsSweptEvent {
+    pub governance: Pubkey,
+    pub cranker: Pubkey,
+    pub expired_count: u32,
+    pub finalized_count: u32,
+}
+
+#[event]
+pub struct ParticipationBadgeCreditedEvent {
+    pub governance: Pubkey,
+    pub voter: Pubkey,
+    pub proposal: Pubkey,
+    pub proposals_participated: u64,
+}
+
+#[event]
+pub struct VoteAccountClosedEvent {
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    pub cranker: Pubkey,
+    pub cranker_reward: u64,
+}
+
+#[event]
+pub struct ProposalExecutionStartedEvent {
+    pub proposal: Pubkey,
+    pub executed_by: Pubkey,
+    pub item_count: u16,
+}
+
+#[event]
+pub struct ExecutionItemAddedEvent {
+    pub proposal: Pubkey,
+    pub execution_item: Pubkey,
+    pub index: u16,
+}
+
+#[event]
+pub struct ExecutionItemExecutedEvent {
+    pub proposal: Pubkey,
+    pub execution_item: Pubkey,
+    pub index: u16,
+    pub executed_by: Pubkey,
+}
+
+#[event]
+pub struct MilestoneScheduleCreatedEvent {
+    pub proposal: Pubkey,
+    pub milestone_schedule: Pubkey,
+    pub recipient: Pubkey,
+    pub tranche_count: u8,
+    pub total_amount: u64,
+}
+
+#[event]
+pub struct MilestoneScheduleActivatedEvent {
+    pub proposal: Pubkey,
+    pub milestone_schedule: Pubkey,
+    pub activated_at: i64,
+}
+
+#[event]
+pub struct MilestoneReleasedEvent {
+    pub proposal: Pubkey,
+    pub milestone_schedule: Pubkey,
+    pub index: u8,
+    pub amount: u64,
+    pub approved_by: Pubkey,
+    pub released_at: i64,
+}
+
+#[event]
+pub struct CrossChainConfiguredEvent {
+    pub governance: Pubkey,
+    pub wormhole_program: Pubkey,
+    pub consistency_level: u8,
+}
+
+#[event]
+pub struct CrossChainMessageAttachedEvent {
+    pub proposal: Pubkey,
+    pub cross_chain_message: Pubkey,
+    pub target_chain: u16,
+    pub target_address: [u8; 32],
+}
+
+#[event]
+pub struct CrossChainMessageRelayedEvent {
+    pub proposal: Pubkey,
+    pub cross_chain_message: Pubkey,
+    pub target_chain: u16,
+    pub sequence: u64,
+    pub wormhole_message: Pubkey,
+}
+
+#[event]
+pub struct ProgramAllowlistInitializedEvent {
+    pub governance: Pubkey,
+    pub programs: Vec<Pubkey>,
+}
+
+#[event]
+pub struct ProgramAllowlistUpdatedEvent {
+    pub governance: Pubkey,
+    pub program_id: Pubkey,
+    pub allowed: bool,
+}
+
+#[event]
+pub struct SubDaoRegisteredEvent {
+    pub governance: Pubkey,
+    pub parent_governance: Pubkey,
+}
+
+#[event]
+pub struct ProposalVetoedEvent {
+    pub proposal: Pubkey,
+    pub vetoed_by: Pubkey,
+    pub veto_time: i64,
+}
+
+#[event]
+pub struct ProposalCancelledEvent {
+    pub proposal: Pubkey,
+    pub cancelled_by: Pubkey,
+    pub cancellation_time: i64,
+}
+
+#[event]
+pub struct GovernanceUpdatedEvent {
+    pub governance: Pubkey,
+    pub min_proposal_tokens: u64,
+    pub voting_period: i64,
+    pub execution_delay: i64,
+    pub execution_window: i64,
+    pub quorum_percentage: u8,
+    pub execution_bounty: u64,
+    pub guardian: Pubkey,
+    pub min_unique_voters: u64,
+    pub treasury_epoch_cap: u64,
+    pub treasury_epoch_duration: i64,
+    pub inactivity_window: u8,
+    pub inactivity_decay_bps: u16,
+}
+
+#[event]
+pub struct GovernancePausedEvent {
+    pub governance: Pubkey,
+    pub paused_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GovernanceUnpausedEvent {
+    pub governance: Pubkey,
+    pub unpaused_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProposalCategoryUpdatedEvent {
+    pub governance: Pubkey,
+    pub category: Pubkey,
+    pub category_id: u16,
+    pub proposal_type: ProposalType,
+    pub quorum_percentage: u8,
+    pub max_amount: u64,
+}
+
+#[event]
+pub struct BalanceSnapshotPostedEvent {
+    pub governance: Pubkey,
+    pub snapshot: Pubkey,
+    pub snapshot_id: u64,
+    pub merkle_root: [u8; 32],
+    pub total_balance: u64,
+    pub snapshot_slot: u64,
+}
+
+#[event]
+pub struct CouncilConfiguredEvent {
+    pub governance: Pubkey,
+    pub council_collection: Pubkey,
+    pub council_size: u64,
+}
+
+#[event]
+pub struct VotingPowerPageAddedEvent {
+    pub voting_power_registry: Pubkey,
+    pub page: Pubkey,
+    pub page_index: u32,
+}
+
+#[event]
+pub struct VoterEntryMovedEvent {
+    pub voter: Pubkey,
+    pub from_page_index: u32,
+    pub from_slot: u16,
+    pub to_page_index: Option<u32>,
+    pub to_slot: Option<u16>,
+}
+
+#[event]
+pub struct VotingMintUpdatedEvent {
+    pub governance: Pubkey,
+    pub mint: Pubkey,
+    pub old_weight_bps: u16,
+    pub new_weight_bps: u16,
+    pub total_voting_power: u64,
+}
+
+#[event]
+pub struct RegistryStakingPoolUpdatedEvent {
+    pub voting_power_registry: Pubkey,
+    pub staking_pool: Pubkey,
+}
+
+#[event]
+pub struct StakedVotingPowerReportedEvent {
+    pub voting_power_registry: Pubkey,
+    pub staking_pool: Pubkey,
+    pub total_voting_power: u64,
+    pub governance_total_voting_power: u64,
+}
+
+#[event]
+pub struct VotingPowerDecayedEvent {
+    pub voter: Pubkey,
+    pub mint: Pubkey,
+    pub voting_power: u64,
+    pub decayed_voting_power: u64,
+    pub total_voting_power: u64,
+}
+
+#[event]
+pub struct VotingPowerRestoredEvent {
+    pub voter: Pubkey,
+    pub mint: Pubkey,
+    pub voting_power: u64,
+    pub restored_voting_power: u64,
+    pub total_voting_power: u64,
+}
+
+#[event]
+pub struct VotingPowerUpdatedEvent {
+    pub voter: Pubkey,
+    pub staking_pool: Pubkey,
+    pub old_voting_power: u64,
+    pub new_voting_power: u64,
+    pub reputation_boost: u64,
+    pub total_voting_power: u64,
+}
+
+#[error_code]
+pub enum GovernanceError {
+    #[msg("Quorum percentage must be between 1 and 100.")]
+    InvalidQuorumPercentage,
+    #[msg("Voting period must be greater than zero.")]
+    InvalidVotingPeriod,
+    #[msg("Execution delay cannot be negative.")]
+    InvalidExecutionDelay,
+    #[msg("Insufficient tokens to create a proposal.")]
+    InsufficientTokens,
+    #[msg("Voting period has ended.")]
+    VotingClosed,
+    #[msg("Proposal has been cancelled.")]
+    ProposalCancelled,
+    #[msg("Proposal has already been executed.")]
+    ProposalAlreadyExecuted,
+    #[msg("Voter has no voting power.")]
+    NoVotingPower,
+    #[msg("Voting period is still open.")]
+    VotingStillOpen,
+    #[msg("Execution delay has not passed yet.")]
+    ExecutionDelayNotPassed,
+    #[msg("Quorum has not been reached.")]
+    QuorumNotReached,
+    #[msg("Proposal did not pass.")]
+    ProposalNotPassed,
+    #[msg("Only the proposer or governance authority can cancel a proposal.")]
+    UnauthorizedCancellation,
+    #[msg("All execution items must be added before voting starts.")]
+    ExecutionItemsIncomplete,
+    #[msg("Execution item index is out of order or out of range.")]
+    InvalidExecutionItemIndex,
+    #[msg("Execution item payload is too large.")]
+    ExecutionItemTooLarge,
+    #[msg("Execution item has already been executed.")]
+    ExecutionItemAlreadyExecuted,
+    #[msg("Proposal execution has already started.")]
+    ExecutionAlreadyStarted,
+    #[msg("Proposal execution has not started yet.")]
+    ExecutionNotStarted,
+    #[msg("Execution item payload is not a valid instruction.")]
+    InvalidExecutionPayload,
+    #[msg("Target program is not on the governance allowlist.")]
+    ProgramNotAllowed,
+    #[msg("Program allowlist is full.")]
+    ProgramAllowlistFull,
+    #[msg("Governance is paused.")]
+    GovernancePaused,
+    #[msg("Execution window cannot be negative.")]
+    InvalidExecutionWindow,
+    #[msg("Execution bounty cannot exceed 1,000 WCT.")]
+    InvalidExecutionBounty,
+    #[msg("Proposal execution window has expired.")]
+    ExecutionWindowExpired,
+    #[msg("Proposal has already been finalized.")]
+    ProposalAlreadyFinalized,
+    #[msg("Governance already accepts the maximum number of voting mints.")]
+    TooManyVotingMints,
+    #[msg("Voting mint weight cannot exceed 3x.")]
+    InvalidVotingMintWeight,
+    #[msg("A voting mint needs the staking pool that registers its holders' power.")]
+    VotingMintPoolRequired,
+    #[msg("Mint already confers voting power.")]
+    VotingMintAlreadyAdded,
+    #[msg("Mint does not confer voting power in this governance.")]
+    UnknownVotingMint,
+    #[msg("Voter power account does not belong to this voter and governance.")]
+    InvalidVoterPowerAccount,
+    #[msg("Voting power for the same mint was supplied twice.")]
+    DuplicateVotingMint,
+    #[msg("Council voting has not been configured.")]
+    CouncilNotConfigured,
+    #[msg("Council proposals can only be voted on with a council NFT.")]
+    CouncilProposal,
+    #[msg("Proposal is not a council proposal.")]
+    NotCouncilProposal,
+    #[msg("NFT is not part of the verified council collection.")]
+    NotCouncilMember,
+    #[msg("This council NFT has already been used by another voter on this proposal.")]
+    CouncilNftAlreadyVoted,
+    #[msg("Reputation boost exceeds the maximum allowed percentage.")]
+    InvalidReputationBoost,
+    #[msg("Voting power page does not match the voter's entry or the registry's tail page.")]
+    WrongVotingPowerPage,
+    #[msg("Voting power page is full; add a new page first.")]
+    VotingPowerPageFull,
+    #[msg("Voting power page slot is already occupied.")]
+    VotingPowerSlotOccupied,
+    #[msg("Voter still has voting power.")]
+    VoterStillHasPower,
+    #[msg("Tail page has no entries to compact.")]
+    NothingToCompact,
+    #[msg("Proposal is not within its veto window.")]
+    VetoWindowClosed,
+    #[msg("Cannot delegate voting power to yourself.")]
+    InvalidDelegate,
+    #[msg("Delegation expiry must be in the future.")]
+    InvalidDelegationExpiry,
+    #[msg("Delegation has expired.")]
+    DelegationExpired,
+    #[msg("The voter has voted directly; a delegate or session key can't change that vote.")]
+    DirectVoteRecorded,
+    #[msg("Category name is too long.")]
+    CategoryNameTooLong,
+    #[msg("Proposal type does not match the category.")]
+    CategoryTypeMismatch,
+    #[msg("Requested amount exceeds the category maximum.")]
+    CategoryAmountExceeded,
+    #[msg("Snapshot proposals can only be voted on with a balance proof.")]
+    SnapshotProposal,
+    #[msg("Proposal does not use this balance snapshot.")]
+    NotSnapshotProposal,
+    #[msg("Invalid Merkle proof.")]
+    InvalidMerkleProof,
+    #[msg("A proposal can only use one of council, snapshot and one-person-one-vote voting.")]
+    ConflictingVoteModes,
+    #[msg("Proposal has not been approved and executed.")]
+    ProposalNotApproved,
+    #[msg("Proposal type does not match the requested action.")]
+    ProposalTypeMismatch,
+    #[msg("Proposal did not authorize this action.")]
+    ActionNotAuthorized,
+    #[msg("Only stakers of the governance token can vote on one-person-one-vote proposals.")]
+    NotAStaker,
+    #[msg("Milestone schedules are only supported for treasury withdrawals.")]
+    NotTreasuryWithdrawal,
+    #[msg("Proposal has already received votes.")]
+    ProposalAlreadyVotedOn,
+    #[msg("Invalid number of milestone tranches.")]
+    InvalidTrancheCount,
+    #[msg("Tranche amounts must add up to the requested amount.")]
+    TrancheAmountMismatch,
+    #[msg("Proposal has a milestone schedule that must be supplied.")]
+    MilestoneScheduleRequired,
+    #[msg("Milestone schedule has not been activated by execution.")]
+    MilestoneScheduleNotActive,
+    #[msg("All milestones have already been released.")]
+    AllMilestonesReleased,
+    #[msg("Sweep accounts must be proposal and result pairs for this governance.")]
+    InvalidSweepAccounts,
+    #[msg("Proposal has not been settled yet.")]
+    ProposalNotSettled,
+    #[msg("Vote account grace period has not passed.")]
+    GracePeriodNotPassed,
+    #[msg("The vote's participation badge can still be claimed.")]
+    BadgeUnclaimed,
+    #[msg("Proposal payload does not match the proposal type.")]
+    PayloadTypeMismatch,
+    #[msg("Proposal payload is malformed.")]
+    InvalidProposalPayload,
+    #[msg("A governance realm cannot be its own parent.")]
+    InvalidParentRealm,
+    #[msg("Proposal has not been finalized.")]
+    ProposalNotFinalized,
+    #[msg("Participation badge has already been credited for this vote.")]
+    BadgeAlreadyClaimed,
+    #[msg("The participation badge claim window for this proposal has closed.")]
+    BadgeClaimWindowClosed,
+    #[msg("Session key must differ from the owner.")]
+    InvalidSessionKey,
+    #[msg("Voting session must expire in the future and within the maximum duration.")]
+    InvalidSessionExpiry,
+    #[msg("Voting session power cap must be positive.")]
+    InvalidSessionPowerCap,
+    #[msg("Voting session has expired.")]
+    VotingSessionExpired,
+    #[msg("Cross-chain message payload is too large.")]
+    CrossChainPayloadTooLarge,
+    #[msg("Cross-chain message has already been relayed.")]
+    CrossChainMessageAlreadyRelayed,
+    #[msg("Wormhole accounts do not match the cross-chain configuration.")]
+    InvalidWormholeAccounts,
+    #[msg("Participation window must be between 1 and 64 proposals.")]
+    InvalidParticipationWindow,
+    #[msg("Treasury epoch duration must be positive.")]
+    InvalidTreasuryEpochDuration,
+    #[msg("Treasury withdrawal would exceed the cap for the trailing window; retry later.")]
+    TreasuryEpochCapExceeded,
+    #[msg("Treasury withdrawal recipient account is missing or does not match the payload.")]
+    RecipientAccountRequired,
+    #[msg("Signer is not the staking pool that reports this registry's voting power.")]
+    UnauthorizedStakingPool,
+    #[msg("Inactivity window must be at most 64 proposals and decay at most 10000 basis points.")]
+    InvalidInactivityDecay,
+    #[msg("Inactivity decay is disabled.")]
+    InactivityDecayDisabled,
+    #[msg("Voter's power has already decayed.")]
+    VoterAlreadyDecayed,
+    #[msg("Voter has taken part in the inactivity window.")]
+    VoterNotInactive,
+    #[msg("Voter's power has not decayed.")]
+    VoterNotDecayed,
+    #[msg("Voter has not voted since their power decayed.")]
+    NoVoteSinceDecay,
+    #[msg("Voting power registry does not match the voter's power account.")]
+    InvalidVotingPowerRegistry,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn governance() -> Governance {
+        Governance::deserialize(&mut &[0u8; Governance::LEN][..]).unwrap()
+    }
+    
+    fn proposal() -> Proposal {
+        Proposal::deserialize(&mut &[0u8; Proposal::LEN][..]).unwrap()
+    }
+    
+    #[test]
+    fn treasury_cap_limits_the_trailing_window() {
+        let mut governance = governance();
+        assert_eq!(treasury_allowance_remaining(&governance, 0), u64::MAX);
+        
+        governance.treasury_epoch_cap = 1000;
+        governance.treasury_epoch_duration = 100;
+        consume_treasury_allowance(&mut governance, 600, 10).unwrap();
+        assert_eq!(treasury_allowance_remaining(&governance, 50), 400);
+        assert!(consume_treasury_allowance(&mut governance, 500, 50).is_err());
+        consume_treasury_allowance(&mut governance, 400, 50).unwrap();
+        assert_eq!(treasury_allowance_remaining(&governance, 50), 0);
+        
+        // The first withdrawal leaves the window once it is a full window old
+        assert_eq!(treasury_allowance_remaining(&governance, 109), 0);
+        assert_eq!(treasury_allowance_remaining(&governance, 110), 600);
+        consume_treasury_allowance(&mut governance, 600, 110).unwrap();
+    }
+    
+    #[test]
+    fn treasury_cap_waits_when_every_record_is_in_the_window() {
+        let mut governance = governance();
+        governance.treasury_epoch_cap = 1000;
+        governance.treasury_epoch_duration = 100;
+        for now in 1..=Governance::MAX_RECENT_TREASURY_WITHDRAWALS as i64 {
+            consume_treasury_allowance(&mut governance, 1, now).unwrap();
+        }
+        assert!(consume_treasury_allowance(&mut governance, 1, 20).is_err());
+        consume_treasury_allowance(&mut governance, 1, 102).unwrap();
+    }
+    
+    #[test]
+    fn balance_snapshot_needs_a_proposal_approving_it() {
+        let merkle_root = [7u8; 32];
+        let action_hash = snapshot_action_hash(1, &merkle_root, 500, 42);
+        let mut proposal = proposal();
+        proposal.proposal_type = ProposalType::Other;
+        proposal.action_hash = action_hash;
+        assert!(require_proposal_approved(&proposal, ProposalType::Other, action_hash).is_err());
+        
+        proposal.executed = true;
+        proposal.outcome = ProposalOutcome::Succeeded;
+        require_proposal_approved(&proposal, ProposalType::Other, action_hash).unwrap();
+        
+        // A different total, or approval under another proposal type, doesn't count
+        let other_total = snapshot_action_hash(1, &merkle_root, 501, 42);
+        assert!(require_proposal_approved(&proposal, ProposalType::Other, other_total).is_err());
+        assert!(require_proposal_approved(&proposal, ProposalType::ParameterChange, action_hash).is_err());
+    }
+}
@@ -1,5 +1,11 @@
 // File: programs/wct-staking/src/lib.rs
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::{hash, hashv};
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::{get_return_data, invoke, invoke_signed};
+use anchor_lang::solana_program::program_option::COption;
+use anchor_lang::solana_program::pubkey;
+use anchor_spl::token::spl_token::instruction::AuthorityType;
 use anchor_spl::token::{self, Mint, Token, TokenAccount};
 use anchor_spl::associated_token::AssociatedToken;
 
@@ -9,20 +15,123 @@ declare_id!("YOUR_STAKING_PROGRAM_ID");
 pub mod wct_staking {
     use super::*;
 
-    // Initialize the staking program with admin authority
-    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+    // Initialize a staking pool with admin authority. `pool_id` tells apart pools that stake
+    // the same mint, e.g. a short-lock and a long-lock pool.
+    pub fn initialize(ctx: Context<Initialize>, pool_id: u64) -> Result<()> {
         let staking_pool = &mut ctx.accounts.staking_pool;
         staking_pool.authority = ctx.accounts.authority.key();
+        staking_pool.manager = Pubkey::default();
+        staking_pool.badge_program = Pubkey::default();
         staking_pool.token_mint = ctx.accounts.token_mint.key();
+        staking_pool.pool_id = pool_id;
+        staking_pool.reward_mint = ctx.accounts.reward_mint.key();
+        staking_pool.staking_vault = ctx.accounts.staking_vault.key();
         staking_pool.treasury_token_account = ctx.accounts.treasury_token_account.key();
+        staking_pool.rewards_vault = ctx.accounts.rewards_vault.key();
+        staking_pool.receipt_mint = ctx.accounts.receipt_mint.key();
         staking_pool.total_staked = 0;
         staking_pool.staker_count = 0;
+        staking_pool.total_voting_power = 0;
         staking_pool.bump = *ctx.bumps.get("staking_pool").unwrap();
         
+        // Reward accumulator starts accruing from initialization
+        let now = Clock::get()?.unix_timestamp;
+        staking_pool.reward_per_token_stored = 0;
+        staking_pool.last_update_timestamp = now;
+        staking_pool.total_reward_weight = 0;
+        
+        // Nothing is paid out until the rewards vault is funded
+        staking_pool.reward_reserve = 0;
+        staking_pool.mint_rewards = false;
+        staking_pool.emission_cap = 0;
+        staking_pool.total_minted = 0;
+        
+        // No secondary reward until configured
+        staking_pool.secondary_reward_mint = Pubkey::default();
+        staking_pool.secondary_rewards_vault = Pubkey::default();
+        staking_pool.secondary_reward_rate = 0;
+        staking_pool.secondary_reward_per_token_stored = 0;
+        staking_pool.secondary_reward_reserve = 0;
+        
+        // Flat rate until an emission schedule is configured
+        staking_pool.emission_per_epoch = 0;
+        staking_pool.emission_epoch_duration = 0;
+        staking_pool.emission_start = 0;
+        staking_pool.halving_interval = 0;
+        
         // Default rewards configuration
         staking_pool.reward_rate = 10; // 10 basis points per day (0.1%)
         staking_pool.min_stake_duration = 30 * 24 * 60 * 60; // 30 days in seconds
         staking_pool.max_stake_duration = 365 * 24 * 60 * 60; // 365 days in seconds
+        staking_pool.rate_checkpoints = [RateCheckpoint::default(); MAX_RATE_CHECKPOINTS];
+        staking_pool.rate_checkpoint_count = 0;
+        record_rate_checkpoint(staking_pool, now);
+        
+        // Nothing is paused
+        staking_pool.paused_flags = 0;
+        staking_pool.emergency_mode = false;
+        staking_pool.incident_exit_enabled = false;
+        
+        // No TVL cap until one is configured
+        staking_pool.max_total_staked = 0;
+        
+        // Positions start at 1 WCT, below which they carry no voting power
+        staking_pool.min_stake_amount = DEFAULT_MIN_STAKE_AMOUNT;
+        
+        // No performance fee until governance sets one
+        staking_pool.performance_fee_bps = 0;
+        staking_pool.fee_vault = Pubkey::default();
+        
+        // Rewards are paid out immediately until vesting is configured
+        staking_pool.reward_vesting_period = 0;
+        staking_pool.vesting_vault = Pubkey::default();
+        
+        // No migrations until a newer pool is designated
+        staking_pool.migration_target = Pubkey::default();
+        
+        // Open to every wallet until a compliance list is configured
+        staking_pool.compliance_authority = Pubkey::default();
+        staking_pool.compliance_mode = COMPLIANCE_NONE;
+        staking_pool.compliance_scope = 0;
+        
+        // Positions can be claimed as often as the user likes until an interval is set
+        staking_pool.min_claim_interval = 0;
+        
+        // Reward parameter changes apply immediately until a timelock is set
+        staking_pool.params_timelock = 0;
+        staking_pool.pending_reward_rate = 0;
+        staking_pool.pending_min_stake_duration = 0;
+        staking_pool.pending_max_stake_duration = 0;
+        staking_pool.pending_params_at = 0;
+        
+        // Positions unstake directly until an unbonding period is configured
+        staking_pool.unbonding_period = 0;
+        
+        // Unbonding tokens aren't queued until a budget is configured
+        staking_pool.unbonding_budget = 0;
+        staking_pool.unbonding_queue_tail = 0;
+        staking_pool.unbonding_queue_head = 0;
+        staking_pool.unbonding_budget_epoch = 0;
+        staking_pool.unbonding_budget_used = 0;
+        
+        // Open for new stake until the pool is retired
+        staking_pool.retire_at = 0;
+        
+        // Governance participation bonus is off until configured
+        staking_pool.governance_program = Pubkey::default();
+        staking_pool.governance = Pubkey::default();
+        staking_pool.participation_min_votes = 0;
+        staking_pool.participation_window = 0;
+        staking_pool.participation_bonus_bps = 0;
+        
+        // The original fixed voting formula until governance changes it
+        staking_pool.tokens_per_vote = DEFAULT_TOKENS_PER_VOTE;
+        
+        // Duration tiers start out as the original 30/90/180/365-day schedule
+        let tier_config = &mut ctx.accounts.tier_config;
+        tier_config.pool = staking_pool.key();
+        tier_config.bump = *ctx.bumps.get("tier_config").unwrap();
+        tier_config.set_tiers(&default_duration_tiers())?;
         
         Ok(())
     }
@@ -30,53 +139,95 @@ pub mod wct_staking {
     // Start staking tokens
     pub fn stake(ctx: Context<Stake>, amount: u64, duration: i64) -> Result<()> {
         let staking_pool = &mut ctx.accounts.staking_pool;
+        let staker_info = &mut ctx.accounts.staker_info;
         let user_stake = &mut ctx.accounts.user_stake;
         let clock = Clock::get()?;
         
+        // Check the pool's circuit breaker
+        require!(staking_pool.paused_flags & PAUSE_STAKING == 0, StakingError::StakingPaused);
+        
+        // Retired pools take no new stake
+        require!(staking_pool.retire_at == 0, StakingError::PoolRetired);
+        
+        // Permissioned pools only serve wallets their compliance list lets through
+        require_compliant(
+            staking_pool,
+            ctx.accounts.user.key(),
+            ctx.accounts.compliance_entry.as_ref(),
+            COMPLIANCE_STAKING,
+        )?;
+        
         // Validate stake duration
         require!(
             duration >= staking_pool.min_stake_duration && duration <= staking_pool.max_stake_duration,
             StakingError::InvalidStakeDuration
         );
         
+        // Reject dust positions
+        require!(
+            amount > 0 && amount >= staking_pool.min_stake_amount,
+            StakingError::StakeTooSmall
+        );
+        
+        // Respect the pool's TVL cap
+        require_within_tvl_cap(staking_pool, amount)?;
+        
         // Calculate end timestamp
         let end_timestamp = clock.unix_timestamp + duration;
         
+        // Bring the accumulator up to date so the new position only earns from now on
+        update_reward_accumulator(staking_pool, clock.unix_timestamp)?;
+        
+        // Claim the next position index for this user
+        let position_index = staker_info.position_count;
+        staker_info.owner = ctx.accounts.user.key();
+        staker_info.pool = staking_pool.key();
+        staker_info.position_count = position_index.try_add(1)?;
+        staker_info.bump = *ctx.bumps.get("staker_info").unwrap();
+        register_position(
+            &mut ctx.accounts.stake_registry,
+            ctx.accounts.user.key(),
+            user_stake.key(),
+            *ctx.bumps.get("stake_registry").unwrap(),
+        )?;
+        
         // Setup user stake account
         user_stake.owner = ctx.accounts.user.key();
+        user_stake.creator = ctx.accounts.user.key();
+        user_stake.pool = staking_pool.key();
+        user_stake.position_mint = Pubkey::default();
+        user_stake.pending_owner = Pubkey::default();
+        user_stake.reward_destination = Pubkey::default();
+        user_stake.frozen_until = 0;
+        user_stake.frozen_reward = 0;
+        user_stake.reward_owed = 0;
+        user_stake.slashed_receipts = 0;
+        user_stake.unbonding_queue_ticket = 0;
+        user_stake.position_index = position_index;
+        user_stake.bump = *ctx.bumps.get("user_stake").unwrap();
         user_stake.stake_amount = amount;
         user_stake.start_timestamp = clock.unix_timestamp;
         user_stake.end_timestamp = end_timestamp;
+        user_stake.lock_duration = duration;
+        user_stake.auto_relock = false;
         user_stake.claimed_reward = 0;
         user_stake.last_claim_timestamp = clock.unix_timestamp;
         user_stake.withdrawn = false;
         
-        // Calculate reputation boost based on duration
-        // 30 days: 10% boost, 90 days: 20% boost, 180 days: 30% boost, 365 days: 50% boost
-        if duration >= 365 * 24 * 60 * 60 {
-            user_stake.reputation_boost = 50; // 50% boost
-        } else if duration >= 180 * 24 * 60 * 60 {
-            user_stake.reputation_boost = 30; // 30% boost
-        } else if duration >= 90 * 24 * 60 * 60 {
-            user_stake.reputation_boost = 20; // 20% boost
-        } else {
-            user_stake.reputation_boost = 10; // 10% boost
-        }
-        
-        // Calculate voting power based on duration
-        // 1 vote per 1000 tokens, multiplied by duration boost
-        let duration_factor = match duration {
-            d if d >= 365 * 24 * 60 * 60 => 3, // 3x for 365 days
-            d if d >= 180 * 24 * 60 * 60 => 2, // 2x for 180 days
-            d if d >= 90 * 24 * 60 * 60 => 1.5, // 1.5x for 90 days
-            _ => 1, // 1x for 30 days
-        };
-        
-        user_stake.voting_power = ((amount / 1_000_000_000) as f64 * duration_factor) as u64;
+        // Calculate reputation boost, voting power and reward rate based on the duration's tier
+        let tier = ctx.accounts.tier_config.tier_for(duration);
+        user_stake.reputation_boost = tier.reputation_boost;
+        let voting_power = voting_power_for(staking_pool.tokens_per_vote, amount, &tier)?;
+        set_voting_power(staking_pool, user_stake, voting_power)?;
+        user_stake.reward_multiplier_bps = tier.reward_multiplier_bps;
+        user_stake.nft_boost_mint = Pubkey::default();
+        user_stake.nft_boost_bps = 0;
+        sync_reward_debt(staking_pool, user_stake)?;
         
         // Update staking pool
-        staking_pool.total_staked = staking_pool.total_staked.checked_add(amount).unwrap();
-        staking_pool.staker_count = staking_pool.staker_count.checked_add(1).unwrap();
+        staking_pool.total_staked = staking_pool.total_staked.try_add(amount)?;
+        add_reward_weight(staking_pool, amount, user_stake.reward_multiplier_bps)?;
+        open_staker_position(staking_pool, staker_info, clock.unix_timestamp)?;
         
         // Transfer tokens from user to staking vault
         token::transfer(
@@ -91,14 +242,235 @@ pub mod wct_staking {
             amount,
         )?;
         
+        // Mint the matching stWCT receipt
+        mint_receipt(
+            staking_pool,
+            &ctx.accounts.receipt_mint,
+            ctx.accounts.user_receipt_account.to_account_info(),
+            &ctx.accounts.token_program,
+            amount,
+        )?;
+        
         // Emit stake event
         emit!(StakeEvent {
             user: ctx.accounts.user.key(),
+            position_index,
             amount,
             duration,
             end_timestamp,
             reputation_boost: user_stake.reputation_boost,
             voting_power: user_stake.voting_power,
+            reward_multiplier_bps: user_stake.reward_multiplier_bps,
+            reward_debt: user_stake.reward_debt,
+            total_staked: staking_pool.total_staked,
+            reward_reserve: staking_pool.reward_reserve,
+            reward_per_token_stored: staking_pool.reward_per_token_stored,
+        });
+        
+        Ok(())
+    }
+
+    // Open several positions at once with staggered locks, e.g. to build an exit ladder. The new
+    // positions' PDAs, at the wallet's next position indexes in order, are passed as
+    // remaining_accounts and must not exist yet. Durations must be strictly increasing.
+    pub fn stake_ladder<'info>(
+        ctx: Context<'_, '_, '_, 'info, StakeLadder<'info>>,
+        amounts: Vec<u64>,
+        durations: Vec<i64>,
+    ) -> Result<()> {
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        let staker_info = &mut ctx.accounts.staker_info;
+        let user = ctx.accounts.user.key();
+        let clock = Clock::get()?;
+        
+        // Check the pool's circuit breaker
+        require!(staking_pool.paused_flags & PAUSE_STAKING == 0, StakingError::StakingPaused);
+        
+        // Retired pools take no new stake
+        require!(staking_pool.retire_at == 0, StakingError::PoolRetired);
+        
+        // Permissioned pools only serve wallets their compliance list lets through
+        require_compliant(
+            staking_pool,
+            user,
+            ctx.accounts.compliance_entry.as_ref(),
+            COMPLIANCE_STAKING,
+        )?;
+        
+        // One amount, duration and position account per rung
+        require!(
+            !amounts.is_empty()
+                && amounts.len() <= MAX_LADDER_RUNGS
+                && amounts.len() == durations.len()
+                && amounts.len() == ctx.remaining_accounts.len(),
+            StakingError::InvalidLadder
+        );
+        for pair in durations.windows(2) {
+            require!(pair[1] > pair[0], StakingError::InvalidLadder);
+        }
+        
+        // Every rung must be a valid stake on its own
+        let mut total_amount: u64 = 0;
+        for (amount, duration) in amounts.iter().zip(durations.iter()) {
+            require!(
+                *duration >= staking_pool.min_stake_duration && *duration <= staking_pool.max_stake_duration,
+                StakingError::InvalidStakeDuration
+            );
+            require!(
+                *amount > 0 && *amount >= staking_pool.min_stake_amount,
+                StakingError::StakeTooSmall
+            );
+            total_amount = total_amount.try_add(*amount)?;
+        }
+        
+        // Respect the pool's TVL cap
+        require_within_tvl_cap(staking_pool, total_amount)?;
+        
+        // Bring the accumulator up to date so the new positions only earn from now on
+        update_reward_accumulator(staking_pool, clock.unix_timestamp)?;
+        
+        staker_info.owner = user;
+        staker_info.pool = staking_pool.key();
+        staker_info.bump = *ctx.bumps.get("staker_info").unwrap();
+        let first_position_index = staker_info.position_count;
+        
+        let pool_key = staking_pool.key();
+        let space = 8 + UserStake::LEN;
+        let lamports = Rent::get()?.minimum_balance(space);
+        for (account_info, (amount, duration)) in ctx
+            .remaining_accounts
+            .iter()
+            .zip(amounts.iter().copied().zip(durations.iter().copied()))
+        {
+            // Claim the next position index for this user
+            let position_index = staker_info.position_count;
+            staker_info.position_count = position_index.try_add(1)?;
+            
+            // Must be the PDA stake would create for that index
+            let position_index_bytes = position_index.to_le_bytes();
+            let (expected_key, bump) = Pubkey::find_program_address(
+                &[
+                    b"user_stake".as_ref(),
+                    user.as_ref(),
+                    pool_key.as_ref(),
+                    &position_index_bytes,
+                ],
+                &crate::ID,
+            );
+            require!(account_info.key() == expected_key, StakingError::InvalidPosition);
+            
+            anchor_lang::system_program::create_account(
+                CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::CreateAccount {
+                        from: ctx.accounts.user.to_account_info(),
+                        to: account_info.clone(),
+                    },
+                    &[&[
+                        b"user_stake".as_ref(),
+                        user.as_ref(),
+                        pool_key.as_ref(),
+                        &position_index_bytes,
+                        &[bump],
+                    ]],
+                ),
+                lamports,
+                space as u64,
+                &crate::ID,
+            )?;
+            let mut user_stake = Account::<UserStake>::try_from_unchecked(account_info)?;
+            
+            register_position(
+                &mut ctx.accounts.stake_registry,
+                user,
+                account_info.key(),
+                *ctx.bumps.get("stake_registry").unwrap(),
+            )?;
+            
+            // Setup user stake account
+            let end_timestamp = clock.unix_timestamp + duration;
+            user_stake.owner = user;
+            user_stake.creator = user;
+            user_stake.pool = pool_key;
+            user_stake.position_mint = Pubkey::default();
+            user_stake.pending_owner = Pubkey::default();
+            user_stake.reward_destination = Pubkey::default();
+            user_stake.frozen_until = 0;
+            user_stake.frozen_reward = 0;
+            user_stake.reward_owed = 0;
+            user_stake.slashed_receipts = 0;
+            user_stake.unbonding_queue_ticket = 0;
+            user_stake.position_index = position_index;
+            user_stake.bump = bump;
+            user_stake.stake_amount = amount;
+            user_stake.start_timestamp = clock.unix_timestamp;
+            user_stake.end_timestamp = end_timestamp;
+            user_stake.lock_duration = duration;
+            user_stake.auto_relock = false;
+            user_stake.claimed_reward = 0;
+            user_stake.last_claim_timestamp = clock.unix_timestamp;
+            user_stake.withdrawn = false;
+            
+            // Calculate reputation boost, voting power and reward rate based on the duration's tier
+            let tier = ctx.accounts.tier_config.tier_for(duration);
+            user_stake.reputation_boost = tier.reputation_boost;
+            let voting_power = voting_power_for(staking_pool.tokens_per_vote, amount, &tier)?;
+            set_voting_power(staking_pool, &mut user_stake, voting_power)?;
+            user_stake.reward_multiplier_bps = tier.reward_multiplier_bps;
+            user_stake.nft_boost_mint = Pubkey::default();
+            user_stake.nft_boost_bps = 0;
+            sync_reward_debt(staking_pool, &mut user_stake)?;
+            
+            // Update staking pool
+            staking_pool.total_staked = staking_pool.total_staked.try_add(amount)?;
+            add_reward_weight(staking_pool, amount, user_stake.reward_multiplier_bps)?;
+            open_staker_position(staking_pool, staker_info, clock.unix_timestamp)?;
+            
+            user_stake.exit(&crate::ID)?;
+            
+            emit!(StakeEvent {
+                user,
+                position_index,
+                amount,
+                duration,
+                end_timestamp,
+                reputation_boost: user_stake.reputation_boost,
+                voting_power: user_stake.voting_power,
+                reward_multiplier_bps: user_stake.reward_multiplier_bps,
+                reward_debt: user_stake.reward_debt,
+                total_staked: staking_pool.total_staked,
+                reward_reserve: staking_pool.reward_reserve,
+                reward_per_token_stored: staking_pool.reward_per_token_stored,
+            });
+        }
+        
+        // Transfer the whole ladder from user to staking vault in one go
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.user_token_account.to_account_info(),
+                    to: ctx.accounts.staking_vault.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            total_amount,
+        )?;
+        
+        // Mint the matching stWCT receipts
+        mint_receipt(
+            staking_pool,
+            &ctx.accounts.receipt_mint,
+            ctx.accounts.user_receipt_account.to_account_info(),
+            &ctx.accounts.token_program,
+            total_amount,
+        )?;
+        
+        emit!(StakeLadderEvent {
+            user,
+            first_position_index,
+            rungs: amounts.len() as u8,
+            total_amount,
         });
         
         Ok(())
@@ -106,42 +478,335 @@ pub mod wct_staking {
 
     // Claim staking rewards
     pub fn claim_reward(ctx: Context<ClaimReward>) -> Result<()> {
-        let staking_pool = &ctx.accounts.staking_pool;
+        let staking_pool = &mut ctx.accounts.staking_pool;
         let user_stake = &mut ctx.accounts.user_stake;
         let clock = Clock::get()?;
         
+        // Rewards go wherever the owner routed them
+        require_reward_destination(user_stake, &ctx.accounts.user_token_account)?;
+        if let Some(user_secondary_token_account) = ctx.accounts.user_secondary_token_account.as_ref() {
+            require_reward_destination(user_stake, user_secondary_token_account)?;
+        }
+        
+        // A session key claims on the owner's behalf, so every check follows the owner
+        let owner = user_stake.owner;
+        if let Some(session_key) = ctx.accounts.session_key.as_ref() {
+            require!(clock.unix_timestamp < session_key.expires_at, StakingError::SessionKeyExpired);
+            
+            // Session keys can only pay out to the owner's own accounts, or its reward destination
+            let payee = reward_payee(user_stake);
+            require!(
+                ctx.accounts.user_token_account.owner == payee
+                    && ctx
+                        .accounts
+                        .user_secondary_token_account
+                        .as_ref()
+                        .map_or(true, |account| account.owner == payee),
+                StakingError::SessionPayoutMismatch
+            );
+        }
+        
+        // Check the pool's circuit breaker
+        require!(staking_pool.paused_flags & PAUSE_CLAIMS == 0, StakingError::ClaimsPaused);
+        
+        // Permissioned pools only serve wallets their compliance list lets through
+        require_compliant(
+            staking_pool,
+            owner,
+            ctx.accounts.compliance_entry.as_ref(),
+            COMPLIANCE_CLAIMS,
+        )?;
+        
+        // Tokenized positions are controlled by whoever holds the position NFT
+        require_position_holder(
+            user_stake,
+            owner,
+            ctx.accounts.position_token_account.as_ref(),
+        )?;
+        
         // Ensure stake is still active
         require!(!user_stake.withdrawn, StakingError::StakeAlreadyWithdrawn);
         
+        // Frozen positions stay untouched until the freeze is lifted or runs out
+        settle_freeze(staking_pool, user_stake, clock.unix_timestamp)?;
+        
+        // Rate-limit claims to the pool's minimum interval
+        require_claim_interval(staking_pool, user_stake, clock.unix_timestamp)?;
+        
         // Calculate time elapsed since last claim
         let time_elapsed = clock
             .unix_timestamp
-            .checked_sub(user_stake.last_claim_timestamp)
-            .unwrap();
+            .try_sub(user_stake.last_claim_timestamp)?;
         
         // Ensure some time has elapsed for rewards
         require!(time_elapsed > 0, StakingError::NoRewardsYet);
         
-        // Calculate reward (pro-rated for time elapsed)
-        // reward = stake_amount * reward_rate * time_elapsed / (365 * 24 * 60 * 60 * 10000)
-        // reward_rate is in basis points (1/100 of a percent)
-        let days_elapsed = time_elapsed as f64 / (24.0 * 60.0 * 60.0);
-        let reward_amount = (user_stake.stake_amount as u128)
-            .checked_mul(staking_pool.reward_rate as u128)
-            .unwrap()
-            .checked_mul(time_elapsed as u128)
-            .unwrap()
-            .checked_div((365 * 24 * 60 * 60 * 10000) as u128)
-            .unwrap() as u64;
+        // Calculate reward from the pool's reward-per-token accumulator
+        let days_elapsed = time_elapsed.try_div(24 * 60 * 60)? as u64;
+        update_reward_accumulator(staking_pool, clock.unix_timestamp)?;
+        
+        // An NFT boost only lasts while the NFT is still held
+        refresh_nft_boost(
+            staking_pool,
+            user_stake,
+            owner,
+            ctx.accounts.boost_nft_account.as_ref(),
+        )?;
+        let reward_amount = pending_reward(staking_pool, user_stake)?;
+        bank_secondary_reward(staking_pool, user_stake)?;
+        
+        // Active governance participants earn a bonus on top of the base rate
+        let participation_bonus = if has_participation_bonus(
+            staking_pool,
+            owner,
+            ctx.accounts.governance_program.as_ref(),
+            ctx.accounts.governance.as_ref(),
+            ctx.accounts.voter_record.as_ref(),
+        )? {
+            participation_bonus_on(staking_pool, reward_amount)?
+        } else {
+            0
+        };
+        
+        // Wallets that have kept staking without a break earn a loyalty bonus too
+        let loyalty_bonus = loyalty_bonus_on(
+            ctx.accounts.staker_info.as_ref(),
+            reward_amount,
+            clock.unix_timestamp,
+        )?;
+        let reward_amount = reward_amount
+            .try_add(participation_bonus)?
+            .try_add(loyalty_bonus)?;
         
         // Update user stake
-        user_stake.claimed_reward = user_stake.claimed_reward.checked_add(reward_amount).unwrap();
+        user_stake.claimed_reward = user_stake.claimed_reward.try_add(reward_amount)?;
         user_stake.last_claim_timestamp = clock.unix_timestamp;
+        sync_reward_debt(staking_pool, user_stake)?;
+        
+        // Pay rewards to user, less the performance fee, or into their vesting schedule
+        let paid_amount = distribute_reward(
+            staking_pool,
+            ctx.accounts.reward_mint.as_ref(),
+            &ctx.accounts.rewards_vault,
+            ctx.accounts.fee_vault.as_ref(),
+            ctx.accounts.vesting_vault.as_ref(),
+            ctx.accounts.reward_vesting.as_mut(),
+            &ctx.accounts.user_token_account,
+            &ctx.accounts.token_program,
+            reward_amount,
+        )?;
+        
+        // Pay the secondary reward too when its accounts are passed
+        let secondary_reward_amount = match (
+            ctx.accounts.secondary_rewards_vault.as_ref(),
+            ctx.accounts.user_secondary_token_account.as_ref(),
+        ) {
+            (Some(secondary_rewards_vault), Some(user_secondary_token_account)) => pay_secondary_reward(
+                staking_pool,
+                user_stake,
+                secondary_rewards_vault,
+                user_secondary_token_account.to_account_info(),
+                &ctx.accounts.token_program,
+            )?,
+            _ => 0,
+        };
+        
+        // Emit reward event
+        emit!(RewardEvent {
+            user: owner,
+            position_index: user_stake.position_index,
+            reward_amount,
+            participation_bonus,
+            loyalty_bonus,
+            performance_fee: reward_amount.try_sub(paid_amount)?,
+            secondary_reward_amount,
+            days_elapsed,
+            total_claimed: user_stake.claimed_reward,
+            stake_amount: user_stake.stake_amount,
+            reward_debt: user_stake.reward_debt,
+            total_staked: staking_pool.total_staked,
+            reward_reserve: staking_pool.reward_reserve,
+            reward_per_token_stored: staking_pool.reward_per_token_stored,
+        });
+        
+        Ok(())
+    }
+
+    // Claim the rewards of several of the caller's positions in one transaction. The positions
+    // are passed as remaining accounts, along with the NFT token accounts of any boosted ones.
+    // Tokenized positions have to be claimed individually.
+    pub fn claim_all<'info>(ctx: Context<'_, '_, '_, 'info, ClaimAll<'info>>) -> Result<()> {
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        let user = ctx.accounts.user.key();
+        let clock = Clock::get()?;
+        
+        // Check the pool's circuit breaker
+        require!(staking_pool.paused_flags & PAUSE_CLAIMS == 0, StakingError::ClaimsPaused);
+        
+        // Permissioned pools only serve wallets their compliance list lets through
+        require_compliant(
+            staking_pool,
+            ctx.accounts.user.key(),
+            ctx.accounts.compliance_entry.as_ref(),
+            COMPLIANCE_CLAIMS,
+        )?;
+        
+        update_reward_accumulator(staking_pool, clock.unix_timestamp)?;
+        
+        // Active governance participants earn a bonus on top of the base rate
+        let participation_bonus_active = has_participation_bonus(
+            staking_pool,
+            user,
+            ctx.accounts.governance_program.as_ref(),
+            ctx.accounts.governance.as_ref(),
+            ctx.accounts.voter_record.as_ref(),
+        )?;
+        
+        // Token accounts among the remaining accounts are boosting NFTs
+        let mut boost_nft_accounts = Vec::new();
+        for account_info in ctx.remaining_accounts.iter().filter(|info| *info.owner == token::ID) {
+            boost_nft_accounts.push(Account::<TokenAccount>::try_from(account_info)?);
+        }
+        
+        let mut reward_amount: u64 = 0;
+        let mut participation_bonus: u64 = 0;
+        let mut loyalty_bonus: u64 = 0;
+        let mut position_count: u64 = 0;
+        for account_info in ctx.remaining_accounts.iter().filter(|info| *info.owner == crate::ID) {
+            let mut user_stake = Account::<UserStake>::try_from(account_info)?;
+            
+            // Must be one of the caller's positions in this pool
+            let expected_key = Pubkey::create_program_address(
+                &[
+                    b"user_stake".as_ref(),
+                    user_stake.creator.as_ref(),
+                    staking_pool.key().as_ref(),
+                    &user_stake.position_index.to_le_bytes(),
+                    &[user_stake.bump],
+                ],
+                &crate::ID,
+            )
+            .map_err(|_| StakingError::InvalidPosition)?;
+            require!(
+                account_info.key() == expected_key
+                    && user_stake.pool == staking_pool.key()
+                    && user_stake.owner == user,
+                StakingError::InvalidPosition
+            );
+            require_position_holder(&user_stake, user, None)?;
+            require_reward_destination(&user_stake, &ctx.accounts.user_token_account)?;
+            require!(!user_stake.withdrawn, StakingError::StakeAlreadyWithdrawn);
+            settle_freeze(staking_pool, &mut user_stake, clock.unix_timestamp)?;
+            require_claim_interval(staking_pool, &user_stake, clock.unix_timestamp)?;
+            
+            // An NFT boost only lasts while the NFT is still held
+            let boost_nft_account = boost_nft_accounts
+                .iter()
+                .find(|account| account.mint == user_stake.nft_boost_mint);
+            refresh_nft_boost(staking_pool, &mut user_stake, user, boost_nft_account)?;
+            
+            let position_reward = pending_reward(staking_pool, &user_stake)?;
+            bank_secondary_reward(staking_pool, &mut user_stake)?;
+            let position_bonus = if participation_bonus_active {
+                participation_bonus_on(staking_pool, position_reward)?
+            } else {
+                0
+            };
+            
+            // The loyalty bonus follows the caller's own streak
+            let position_loyalty_bonus = loyalty_bonus_on(
+                ctx.accounts.staker_info.as_ref(),
+                position_reward,
+                clock.unix_timestamp,
+            )?;
+            let position_reward = position_reward
+                .try_add(position_bonus)?
+                .try_add(position_loyalty_bonus)?;
+            
+            user_stake.claimed_reward = user_stake.claimed_reward.try_add(position_reward)?;
+            user_stake.last_claim_timestamp = clock.unix_timestamp;
+            sync_reward_debt(staking_pool, &mut user_stake)?;
+            user_stake.exit(&crate::ID)?;
+            
+            reward_amount = reward_amount.try_add(position_reward)?;
+            participation_bonus = participation_bonus.try_add(position_bonus)?;
+            loyalty_bonus = loyalty_bonus.try_add(position_loyalty_bonus)?;
+            position_count += 1;
+        }
+        require!(reward_amount > 0, StakingError::NoRewardsYet);
+        
+        // Pay everything at once, less the performance fee, or into the vesting schedule
+        let paid_amount = distribute_reward(
+            staking_pool,
+            ctx.accounts.reward_mint.as_ref(),
+            &ctx.accounts.rewards_vault,
+            ctx.accounts.fee_vault.as_ref(),
+            ctx.accounts.vesting_vault.as_ref(),
+            ctx.accounts.reward_vesting.as_mut(),
+            &ctx.accounts.user_token_account,
+            &ctx.accounts.token_program,
+            reward_amount,
+        )?;
+        
+        emit!(ClaimAllEvent {
+            user,
+            position_count,
+            reward_amount,
+            participation_bonus,
+            loyalty_bonus,
+            performance_fee: reward_amount.try_sub(paid_amount)?,
+        });
+        
+        Ok(())
+    }
+
+    // Recover a position's full principal, including tokens still unbonding, while the pool is in
+    // emergency mode. Locks, cooldowns and pauses are ignored and no rewards are paid. A
+    // registered position is deregistered on the way out, with the realm accounts passed as
+    // remaining accounts (see deregister_exiting_position).
+    pub fn emergency_withdraw<'info>(ctx: Context<'_, '_, '_, 'info, Unstake<'info>>) -> Result<()> {
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        let user_stake = &mut ctx.accounts.user_stake;
+        let clock = Clock::get()?;
+        
+        require!(staking_pool.emergency_mode, StakingError::NotInEmergencyMode);
+        
+        // Ensure stake is still active
+        require!(!user_stake.withdrawn, StakingError::StakeAlreadyWithdrawn);
+        
+        // Frozen positions stay untouched until the freeze is lifted or runs out
+        settle_freeze(staking_pool, user_stake, clock.unix_timestamp)?;
+        
+        // Tokenized positions are controlled by whoever holds the position NFT
+        require_position_holder(
+            user_stake,
+            ctx.accounts.user.key(),
+            ctx.accounts.position_token_account.as_ref(),
+        )?;
+        
+        // Registered governance power comes off with the position
+        deregister_exiting_position(
+            staking_pool,
+            user_stake,
+            ctx.remaining_accounts,
+            ctx.accounts.user.to_account_info(),
+        )?;
+        
+        let (amount, receipt_amount) = exit_position(staking_pool, user_stake)?;
+        if receipt_amount > 0 {
+            burn_receipt(
+                &ctx.accounts.receipt_mint,
+                &ctx.accounts.user_receipt_account,
+                &ctx.accounts.user,
+                &ctx.accounts.token_program,
+                receipt_amount,
+            )?;
+        }
         
-        // Transfer rewards from treasury to user
         let pool_seeds = &[
             b"staking_pool".as_ref(),
             staking_pool.token_mint.as_ref(),
+            &staking_pool.pool_id.to_le_bytes(),
             &[staking_pool.bump],
         ];
         
@@ -149,83 +814,82 @@ pub mod wct_staking {
             CpiContext::new_with_signer(
                 ctx.accounts.token_program.to_account_info(),
                 token::Transfer {
-                    from: ctx.accounts.treasury_token_account.to_account_info(),
+                    from: ctx.accounts.staking_vault.to_account_info(),
                     to: ctx.accounts.user_token_account.to_account_info(),
-                    authority: ctx.accounts.staking_pool.to_account_info(),
+                    authority: staking_pool.to_account_info(),
                 },
                 &[pool_seeds],
             ),
-            reward_amount,
+            amount,
         )?;
         
-        // Emit reward event
-        emit!(RewardEvent {
+        close_staker_position(staking_pool, &mut ctx.accounts.staker_info)?;
+        
+        emit!(EmergencyWithdrawEvent {
             user: ctx.accounts.user.key(),
-            reward_amount,
-            days_elapsed: days_elapsed as u64,
-            total_claimed: user_stake.claimed_reward,
+            position_index: user_stake.position_index,
+            amount,
         });
         
         Ok(())
     }
 
-    // Unstake tokens after the lock period
-    pub fn unstake(ctx: Context<Unstake>) -> Result<()> {
+    // Leave a position before its lock expires while governance has flagged an incident. The
+    // full principal comes back, but every unclaimed reward on the position is forfeited. A
+    // registered position is deregistered on the way out, with the realm accounts passed as
+    // remaining accounts (see deregister_exiting_position).
+    pub fn incident_exit<'info>(ctx: Context<'_, '_, '_, 'info, Unstake<'info>>) -> Result<()> {
         let staking_pool = &mut ctx.accounts.staking_pool;
         let user_stake = &mut ctx.accounts.user_stake;
         let clock = Clock::get()?;
         
+        require!(staking_pool.incident_exit_enabled, StakingError::IncidentExitNotEnabled);
+        
+        // Check the pool's circuit breaker
+        require!(staking_pool.paused_flags & PAUSE_UNSTAKING == 0, StakingError::UnstakingPaused);
+        
         // Ensure stake is still active
         require!(!user_stake.withdrawn, StakingError::StakeAlreadyWithdrawn);
         
-        // Check if lock period has ended
-        require!(
-            clock.unix_timestamp >= user_stake.end_timestamp,
-            StakingError::StakeLockNotExpired
-        );
+        // Frozen positions stay untouched until the freeze is lifted or runs out
+        settle_freeze(staking_pool, user_stake, clock.unix_timestamp)?;
         
-        // Calculate final reward if not claimed
-        if clock.unix_timestamp > user_stake.last_claim_timestamp {
-            let time_elapsed = clock
-                .unix_timestamp
-                .checked_sub(user_stake.last_claim_timestamp)
-                .unwrap();
-                
-            let final_reward = (user_stake.stake_amount as u128)
-                .checked_mul(staking_pool.reward_rate as u128)
-                .unwrap()
-                .checked_mul(time_elapsed as u128)
-                .unwrap()
-                .checked_div((365 * 24 * 60 * 60 * 10000) as u128)
-                .unwrap() as u64;
-                
-            user_stake.claimed_reward = user_stake.claimed_reward.checked_add(final_reward).unwrap();
-            
-            // Transfer final reward
-            let pool_seeds = &[
-                b"staking_pool".as_ref(),
-                staking_pool.token_mint.as_ref(),
-                &[staking_pool.bump],
-            ];
-            
-            token::transfer(
-                CpiContext::new_with_signer(
-                    ctx.accounts.token_program.to_account_info(),
-                    token::Transfer {
-                        from: ctx.accounts.treasury_token_account.to_account_info(),
-                        to: ctx.accounts.user_token_account.to_account_info(),
-                        authority: ctx.accounts.staking_pool.to_account_info(),
-                    },
-                    &[pool_seeds],
-                ),
-                final_reward,
+        // Tokenized positions are controlled by whoever holds the position NFT
+        require_position_holder(
+            user_stake,
+            ctx.accounts.user.key(),
+            ctx.accounts.position_token_account.as_ref(),
+        )?;
+        
+        // Registered governance power comes off with the position
+        deregister_exiting_position(
+            staking_pool,
+            user_stake,
+            ctx.remaining_accounts,
+            ctx.accounts.user.to_account_info(),
+        )?;
+        
+        // Settle accrual up to now; what the position earned stays in the pool's reserves
+        update_reward_accumulator(staking_pool, clock.unix_timestamp)?;
+        let forfeited_reward = pending_reward(staking_pool, user_stake)?;
+        bank_secondary_reward(staking_pool, user_stake)?;
+        let forfeited_secondary_reward = user_stake.secondary_reward_owed;
+        
+        let (amount, receipt_amount) = exit_position(staking_pool, user_stake)?;
+        if receipt_amount > 0 {
+            burn_receipt(
+                &ctx.accounts.receipt_mint,
+                &ctx.accounts.user_receipt_account,
+                &ctx.accounts.user,
+                &ctx.accounts.token_program,
+                receipt_amount,
             )?;
         }
         
-        // Return staked tokens
         let pool_seeds = &[
             b"staking_pool".as_ref(),
             staking_pool.token_mint.as_ref(),
+            &staking_pool.pool_id.to_le_bytes(),
             &[staking_pool.bump],
         ];
         
@@ -235,292 +899,7350 @@ pub mod wct_staking {
                 token::Transfer {
                     from: ctx.accounts.staking_vault.to_account_info(),
                     to: ctx.accounts.user_token_account.to_account_info(),
-                    authority: ctx.accounts.staking_pool.to_account_info(),
+                    authority: staking_pool.to_account_info(),
                 },
                 &[pool_seeds],
             ),
-            user_stake.stake_amount,
+            amount,
         )?;
         
-        // Update staking pool
-        staking_pool.total_staked = staking_pool.total_staked.checked_sub(user_stake.stake_amount).unwrap();
-        staking_pool.staker_count = staking_pool.staker_count.checked_sub(1).unwrap();
+        close_staker_position(staking_pool, &mut ctx.accounts.staker_info)?;
         
-        // Mark stake as withdrawn
-        user_stake.withdrawn = true;
+        // The forfeited rewards stay behind in the pool
+        user_stake.secondary_reward_owed = 0;
+        sync_reward_debt(staking_pool, user_stake)?;
         
-        // Emit unstake event
-        emit!(UnstakeEvent {
+        emit!(IncidentExitEvent {
             user: ctx.accounts.user.key(),
-            amount: user_stake.stake_amount,
-            total_rewards: user_stake.claimed_reward,
+            position_index: user_stake.position_index,
+            amount,
+            forfeited_reward,
+            forfeited_secondary_reward,
         });
         
         Ok(())
     }
 
-    // Update reward parameters (admin only)
-    pub fn update_reward_params(
-        ctx: Context<UpdateRewardParams>,
-        new_reward_rate: u64,
-        new_min_duration: i64,
-        new_max_duration: i64,
-    ) -> Result<()> {
+    // Restake a position's pending rewards as principal of the same position. Anyone may crank
+    // this for the owner; the rewards are paid straight into the staking vault.
+    pub fn compound(ctx: Context<Compound>) -> Result<()> {
         let staking_pool = &mut ctx.accounts.staking_pool;
+        let user_stake = &mut ctx.accounts.user_stake;
+        let clock = Clock::get()?;
         
-        // Update parameters
-        staking_pool.reward_rate = new_reward_rate;
-        staking_pool.min_stake_duration = new_min_duration;
-        staking_pool.max_stake_duration = new_max_duration;
+        // Check the pool's circuit breaker
+        require!(staking_pool.paused_flags & PAUSE_CLAIMS == 0, StakingError::ClaimsPaused);
         
-        // Emit event
-        emit!(ParamsUpdateEvent {
-            reward_rate: new_reward_rate,
-            min_stake_duration: new_min_duration,
-            max_stake_duration: new_max_duration,
-        });
+        // Retired pools take no new stake
+        require!(staking_pool.retire_at == 0, StakingError::PoolRetired);
         
-        Ok(())
-    }
-}
-
-#[derive(Accounts)]
-pub struct Initialize<'info> {
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + StakingPool::LEN,
-        seeds = [b"staking_pool".as_ref(), token_mint.key().as_ref()],
-        bump
-    )]
-    pub staking_pool: Account<'info, StakingPool>,
-    
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    
-    pub token_mint: Account<'info, Mint>,
-    
-    #[account(
-        constraint = treasury_token_account.mint == token_mint.key(),
-        constraint = treasury_token_account.owner == staking_pool.key(),
-    )]
-    pub treasury_token_account: Account<'info, TokenAccount>,
-    
-    #[account(
-        init,
-        payer = authority,
+        // Permissioned pools only serve wallets their compliance list lets through
+        require_compliant(
+            staking_pool,
+            ctx.accounts.owner.key(),
+            ctx.accounts.compliance_entry.as_ref(),
+            COMPLIANCE_STAKING | COMPLIANCE_CLAIMS,
+        )?;
+        
+        // Rewards can only become principal when they're paid in the staked token
+        require!(
+            staking_pool.reward_mint == staking_pool.token_mint,
+            StakingError::RewardMintMismatch
+        );
+        
+        // Ensure stake is still active
+        require!(!user_stake.withdrawn, StakingError::StakeAlreadyWithdrawn);
+        
+        // Frozen positions stay untouched until the freeze is lifted or runs out
+        settle_freeze(staking_pool, user_stake, clock.unix_timestamp)?;
+        require!(user_stake.stake_amount > 0, StakingError::NoRewardsYet);
+        
+        // Rate-limit claims to the pool's minimum interval
+        require_claim_interval(staking_pool, user_stake, clock.unix_timestamp)?;
+        
+        update_reward_accumulator(staking_pool, clock.unix_timestamp)?;
+        
+        // An NFT boost only lasts while the NFT is still held
+        refresh_nft_boost(
+            staking_pool,
+            user_stake,
+            ctx.accounts.owner.key(),
+            ctx.accounts.boost_nft_account.as_ref(),
+        )?;
+        let reward_amount = pending_reward(staking_pool, user_stake)?;
+        bank_secondary_reward(staking_pool, user_stake)?;
+        
+        // Active governance participants earn a bonus on top of the base rate
+        let participation_bonus = if has_participation_bonus(
+            staking_pool,
+            ctx.accounts.owner.key(),
+            ctx.accounts.governance_program.as_ref(),
+            ctx.accounts.governance.as_ref(),
+            ctx.accounts.voter_record.as_ref(),
+        )? {
+            participation_bonus_on(staking_pool, reward_amount)?
+        } else {
+            0
+        };
+        
+        // Wallets that have kept staking without a break earn a loyalty bonus too
+        let loyalty_bonus = loyalty_bonus_on(
+            ctx.accounts.staker_info.as_ref(),
+            reward_amount,
+            clock.unix_timestamp,
+        )?;
+        let reward_amount = reward_amount
+            .try_add(participation_bonus)?
+            .try_add(loyalty_bonus)?;
+        require!(reward_amount > 0, StakingError::NoRewardsYet);
+        
+        // Compounded rewards count towards the TVL cap like any new stake
+        require_within_tvl_cap(staking_pool, reward_amount)?;
+        
+        // Pay rewards straight into the staking vault; only what's left after the performance
+        // fee is compounded
+        let reward_amount = pay_reward(
+            staking_pool,
+            ctx.accounts.reward_mint.as_ref(),
+            &ctx.accounts.rewards_vault,
+            ctx.accounts.fee_vault.as_ref(),
+            ctx.accounts.staking_vault.to_account_info(),
+            &ctx.accounts.token_program,
+            reward_amount,
+        )?;
+        
+        // Compounded rewards become principal, so they get receipts too
+        mint_receipt(
+            staking_pool,
+            &ctx.accounts.receipt_mint,
+            ctx.accounts.owner_receipt_account.to_account_info(),
+            &ctx.accounts.token_program,
+            reward_amount,
+        )?;
+        
+        // Scale voting power up with the new balance, keeping the position's tier
+        let new_amount = user_stake.stake_amount.try_add(reward_amount)?;
+        let voting_power = (user_stake.voting_power as u128)
+            .try_mul(new_amount as u128)?
+            .try_div(user_stake.stake_amount as u128)? as u64;
+        set_voting_power(staking_pool, user_stake, voting_power)?;
+        user_stake.stake_amount = new_amount;
+        user_stake.claimed_reward = user_stake.claimed_reward.try_add(reward_amount)?;
+        user_stake.last_claim_timestamp = clock.unix_timestamp;
+        sync_reward_debt(staking_pool, user_stake)?;
+        
+        staking_pool.total_staked = staking_pool.total_staked.try_add(reward_amount)?;
+        add_reward_weight(staking_pool, reward_amount, user_stake.reward_multiplier_bps)?;
+        
+        emit!(CompoundEvent {
+            user: user_stake.owner,
+            position_index: user_stake.position_index,
+            compounded_amount: reward_amount,
+            participation_bonus,
+            loyalty_bonus,
+            stake_amount: user_stake.stake_amount,
+            voting_power: user_stake.voting_power,
+        });
+        
+        Ok(())
+    }
+
+    // Claim a position's pending rewards straight into a brand-new position locked for
+    // `duration`, so they start voting without passing through the wallet
+    pub fn claim_and_lock(ctx: Context<ClaimAndLock>, duration: i64) -> Result<()> {
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        let staker_info = &mut ctx.accounts.staker_info;
+        let user_stake = &mut ctx.accounts.user_stake;
+        let new_user_stake = &mut ctx.accounts.new_user_stake;
+        let clock = Clock::get()?;
+        
+        // Check the pool's circuit breaker
+        require!(staking_pool.paused_flags & PAUSE_CLAIMS == 0, StakingError::ClaimsPaused);
+        require!(staking_pool.paused_flags & PAUSE_STAKING == 0, StakingError::StakingPaused);
+        
+        // Retired pools take no new stake
+        require!(staking_pool.retire_at == 0, StakingError::PoolRetired);
+        
+        // Permissioned pools only serve wallets their compliance list lets through
+        require_compliant(
+            staking_pool,
+            ctx.accounts.user.key(),
+            ctx.accounts.compliance_entry.as_ref(),
+            COMPLIANCE_STAKING | COMPLIANCE_CLAIMS,
+        )?;
+        
+        // Rewards can only become principal when they're paid in the staked token
+        require!(
+            staking_pool.reward_mint == staking_pool.token_mint,
+            StakingError::RewardMintMismatch
+        );
+        
+        // Tokenized positions are controlled by whoever holds the position NFT
+        require_position_holder(
+            user_stake,
+            ctx.accounts.user.key(),
+            ctx.accounts.position_token_account.as_ref(),
+        )?;
+        
+        // Ensure stake is still active
+        require!(!user_stake.withdrawn, StakingError::StakeAlreadyWithdrawn);
+        
+        // Frozen positions stay untouched until the freeze is lifted or runs out
+        settle_freeze(staking_pool, user_stake, clock.unix_timestamp)?;
+        
+        // Rate-limit claims to the pool's minimum interval
+        require_claim_interval(staking_pool, user_stake, clock.unix_timestamp)?;
+        
+        // Validate stake duration
+        require!(
+            duration >= staking_pool.min_stake_duration && duration <= staking_pool.max_stake_duration,
+            StakingError::InvalidStakeDuration
+        );
+        
+        update_reward_accumulator(staking_pool, clock.unix_timestamp)?;
+        
+        // An NFT boost only lasts while the NFT is still held
+        refresh_nft_boost(
+            staking_pool,
+            user_stake,
+            ctx.accounts.user.key(),
+            ctx.accounts.boost_nft_account.as_ref(),
+        )?;
+        let reward_amount = pending_reward(staking_pool, user_stake)?;
+        bank_secondary_reward(staking_pool, user_stake)?;
+        
+        // Active governance participants earn a bonus on top of the base rate
+        let participation_bonus = if has_participation_bonus(
+            staking_pool,
+            ctx.accounts.user.key(),
+            ctx.accounts.governance_program.as_ref(),
+            ctx.accounts.governance.as_ref(),
+            ctx.accounts.voter_record.as_ref(),
+        )? {
+            participation_bonus_on(staking_pool, reward_amount)?
+        } else {
+            0
+        };
+        
+        // Wallets that have kept staking without a break earn a loyalty bonus too
+        let loyalty_bonus = loyalty_bonus_on(Some(&*staker_info), reward_amount, clock.unix_timestamp)?;
+        let reward_amount = reward_amount
+            .try_add(participation_bonus)?
+            .try_add(loyalty_bonus)?;
+        require!(reward_amount > 0, StakingError::NoRewardsYet);
+        
+        // The new position is held to the same limits as any other stake
+        require_within_tvl_cap(staking_pool, reward_amount)?;
+        
+        user_stake.claimed_reward = user_stake.claimed_reward.try_add(reward_amount)?;
+        user_stake.last_claim_timestamp = clock.unix_timestamp;
+        sync_reward_debt(staking_pool, user_stake)?;
+        
+        // Pay rewards straight into the staking vault; what's left after the performance fee
+        // becomes the new position
+        let amount = pay_reward(
+            staking_pool,
+            ctx.accounts.reward_mint.as_ref(),
+            &ctx.accounts.rewards_vault,
+            ctx.accounts.fee_vault.as_ref(),
+            ctx.accounts.staking_vault.to_account_info(),
+            &ctx.accounts.token_program,
+            reward_amount,
+        )?;
+        require!(amount >= staking_pool.min_stake_amount, StakingError::StakeTooSmall);
+        
+        // Claim the next position index for this user
+        let position_index = staker_info.position_count;
+        staker_info.owner = ctx.accounts.user.key();
+        staker_info.pool = staking_pool.key();
+        staker_info.position_count = position_index.try_add(1)?;
+        staker_info.bump = *ctx.bumps.get("staker_info").unwrap();
+        register_position(
+            &mut ctx.accounts.stake_registry,
+            ctx.accounts.user.key(),
+            new_user_stake.key(),
+            *ctx.bumps.get("stake_registry").unwrap(),
+        )?;
+        
+        // Setup the new position
+        let end_timestamp = clock.unix_timestamp + duration;
+        new_user_stake.owner = ctx.accounts.user.key();
+        new_user_stake.creator = ctx.accounts.user.key();
+        new_user_stake.pool = staking_pool.key();
+        new_user_stake.position_mint = Pubkey::default();
+        new_user_stake.pending_owner = Pubkey::default();
+        new_user_stake.reward_destination = Pubkey::default();
+        new_user_stake.frozen_until = 0;
+        new_user_stake.frozen_reward = 0;
+        new_user_stake.reward_owed = 0;
+        new_user_stake.slashed_receipts = 0;
+        new_user_stake.unbonding_queue_ticket = 0;
+        new_user_stake.position_index = position_index;
+        new_user_stake.bump = *ctx.bumps.get("new_user_stake").unwrap();
+        new_user_stake.stake_amount = amount;
+        new_user_stake.start_timestamp = clock.unix_timestamp;
+        new_user_stake.end_timestamp = end_timestamp;
+        new_user_stake.lock_duration = duration;
+        new_user_stake.auto_relock = false;
+        new_user_stake.claimed_reward = 0;
+        new_user_stake.last_claim_timestamp = clock.unix_timestamp;
+        new_user_stake.withdrawn = false;
+        
+        // Calculate reputation boost, voting power and reward rate based on the duration's tier
+        let tier = ctx.accounts.tier_config.tier_for(duration);
+        new_user_stake.reputation_boost = tier.reputation_boost;
+        let voting_power = voting_power_for(staking_pool.tokens_per_vote, amount, &tier)?;
+        set_voting_power(staking_pool, new_user_stake, voting_power)?;
+        new_user_stake.reward_multiplier_bps = tier.reward_multiplier_bps;
+        new_user_stake.nft_boost_mint = Pubkey::default();
+        new_user_stake.nft_boost_bps = 0;
+        sync_reward_debt(staking_pool, new_user_stake)?;
+        
+        // Update staking pool
+        staking_pool.total_staked = staking_pool.total_staked.try_add(amount)?;
+        add_reward_weight(staking_pool, amount, new_user_stake.reward_multiplier_bps)?;
+        open_staker_position(staking_pool, staker_info, clock.unix_timestamp)?;
+        
+        // Mint the matching stWCT receipt
+        mint_receipt(
+            staking_pool,
+            &ctx.accounts.receipt_mint,
+            ctx.accounts.user_receipt_account.to_account_info(),
+            &ctx.accounts.token_program,
+            amount,
+        )?;
+        
+        emit!(StakeEvent {
+            user: ctx.accounts.user.key(),
+            position_index,
+            amount,
+            duration,
+            end_timestamp,
+            reputation_boost: new_user_stake.reputation_boost,
+            voting_power: new_user_stake.voting_power,
+            reward_multiplier_bps: new_user_stake.reward_multiplier_bps,
+            reward_debt: new_user_stake.reward_debt,
+            total_staked: staking_pool.total_staked,
+            reward_reserve: staking_pool.reward_reserve,
+            reward_per_token_stored: staking_pool.reward_per_token_stored,
+        });
+        
+        Ok(())
+    }
+
+    // Unstake some or all of a position's tokens after the lock period
+    pub fn unstake(ctx: Context<Unstake>, amount: u64) -> Result<()> {
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        let user_stake = &mut ctx.accounts.user_stake;
+        let clock = Clock::get()?;
+        
+        // Check the pool's circuit breaker
+        require!(staking_pool.paused_flags & PAUSE_UNSTAKING == 0, StakingError::UnstakingPaused);
+        
+        // Tokenized positions are controlled by whoever holds the position NFT
+        require_position_holder(
+            user_stake,
+            ctx.accounts.user.key(),
+            ctx.accounts.position_token_account.as_ref(),
+        )?;
+        
+        // Ensure stake is still active
+        require!(!user_stake.withdrawn, StakingError::StakeAlreadyWithdrawn);
+        
+        // Registered governance power has to come off before the stake can leave the position
+        require_unregistered(user_stake)?;
+        
+        // Frozen positions stay untouched until the freeze is lifted or runs out
+        settle_freeze(staking_pool, user_stake, clock.unix_timestamp)?;
+        
+        // Check if lock period has ended, or the pool has retired
+        require!(
+            lock_released(staking_pool, user_stake, clock.unix_timestamp),
+            StakingError::StakeLockNotExpired
+        );
+        
+        // Pools with an unbonding period or queue exit through request_unstake and withdraw
+        require!(
+            staking_pool.unbonding_period == 0 && staking_pool.unbonding_budget == 0,
+            StakingError::UnbondingRequired
+        );
+        
+        require!(
+            amount > 0 && amount <= user_stake.stake_amount,
+            StakingError::InvalidUnstakeAmount
+        );
+        
+        // Settle rewards earned on the full balance so far
+        update_reward_accumulator(staking_pool, clock.unix_timestamp)?;
+        let final_reward = pending_reward(staking_pool, user_stake)?;
+        bank_secondary_reward(staking_pool, user_stake)?;
+        if final_reward > 0 {
+            user_stake.claimed_reward = user_stake.claimed_reward.try_add(final_reward)?;
+            user_stake.last_claim_timestamp = clock.unix_timestamp;
+            
+            // Pay final reward wherever the owner routed rewards
+            require_reward_destination(user_stake, &ctx.accounts.user_reward_account)?;
+            distribute_reward(
+                staking_pool,
+                ctx.accounts.reward_mint.as_ref(),
+                &ctx.accounts.rewards_vault,
+                ctx.accounts.fee_vault.as_ref(),
+                ctx.accounts.vesting_vault.as_ref(),
+                ctx.accounts.reward_vesting.as_mut(),
+                &ctx.accounts.user_reward_account,
+                &ctx.accounts.token_program,
+                final_reward,
+            )?;
+        }
+        
+        // Burn the receipts for the tokens leaving the pool
+        let receipt_amount = receipts_to_burn(user_stake, amount)?;
+        burn_receipt(
+            &ctx.accounts.receipt_mint,
+            &ctx.accounts.user_receipt_account,
+            &ctx.accounts.user,
+            &ctx.accounts.token_program,
+            receipt_amount,
+        )?;
+        
+        // Return staked tokens
+        let pool_seeds = &[
+            b"staking_pool".as_ref(),
+            staking_pool.token_mint.as_ref(),
+            &staking_pool.pool_id.to_le_bytes(),
+            &[staking_pool.bump],
+        ];
+        
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.staking_vault.to_account_info(),
+                    to: ctx.accounts.user_token_account.to_account_info(),
+                    authority: ctx.accounts.staking_pool.to_account_info(),
+                },
+                &[pool_seeds],
+            ),
+            amount,
+        )?;
+        
+        let remaining_amount = reduce_position(staking_pool, user_stake, amount)?;
+        sync_reward_debt(staking_pool, user_stake)?;
+        
+        // Update staking pool
+        staking_pool.total_staked = staking_pool.total_staked.try_sub(amount)?;
+        remove_reward_weight(staking_pool, amount, user_stake.reward_multiplier_bps)?;
+        
+        // Mark stake as withdrawn once nothing is left, including tokens still unbonding
+        if remaining_amount == 0 && user_stake.unbonding_amount == 0 {
+            close_staker_position(staking_pool, &mut ctx.accounts.staker_info)?;
+            user_stake.withdrawn = true;
+        }
+        
+        // Emit unstake event
+        emit!(UnstakeEvent {
+            user: ctx.accounts.user.key(),
+            position_index: user_stake.position_index,
+            amount,
+            remaining_amount,
+            total_rewards: user_stake.claimed_reward,
+            unbonding_amount: user_stake.unbonding_amount,
+            voting_power: user_stake.voting_power,
+            reward_debt: user_stake.reward_debt,
+            withdrawn: user_stake.withdrawn,
+            total_staked: staking_pool.total_staked,
+            reward_reserve: staking_pool.reward_reserve,
+            reward_per_token_stored: staking_pool.reward_per_token_stored,
+        });
+        
+        Ok(())
+    }
+
+    // Start unbonding part of an expired position. Its voting power drops immediately and the
+    // tokens can be withdrawn once the pool's unbonding period has elapsed.
+    pub fn request_unstake(ctx: Context<Unstake>, amount: u64) -> Result<()> {
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        let user_stake = &mut ctx.accounts.user_stake;
+        let clock = Clock::get()?;
+        
+        // Check the pool's circuit breaker
+        require!(staking_pool.paused_flags & PAUSE_UNSTAKING == 0, StakingError::UnstakingPaused);
+        
+        // Tokenized positions are controlled by whoever holds the position NFT
+        require_position_holder(
+            user_stake,
+            ctx.accounts.user.key(),
+            ctx.accounts.position_token_account.as_ref(),
+        )?;
+        
+        // Ensure stake is still active
+        require!(!user_stake.withdrawn, StakingError::StakeAlreadyWithdrawn);
+        
+        // Registered governance power has to come off before the stake can leave the position
+        require_unregistered(user_stake)?;
+        
+        // Frozen positions stay untouched until the freeze is lifted or runs out
+        settle_freeze(staking_pool, user_stake, clock.unix_timestamp)?;
+        
+        // Check if lock period has ended, or the pool has retired
+        require!(
+            lock_released(staking_pool, user_stake, clock.unix_timestamp),
+            StakingError::StakeLockNotExpired
+        );
+        
+        require!(
+            amount > 0 && amount <= user_stake.stake_amount,
+            StakingError::InvalidUnstakeAmount
+        );
+        
+        // Settle rewards earned on the full balance so far; unbonding tokens earn nothing
+        update_reward_accumulator(staking_pool, clock.unix_timestamp)?;
+        let final_reward = pending_reward(staking_pool, user_stake)?;
+        bank_secondary_reward(staking_pool, user_stake)?;
+        if final_reward > 0 {
+            user_stake.claimed_reward = user_stake.claimed_reward.try_add(final_reward)?;
+            user_stake.last_claim_timestamp = clock.unix_timestamp;
+            
+            require_reward_destination(user_stake, &ctx.accounts.user_reward_account)?;
+            distribute_reward(
+                staking_pool,
+                ctx.accounts.reward_mint.as_ref(),
+                &ctx.accounts.rewards_vault,
+                ctx.accounts.fee_vault.as_ref(),
+                ctx.accounts.vesting_vault.as_ref(),
+                ctx.accounts.reward_vesting.as_mut(),
+                &ctx.accounts.user_reward_account,
+                &ctx.accounts.token_program,
+                final_reward,
+            )?;
+        }
+        
+        // Receipts are burned when unbonding starts, since the tokens stop counting as stake
+        let receipt_amount = receipts_to_burn(user_stake, amount)?;
+        burn_receipt(
+            &ctx.accounts.receipt_mint,
+            &ctx.accounts.user_receipt_account,
+            &ctx.accounts.user,
+            &ctx.accounts.token_program,
+            receipt_amount,
+        )?;
+        
+        reduce_position(staking_pool, user_stake, amount)?;
+        sync_reward_debt(staking_pool, user_stake)?;
+        
+        // A new request adds to any pending one and restarts the cooldown
+        user_stake.unbonding_amount = user_stake.unbonding_amount.try_add(amount)?;
+        user_stake.unbonding_ends_at = clock.unix_timestamp + staking_pool.unbonding_period;
+        
+        // With a budget configured, the request joins the back of the unbonding queue along with
+        // anything the position already had unbonding
+        if staking_pool.unbonding_budget > 0 {
+            staking_pool.unbonding_queue_tail = staking_pool.unbonding_queue_tail.try_add(amount)?;
+            user_stake.unbonding_queue_ticket = staking_pool.unbonding_queue_tail;
+        }
+        
+        staking_pool.total_staked = staking_pool.total_staked.try_sub(amount)?;
+        remove_reward_weight(staking_pool, amount, user_stake.reward_multiplier_bps)?;
+        
+        emit!(UnstakeRequestedEvent {
+            user: ctx.accounts.user.key(),
+            position_index: user_stake.position_index,
+            amount,
+            unbonding_amount: user_stake.unbonding_amount,
+            unbonding_ends_at: user_stake.unbonding_ends_at,
+            unbonding_queue_ticket: user_stake.unbonding_queue_ticket,
+        });
+        
+        Ok(())
+    }
+
+    // Withdraw a position's unbonded tokens once the cooldown has elapsed
+    pub fn withdraw(ctx: Context<Withdraw>) -> Result<()> {
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        let user_stake = &mut ctx.accounts.user_stake;
+        let clock = Clock::get()?;
+        
+        // Check the pool's circuit breaker
+        require!(staking_pool.paused_flags & PAUSE_UNSTAKING == 0, StakingError::UnstakingPaused);
+        
+        // Tokenized positions are controlled by whoever holds the position NFT
+        require_position_holder(
+            user_stake,
+            ctx.accounts.user.key(),
+            ctx.accounts.position_token_account.as_ref(),
+        )?;
+        
+        // Frozen positions stay untouched until the freeze is lifted or runs out
+        settle_freeze(staking_pool, user_stake, clock.unix_timestamp)?;
+        
+        let amount = user_stake.unbonding_amount;
+        require!(amount > 0, StakingError::NothingUnbonding);
+        require!(
+            clock.unix_timestamp >= user_stake.unbonding_ends_at,
+            StakingError::UnbondingNotComplete
+        );
+        
+        // Wait for the unbonding queue to reach the position
+        advance_unbonding_queue(staking_pool, clock.unix_timestamp)?;
+        require_unbonding_released(staking_pool, user_stake)?;
+        
+        let pool_seeds = &[
+            b"staking_pool".as_ref(),
+            staking_pool.token_mint.as_ref(),
+            &staking_pool.pool_id.to_le_bytes(),
+            &[staking_pool.bump],
+        ];
+        
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.staking_vault.to_account_info(),
+                    to: ctx.accounts.user_token_account.to_account_info(),
+                    authority: staking_pool.to_account_info(),
+                },
+                &[pool_seeds],
+            ),
+            amount,
+        )?;
+        
+        user_stake.unbonding_amount = 0;
+        user_stake.unbonding_queue_ticket = 0;
+        
+        // Mark stake as withdrawn once nothing is left
+        if user_stake.stake_amount == 0 {
+            close_staker_position(staking_pool, &mut ctx.accounts.staker_info)?;
+            user_stake.withdrawn = true;
+        }
+        
+        emit!(UnstakeEvent {
+            user: ctx.accounts.user.key(),
+            position_index: user_stake.position_index,
+            amount,
+            remaining_amount: user_stake.stake_amount,
+            total_rewards: user_stake.claimed_reward,
+            unbonding_amount: user_stake.unbonding_amount,
+            voting_power: user_stake.voting_power,
+            reward_debt: user_stake.reward_debt,
+            withdrawn: user_stake.withdrawn,
+            total_staked: staking_pool.total_staked,
+            reward_reserve: staking_pool.reward_reserve,
+            reward_per_token_stored: staking_pool.reward_per_token_stored,
+        });
+        
+        Ok(())
+    }
+
+    // Close a fully withdrawn position and return its rent to the owner
+    pub fn close_stake_account(ctx: Context<CloseStakeAccount>) -> Result<()> {
+        let user_stake = &ctx.accounts.user_stake;
+        
+        // Only dead positions can be closed
+        require!(user_stake.withdrawn, StakingError::StakeNotWithdrawn);
+        
+        // Don't let a close throw away secondary rewards that are still owed
+        require!(user_stake.secondary_reward_owed == 0, StakingError::SecondaryRewardOutstanding);
+        
+        // Tokenized positions are controlled by whoever holds the position NFT
+        require_position_holder(
+            user_stake,
+            ctx.accounts.user.key(),
+            ctx.accounts.position_token_account.as_ref(),
+        )?;
+        
+        // Drop the position from its creator's registry
+        let position = user_stake.key();
+        ctx.accounts.stake_registry.positions.retain(|key| *key != position);
+        
+        emit!(StakeAccountClosedEvent {
+            user: ctx.accounts.user.key(),
+            position_index: user_stake.position_index,
+        });
+        
+        Ok(())
+    }
+
+    // Represent a position as an NFT held by its owner. The NFT's metadata names the position
+    // and points at `uri` for its details; whoever holds the NFT controls the position.
+    pub fn tokenize_position(ctx: Context<TokenizePosition>, uri: String) -> Result<()> {
+        let staking_pool = &ctx.accounts.staking_pool;
+        let user_stake = &mut ctx.accounts.user_stake;
+        
+        // Ensure stake is still active
+        require!(!user_stake.withdrawn, StakingError::StakeAlreadyWithdrawn);
+        
+        // Frozen positions can't change hands
+        require_not_frozen(user_stake, Clock::get()?.unix_timestamp)?;
+        require!(user_stake.position_mint == Pubkey::default(), StakingError::PositionAlreadyTokenized);
+        
+        // Registered governance power has to come off before the stake can leave the position
+        require_unregistered(user_stake)?;
+        require!(uri.len() <= MAX_METADATA_URI_LENGTH, StakingError::InvalidMetadataUri);
+        
+        // Metadata lives at the token metadata program's PDA for the mint
+        let position_mint = ctx.accounts.position_mint.key();
+        let (expected_metadata, _) = Pubkey::find_program_address(
+            &[b"metadata".as_ref(), TOKEN_METADATA_PROGRAM_ID.as_ref(), position_mint.as_ref()],
+            &TOKEN_METADATA_PROGRAM_ID,
+        );
+        require!(
+            ctx.accounts.metadata.key() == expected_metadata,
+            StakingError::InvalidMetadataAccount
+        );
+        
+        let pool_seeds = &[
+            b"staking_pool".as_ref(),
+            staking_pool.token_mint.as_ref(),
+            &staking_pool.pool_id.to_le_bytes(),
+            &[staking_pool.bump],
+        ];
+        
+        // Mint the single position token to the owner
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::MintTo {
+                    mint: ctx.accounts.position_mint.to_account_info(),
+                    to: ctx.accounts.user_position_account.to_account_info(),
+                    authority: ctx.accounts.staking_pool.to_account_info(),
+                },
+                &[pool_seeds],
+            ),
+            1,
+        )?;
+        
+        // CreateMetadataAccountV3 with the pool as mint and update authority
+        let name = format!("WCT Stake #{}", user_stake.position_index);
+        let mut data = vec![CREATE_METADATA_ACCOUNT_V3_INSTRUCTION];
+        for field in [name.as_bytes(), POSITION_NFT_SYMBOL.as_bytes(), uri.as_bytes()] {
+            data.extend_from_slice(&(field.len() as u32).to_le_bytes());
+            data.extend_from_slice(field);
+        }
+        data.extend_from_slice(&0u16.to_le_bytes()); // seller_fee_basis_points
+        data.push(0); // creators: None
+        data.push(0); // collection: None
+        data.push(0); // uses: None
+        data.push(1); // is_mutable
+        data.push(0); // collection_details: None
+        
+        let instruction = Instruction {
+            program_id: TOKEN_METADATA_PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new(ctx.accounts.metadata.key(), false),
+                AccountMeta::new_readonly(position_mint, false),
+                AccountMeta::new_readonly(staking_pool.key(), true),
+                AccountMeta::new(ctx.accounts.user.key(), true),
+                AccountMeta::new_readonly(staking_pool.key(), true),
+                AccountMeta::new_readonly(ctx.accounts.system_program.key(), false),
+            ],
+            data,
+        };
+        invoke_signed(
+            &instruction,
+            &[
+                ctx.accounts.metadata.to_account_info(),
+                ctx.accounts.position_mint.to_account_info(),
+                ctx.accounts.staking_pool.to_account_info(),
+                ctx.accounts.user.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+                ctx.accounts.token_metadata_program.to_account_info(),
+            ],
+            &[pool_seeds],
+        )?;
+        
+        // Fix the supply at one
+        token::set_authority(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::SetAuthority {
+                    current_authority: ctx.accounts.staking_pool.to_account_info(),
+                    account_or_mint: ctx.accounts.position_mint.to_account_info(),
+                },
+                &[pool_seeds],
+            ),
+            AuthorityType::MintTokens,
+            None,
+        )?;
+        
+        user_stake.position_mint = position_mint;
+        
+        emit!(PositionTokenizedEvent {
+            user: ctx.accounts.user.key(),
+            position_index: user_stake.position_index,
+            position_mint,
+        });
+        
+        Ok(())
+    }
+
+    // Point a tokenized position's owner at the current NFT holder. Anyone can call this, so a
+    // buyer can sync in the same transaction as the purchase.
+    pub fn sync_position_owner(ctx: Context<SyncPositionOwner>) -> Result<()> {
+        let user_stake = &mut ctx.accounts.user_stake;
+        let new_owner = ctx.accounts.position_token_account.owner;
+        
+        let previous_owner = user_stake.owner;
+        user_stake.owner = new_owner;
+        
+        // The previous holder's reward routing doesn't carry over
+        if new_owner != previous_owner {
+            user_stake.reward_destination = Pubkey::default();
+        }
+        
+        emit!(PositionOwnerSyncedEvent {
+            position_mint: user_stake.position_mint,
+            position_index: user_stake.position_index,
+            previous_owner,
+            new_owner,
+        });
+        
+        Ok(())
+    }
+
+    // Freeze a disputed position for `duration` seconds: it stops earning rewards and can't be
+    // claimed, unstaked, moved or transferred until governance unfreezes it or the freeze runs
+    // out. Freezing a frozen position sets a new expiry. Governance only.
+    pub fn freeze_position(ctx: Context<FreezePosition>, duration: i64) -> Result<()> {
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        let user_stake = &mut ctx.accounts.user_stake;
+        let clock = Clock::get()?;
+        
+        require!(
+            duration > 0 && duration <= MAX_FREEZE_DURATION,
+            StakingError::InvalidFreezeDuration
+        );
+        
+        // Ensure stake is still active
+        require!(!user_stake.withdrawn, StakingError::StakeAlreadyWithdrawn);
+        
+        // Set aside what the position has earned so far and take it out of the reward weight
+        if user_stake.frozen_until == 0 {
+            update_reward_accumulator(staking_pool, clock.unix_timestamp)?;
+            user_stake.frozen_reward = earned_reward(staking_pool, user_stake)?;
+            bank_secondary_reward(staking_pool, user_stake)?;
+            remove_reward_weight(staking_pool, user_stake.stake_amount, user_stake.reward_multiplier_bps)?;
+        }
+        user_stake.frozen_until = clock.unix_timestamp.try_add(duration)?;
+        
+        emit!(PositionFrozenEvent {
+            owner: user_stake.owner,
+            position: user_stake.key(),
+            frozen_until: user_stake.frozen_until,
+            frozen_reward: user_stake.frozen_reward,
+        });
+        
+        Ok(())
+    }
+
+    // Lift a freeze before it runs out (governance only). Expired freezes are lifted
+    // automatically the next time the position is used.
+    pub fn unfreeze_position(ctx: Context<FreezePosition>) -> Result<()> {
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        let user_stake = &mut ctx.accounts.user_stake;
+        let clock = Clock::get()?;
+        
+        require!(user_stake.frozen_until != 0, StakingError::PositionNotFrozen);
+        lift_freeze(staking_pool, user_stake, clock.unix_timestamp)?;
+        
+        emit!(PositionUnfrozenEvent {
+            owner: user_stake.owner,
+            position: user_stake.key(),
+        });
+        
+        Ok(())
+    }
+
+    // Confiscate `slash_bps` of a position's stake to the treasury, as approved by an executed
+    // governance proposal whose action hash commits to the position and `slash_bps`. Anyone can
+    // apply an approved slash, once per proposal. Power the position has registered with the
+    // realm is cut down in the same instruction, so its registry accounts (voting power
+    // registry, voter power and page) follow as remaining accounts while it is registered. The
+    // owner's stWCT receipts for the slashed tokens are left unbacked and burned with the
+    // position's next exit.
+    pub fn slash_stake<'info>(
+        ctx: Context<'_, '_, '_, 'info, SlashStake<'info>>,
+        slash_bps: u16,
+    ) -> Result<()> {
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        let user_stake = &mut ctx.accounts.user_stake;
+        let clock = Clock::get()?;
+        
+        // Ensure stake is still active
+        require!(!user_stake.withdrawn, StakingError::StakeAlreadyWithdrawn);
+        
+        // Slashing settles the dispute a freeze was holding the position for
+        if user_stake.frozen_until != 0 {
+            lift_freeze(staking_pool, user_stake, clock.unix_timestamp)?;
+        }
+        require!(
+            slash_bps > 0 && slash_bps <= 10000,
+            StakingError::InvalidSlashAmount
+        );
+        
+        // assert_proposal_approved(Other, action_hash) fails unless the proposal was executed
+        // and approved exactly this slash
+        let action_hash = slash_action_hash(&user_stake.key(), slash_bps);
+        let mut data = hash(b"global:assert_proposal_approved").to_bytes()[..8].to_vec();
+        data.push(OTHER_PROPOSAL_TYPE);
+        data.extend_from_slice(&action_hash);
+        
+        let ix = Instruction {
+            program_id: ctx.accounts.governance_program.key(),
+            accounts: vec![
+                AccountMeta::new_readonly(ctx.accounts.governance.key(), false),
+                AccountMeta::new_readonly(ctx.accounts.proposal.key(), false),
+            ],
+            data,
+        };
+        invoke(
+            &ix,
+            &[
+                ctx.accounts.governance.to_account_info(),
+                ctx.accounts.proposal.to_account_info(),
+            ],
+        )?;
+        
+        let amount = apply_slash(staking_pool, user_stake, slash_bps, clock.unix_timestamp)?;
+        
+        let pool_seeds = &[
+            b"staking_pool".as_ref(),
+            staking_pool.token_mint.as_ref(),
+            &staking_pool.pool_id.to_le_bytes(),
+            &[staking_pool.bump],
+        ];
+        
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.staking_vault.to_account_info(),
+                    to: ctx.accounts.treasury_token_account.to_account_info(),
+                    authority: staking_pool.to_account_info(),
+                },
+                &[pool_seeds],
+            ),
+            amount,
+        )?;
+        
+        // Mark stake as withdrawn once nothing is left, including tokens still unbonding
+        if user_stake.stake_amount == 0 && user_stake.unbonding_amount == 0 {
+            close_staker_position(staking_pool, &mut ctx.accounts.staker_info)?;
+            user_stake.withdrawn = true;
+        }
+        
+        // Cut the registered power down to what is left of the position. Power registered to a
+        // previous owner, or to a position that is now empty, comes off entirely.
+        if user_stake.registered_voting_power > 0 {
+            require!(ctx.remaining_accounts.len() == 3, StakingError::InvalidGovernanceAccounts);
+            let realm = RealmRegistration {
+                governance_program: ctx.accounts.governance_program.to_account_info(),
+                governance: ctx.accounts.governance.to_account_info(),
+                voting_power_registry: ctx.remaining_accounts[0].clone(),
+                voter_power: ctx.remaining_accounts[1].clone(),
+                voting_power_page: ctx.remaining_accounts[2].clone(),
+                payer: ctx.accounts.payer.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                rent: ctx.accounts.rent.to_account_info(),
+            };
+            let voter = user_stake.registered_voter;
+            let (voting_power, reputation_boost) = if user_stake.withdrawn || voter != user_stake.owner {
+                (0, 0)
+            } else {
+                (user_stake.voting_power, user_stake.reputation_boost)
+            };
+            register_position_power(staking_pool, user_stake, &realm, voter, voting_power, reputation_boost)?;
+        }
+        
+        let receipt = &mut ctx.accounts.slash_receipt;
+        receipt.proposal = ctx.accounts.proposal.key();
+        receipt.position = user_stake.key();
+        receipt.applied_at = clock.unix_timestamp;
+        receipt.bump = *ctx.bumps.get("slash_receipt").unwrap();
+        
+        emit!(StakeSlashedEvent {
+            owner: user_stake.owner,
+            position_index: user_stake.position_index,
+            proposal: ctx.accounts.proposal.key(),
+            slash_bps,
+            amount,
+            remaining_amount: user_stake.stake_amount,
+        });
+        
+        Ok(())
+    }
+
+    // Offer a position to a new wallet, keeping its lock, boost and rewards. The new owner
+    // completes the move with accept_stake_ownership; offering to the default key cancels.
+    pub fn transfer_stake_ownership(ctx: Context<TransferStakeOwnership>, new_owner: Pubkey) -> Result<()> {
+        let user_stake = &mut ctx.accounts.user_stake;
+        
+        // Ensure stake is still active
+        require!(!user_stake.withdrawn, StakingError::StakeAlreadyWithdrawn);
+        
+        // Frozen positions can't change hands
+        require_not_frozen(user_stake, Clock::get()?.unix_timestamp)?;
+        
+        // Tokenized positions change hands with their NFT
+        require!(user_stake.position_mint == Pubkey::default(), StakingError::PositionTokenized);
+        require!(new_owner != user_stake.owner, StakingError::InvalidNewOwner);
+        
+        user_stake.pending_owner = new_owner;
+        
+        emit!(StakeOwnershipOfferedEvent {
+            owner: user_stake.owner,
+            position_index: user_stake.position_index,
+            pending_owner: new_owner,
+        });
+        
+        Ok(())
+    }
+
+    // Accept a position offered with transfer_stake_ownership
+    pub fn accept_stake_ownership(ctx: Context<AcceptStakeOwnership>) -> Result<()> {
+        let user_stake = &mut ctx.accounts.user_stake;
+        
+        // Ensure stake is still active
+        require!(!user_stake.withdrawn, StakingError::StakeAlreadyWithdrawn);
+        
+        // Frozen positions can't change hands
+        require_not_frozen(user_stake, Clock::get()?.unix_timestamp)?;
+        
+        // An offer made before the position was tokenized no longer applies
+        require!(user_stake.position_mint == Pubkey::default(), StakingError::PositionTokenized);
+        
+        // Registered governance power has to come off before the stake can leave the position
+        require_unregistered(user_stake)?;
+        require!(
+            user_stake.pending_owner != Pubkey::default()
+                && user_stake.pending_owner == ctx.accounts.new_owner.key(),
+            StakingError::NotPendingOwner
+        );
+        
+        let previous_owner = user_stake.owner;
+        user_stake.owner = ctx.accounts.new_owner.key();
+        user_stake.pending_owner = Pubkey::default();
+        
+        // The previous owner's reward routing doesn't carry over
+        user_stake.reward_destination = Pubkey::default();
+        
+        emit!(StakeOwnershipTransferredEvent {
+            previous_owner,
+            new_owner: user_stake.owner,
+            position_index: user_stake.position_index,
+        });
+        
+        Ok(())
+    }
+
+    // Authorize `session_signer` to claim rewards for the caller's positions for the next
+    // `duration` seconds. Calling it again for the same key replaces the expiry.
+    pub fn create_session_key(
+        ctx: Context<CreateSessionKey>,
+        session_signer: Pubkey,
+        duration: i64,
+    ) -> Result<()> {
+        require!(
+            duration > 0 && duration <= MAX_SESSION_DURATION,
+            StakingError::InvalidSessionDuration
+        );
+        
+        let clock = Clock::get()?;
+        let session_key = &mut ctx.accounts.session_key;
+        session_key.owner = ctx.accounts.owner.key();
+        session_key.session_signer = session_signer;
+        session_key.expires_at = clock.unix_timestamp.try_add(duration)?;
+        session_key.bump = *ctx.bumps.get("session_key").unwrap();
+        
+        emit!(SessionKeyCreatedEvent {
+            owner: session_key.owner,
+            session_signer,
+            expires_at: session_key.expires_at,
+        });
+        
+        Ok(())
+    }
+
+    // Revoke a session key before it expires, refunding its rent to the owner
+    pub fn revoke_session_key(ctx: Context<RevokeSessionKey>) -> Result<()> {
+        emit!(SessionKeyRevokedEvent {
+            owner: ctx.accounts.owner.key(),
+            session_signer: ctx.accounts.session_key.session_signer,
+        });
+        
+        Ok(())
+    }
+
+    // Extend a position's lock to end `new_duration` seconds from now, moving it into the
+    // tier of its new remaining lock if that tier is higher
+    pub fn extend_lock(ctx: Context<ExtendLock>, new_duration: i64) -> Result<()> {
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        let user_stake = &mut ctx.accounts.user_stake;
+        let clock = Clock::get()?;
+        
+        // Tokenized positions are controlled by whoever holds the position NFT
+        require_position_holder(
+            user_stake,
+            ctx.accounts.user.key(),
+            ctx.accounts.position_token_account.as_ref(),
+        )?;
+        
+        // Ensure stake is still active
+        require!(!user_stake.withdrawn, StakingError::StakeAlreadyWithdrawn);
+        
+        // Frozen positions stay untouched until the freeze is lifted or runs out
+        settle_freeze(staking_pool, user_stake, clock.unix_timestamp)?;
+        
+        // Validate stake duration
+        require!(
+            new_duration >= staking_pool.min_stake_duration && new_duration <= staking_pool.max_stake_duration,
+            StakingError::InvalidStakeDuration
+        );
+        
+        // The lock can only be pushed further out
+        let old_end_timestamp = user_stake.end_timestamp;
+        let new_end_timestamp = clock.unix_timestamp + new_duration;
+        require!(
+            new_end_timestamp > old_end_timestamp,
+            StakingError::LockNotExtended
+        );
+        
+        user_stake.end_timestamp = new_end_timestamp;
+        user_stake.lock_duration = new_duration;
+        
+        // Never downgrade a position that was locked into a higher tier originally
+        let tier = ctx.accounts.tier_config.tier_for(new_duration);
+        user_stake.reputation_boost = user_stake.reputation_boost.max(tier.reputation_boost);
+        let tier_voting_power = boosted_voting_power(
+            voting_power_for(staking_pool.tokens_per_vote, user_stake.stake_amount, &tier)?,
+            user_stake.nft_boost_bps,
+        )?;
+        let voting_power = user_stake.voting_power.max(tier_voting_power);
+        set_voting_power(staking_pool, user_stake, voting_power)?;
+        
+        // Rewards switch to the higher tier rate from now on, keeping any NFT boost on top
+        let reward_multiplier_bps = tier
+            .reward_multiplier_bps
+            .try_add(user_stake.nft_boost_bps)?;
+        if reward_multiplier_bps > user_stake.reward_multiplier_bps {
+            update_reward_accumulator(staking_pool, clock.unix_timestamp)?;
+            set_reward_multiplier(staking_pool, user_stake, reward_multiplier_bps)?;
+        }
+        
+        emit!(LockExtendedEvent {
+            user: ctx.accounts.user.key(),
+            position_index: user_stake.position_index,
+            old_end_timestamp,
+            new_end_timestamp,
+            reputation_boost: user_stake.reputation_boost,
+            voting_power: user_stake.voting_power,
+        });
+        
+        Ok(())
+    }
+
+    // Carve `amount` out of a position into a new, independent position owned by the caller.
+    // Both keep the original lock and tier; pending rewards and voting power split pro rata.
+    pub fn split_stake(ctx: Context<SplitStake>, amount: u64) -> Result<()> {
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        let staker_info = &mut ctx.accounts.staker_info;
+        let user_stake = &mut ctx.accounts.user_stake;
+        let new_user_stake = &mut ctx.accounts.new_user_stake;
+        let clock = Clock::get()?;
+        
+        // Tokenized positions are controlled by whoever holds the position NFT
+        require_position_holder(
+            user_stake,
+            ctx.accounts.user.key(),
+            ctx.accounts.position_token_account.as_ref(),
+        )?;
+        
+        // Ensure stake is still active
+        require!(!user_stake.withdrawn, StakingError::StakeAlreadyWithdrawn);
+        
+        // Registered governance power has to come off before the stake can leave the position
+        require_unregistered(user_stake)?;
+        
+        // Frozen positions stay untouched until the freeze is lifted or runs out
+        settle_freeze(staking_pool, user_stake, clock.unix_timestamp)?;
+        
+        // Both halves must be non-empty
+        require!(
+            amount > 0 && amount < user_stake.stake_amount,
+            StakingError::InvalidSplitAmount
+        );
+        
+        // Settle rewards up to now so they can be divided between the two positions
+        update_reward_accumulator(staking_pool, clock.unix_timestamp)?;
+        
+        // An NFT boosts a single position, so it comes off before splitting and can be reapplied
+        remove_nft_boost(staking_pool, user_stake)?;
+        bank_secondary_reward(staking_pool, user_stake)?;
+        let pending = earned_reward(staking_pool, user_stake)? as u128;
+        let split_pending = pending
+            .try_mul(amount as u128)?
+            .try_div(user_stake.stake_amount as u128)?;
+        let split_reward_owed = (user_stake.reward_owed as u128)
+            .try_mul(amount as u128)?
+            .try_div(user_stake.stake_amount as u128)? as u64;
+        let split_secondary_owed = (user_stake.secondary_reward_owed as u128)
+            .try_mul(amount as u128)?
+            .try_div(user_stake.stake_amount as u128)? as u64;
+        
+        // Shrink the original position
+        let voting_power = user_stake.voting_power;
+        reduce_position(staking_pool, user_stake, amount)?;
+        user_stake.reward_owed = user_stake.reward_owed.try_sub(split_reward_owed)?;
+        user_stake.secondary_reward_owed = user_stake.secondary_reward_owed.try_sub(split_secondary_owed)?;
+        user_stake.reward_debt = accumulated_reward(staking_pool, user_stake)?
+            .saturating_sub(pending.try_sub(split_pending)?);
+        user_stake.secondary_reward_debt = accumulated_secondary_reward(staking_pool, user_stake)?;
+        
+        // Claim the next position index for the caller
+        let position_index = staker_info.position_count;
+        staker_info.owner = ctx.accounts.user.key();
+        staker_info.pool = staking_pool.key();
+        staker_info.position_count = position_index.try_add(1)?;
+        staker_info.bump = *ctx.bumps.get("staker_info").unwrap();
+        register_position(
+            &mut ctx.accounts.stake_registry,
+            ctx.accounts.user.key(),
+            new_user_stake.key(),
+            *ctx.bumps.get("stake_registry").unwrap(),
+        )?;
+        
+        // Setup the new position with the same lock and tier
+        new_user_stake.owner = ctx.accounts.user.key();
+        new_user_stake.creator = ctx.accounts.user.key();
+        new_user_stake.pool = staking_pool.key();
+        new_user_stake.position_mint = Pubkey::default();
+        new_user_stake.pending_owner = Pubkey::default();
+        new_user_stake.reward_destination = user_stake.reward_destination;
+        new_user_stake.frozen_until = 0;
+        new_user_stake.frozen_reward = 0;
+        new_user_stake.reward_owed = 0;
+        new_user_stake.slashed_receipts = 0;
+        new_user_stake.unbonding_queue_ticket = 0;
+        new_user_stake.position_index = position_index;
+        new_user_stake.bump = *ctx.bumps.get("new_user_stake").unwrap();
+        new_user_stake.stake_amount = amount;
+        new_user_stake.start_timestamp = user_stake.start_timestamp;
+        new_user_stake.end_timestamp = user_stake.end_timestamp;
+        new_user_stake.lock_duration = user_stake.lock_duration;
+        new_user_stake.auto_relock = user_stake.auto_relock;
+        new_user_stake.claimed_reward = 0;
+        new_user_stake.last_claim_timestamp = user_stake.last_claim_timestamp;
+        new_user_stake.reputation_boost = user_stake.reputation_boost;
+        set_voting_power(staking_pool, new_user_stake, voting_power.try_sub(user_stake.voting_power)?)?;
+        new_user_stake.withdrawn = false;
+        new_user_stake.unbonding_amount = 0;
+        new_user_stake.unbonding_ends_at = 0;
+        new_user_stake.reward_multiplier_bps = user_stake.reward_multiplier_bps;
+        new_user_stake.nft_boost_mint = Pubkey::default();
+        new_user_stake.nft_boost_bps = 0;
+        new_user_stake.reward_owed = split_reward_owed;
+        new_user_stake.secondary_reward_owed = split_secondary_owed;
+        new_user_stake.reward_debt = accumulated_reward(staking_pool, new_user_stake)?
+            .saturating_sub(split_pending);
+        new_user_stake.secondary_reward_debt = accumulated_secondary_reward(staking_pool, new_user_stake)?;
+        
+        // Principal and reward weight are unchanged; there is just one more position
+        open_staker_position(staking_pool, staker_info, clock.unix_timestamp)?;
+        
+        emit!(StakeSplitEvent {
+            user: ctx.accounts.user.key(),
+            position_index: user_stake.position_index,
+            new_position_index: position_index,
+            amount,
+            remaining_amount: user_stake.stake_amount,
+        });
+        
+        Ok(())
+    }
+
+    // Approve an NFT collection whose holders get `boost_bps` on top of their position's reward
+    // multiplier and a matching voting power boost (admin only). Registering again updates the
+    // boost for positions boosted from then on; 0 stops new boosts.
+    pub fn register_boost_collection(ctx: Context<RegisterBoostCollection>, boost_bps: u16) -> Result<()> {
+        require!(boost_bps <= MAX_NFT_BOOST_BPS, StakingError::InvalidNftBoost);
+        
+        let boost_collection = &mut ctx.accounts.boost_collection;
+        boost_collection.pool = ctx.accounts.staking_pool.key();
+        boost_collection.collection_mint = ctx.accounts.collection_mint.key();
+        boost_collection.boost_bps = boost_bps;
+        boost_collection.bump = *ctx.bumps.get("boost_collection").unwrap();
+        
+        emit!(BoostCollectionUpdateEvent {
+            collection_mint: boost_collection.collection_mint,
+            boost_bps,
+        });
+        
+        Ok(())
+    }
+
+    // Boost a position with an NFT from an approved collection. The boost is dropped on the
+    // next claim once the position holder no longer has the NFT.
+    pub fn apply_nft_boost(ctx: Context<ApplyNftBoost>) -> Result<()> {
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        let user_stake = &mut ctx.accounts.user_stake;
+        let boost_collection = &ctx.accounts.boost_collection;
+        let nft_mint = ctx.accounts.nft_token_account.mint;
+        let clock = Clock::get()?;
+        
+        // Tokenized positions are controlled by whoever holds the position NFT
+        require_position_holder(
+            user_stake,
+            ctx.accounts.user.key(),
+            ctx.accounts.position_token_account.as_ref(),
+        )?;
+        
+        // Ensure stake is still active
+        require!(!user_stake.withdrawn, StakingError::StakeAlreadyWithdrawn);
+        
+        // Frozen positions stay untouched until the freeze is lifted or runs out
+        settle_freeze(staking_pool, user_stake, clock.unix_timestamp)?;
+        require!(
+            user_stake.nft_boost_mint == Pubkey::default(),
+            StakingError::NftBoostAlreadyApplied
+        );
+        require!(boost_collection.boost_bps > 0, StakingError::InvalidNftBoost);
+        
+        // The NFT must be a verified member of the approved collection
+        let (expected_metadata, _) = Pubkey::find_program_address(
+            &[b"metadata".as_ref(), TOKEN_METADATA_PROGRAM_ID.as_ref(), nft_mint.as_ref()],
+            &TOKEN_METADATA_PROGRAM_ID,
+        );
+        let nft_metadata = &ctx.accounts.nft_metadata;
+        require!(
+            nft_metadata.key() == expected_metadata && *nft_metadata.owner == TOKEN_METADATA_PROGRAM_ID,
+            StakingError::InvalidMetadataAccount
+        );
+        require!(
+            verified_collection(&nft_metadata.try_borrow_data()?) == Some(boost_collection.collection_mint),
+            StakingError::NftNotInCollection
+        );
+        
+        // Rewards earn at the boosted rate from now on
+        update_reward_accumulator(staking_pool, clock.unix_timestamp)?;
+        let boost_bps = boost_collection.boost_bps;
+        let reward_multiplier_bps = user_stake.reward_multiplier_bps.try_add(boost_bps)?;
+        set_reward_multiplier(staking_pool, user_stake, reward_multiplier_bps)?;
+        let voting_power = boosted_voting_power(user_stake.voting_power, boost_bps)?;
+        set_voting_power(staking_pool, user_stake, voting_power)?;
+        user_stake.nft_boost_mint = nft_mint;
+        user_stake.nft_boost_bps = boost_bps;
+        
+        emit!(NftBoostEvent {
+            user: ctx.accounts.user.key(),
+            position_index: user_stake.position_index,
+            nft_mint,
+            boost_bps,
+            voting_power: user_stake.voting_power,
+        });
+        
+        Ok(())
+    }
+
+    // Move a position to the pool's migration target in one go: its principal, its lock as it
+    // stands and its unclaimed rewards, which the old pool hands to the new pool's reward
+    // reserve. The position's reward rate and voting power follow the new pool's tiers.
+    pub fn migrate_stake(ctx: Context<MigrateStake>) -> Result<()> {
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        let new_staking_pool = &mut ctx.accounts.new_staking_pool;
+        let staker_info = &mut ctx.accounts.staker_info;
+        let user_stake = &mut ctx.accounts.user_stake;
+        let new_user_stake = &mut ctx.accounts.new_user_stake;
+        let clock = Clock::get()?;
+        
+        // Principal and rewards have to mean the same thing in both pools
+        require!(
+            new_staking_pool.token_mint == staking_pool.token_mint
+                && new_staking_pool.reward_mint == staking_pool.reward_mint,
+            StakingError::InvalidMigrationTarget
+        );
+        
+        // Check the new pool's circuit breaker
+        require!(new_staking_pool.paused_flags & PAUSE_STAKING == 0, StakingError::StakingPaused);
+        
+        // Retired pools take no new stake
+        require!(new_staking_pool.retire_at == 0, StakingError::PoolRetired);
+        
+        // Permissioned pools only serve wallets their compliance list lets through
+        require_compliant(
+            new_staking_pool,
+            ctx.accounts.user.key(),
+            ctx.accounts.compliance_entry.as_ref(),
+            COMPLIANCE_STAKING,
+        )?;
+        
+        // Tokenized positions are controlled by whoever holds the position NFT and can't move
+        require_position_holder(user_stake, ctx.accounts.user.key(), None)?;
+        
+        // Ensure stake is still active and not halfway out
+        require!(!user_stake.withdrawn, StakingError::StakeAlreadyWithdrawn);
+        require!(user_stake.unbonding_amount == 0, StakingError::UnbondingInProgress);
+        
+        // Registered governance power has to come off before the stake can leave the position
+        require_unregistered(user_stake)?;
+        
+        // Frozen positions stay untouched until the freeze is lifted or runs out
+        settle_freeze(staking_pool, user_stake, clock.unix_timestamp)?;
+        
+        let amount = user_stake.stake_amount;
+        require_within_tvl_cap(new_staking_pool, amount)?;
+        
+        // Settle the position in the old pool. NFT boosts are registered per pool, so they
+        // don't carry over.
+        update_reward_accumulator(staking_pool, clock.unix_timestamp)?;
+        remove_nft_boost(staking_pool, user_stake)?;
+        bank_secondary_reward(staking_pool, user_stake)?;
+        require!(user_stake.secondary_reward_owed == 0, StakingError::SecondaryRewardOutstanding);
+        let pending = pending_reward(staking_pool, user_stake)?;
+        
+        // Unclaimed rewards move into the new pool's reserve
+        if pending > 0 {
+            charge_reward(staking_pool, pending)?;
+            send_reward(
+                staking_pool,
+                ctx.accounts.reward_mint.as_ref(),
+                &ctx.accounts.rewards_vault,
+                ctx.accounts.new_rewards_vault.to_account_info(),
+                &ctx.accounts.token_program,
+                pending,
+            )?;
+            new_staking_pool.reward_reserve = new_staking_pool.reward_reserve.try_add(pending)?;
+        }
+        
+        // Principal moves between the staking vaults and the receipts are swapped
+        let pool_seeds = &[
+            b"staking_pool".as_ref(),
+            staking_pool.token_mint.as_ref(),
+            &staking_pool.pool_id.to_le_bytes(),
+            &[staking_pool.bump],
+        ];
+        
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.staking_vault.to_account_info(),
+                    to: ctx.accounts.new_staking_vault.to_account_info(),
+                    authority: staking_pool.to_account_info(),
+                },
+                &[pool_seeds],
+            ),
+            amount,
+        )?;
+        
+        // Close out the old position
+        let (_, receipt_amount) = exit_position(staking_pool, user_stake)?;
+        close_staker_position(staking_pool, &mut ctx.accounts.old_staker_info)?;
+        
+        burn_receipt(
+            &ctx.accounts.receipt_mint,
+            &ctx.accounts.user_receipt_account,
+            &ctx.accounts.user,
+            &ctx.accounts.token_program,
+            receipt_amount,
+        )?;
+        
+        mint_receipt(
+            new_staking_pool,
+            &ctx.accounts.new_receipt_mint,
+            ctx.accounts.user_new_receipt_account.to_account_info(),
+            &ctx.accounts.token_program,
+            amount,
+        )?;
+        
+        // Claim the next position index in the new pool
+        update_reward_accumulator(new_staking_pool, clock.unix_timestamp)?;
+        let position_index = staker_info.position_count;
+        staker_info.owner = ctx.accounts.user.key();
+        staker_info.pool = new_staking_pool.key();
+        staker_info.position_count = position_index.try_add(1)?;
+        staker_info.bump = *ctx.bumps.get("staker_info").unwrap();
+        register_position(
+            &mut ctx.accounts.stake_registry,
+            ctx.accounts.user.key(),
+            new_user_stake.key(),
+            *ctx.bumps.get("stake_registry").unwrap(),
+        )?;
+        
+        // Setup the new position with the same lock
+        new_user_stake.owner = ctx.accounts.user.key();
+        new_user_stake.creator = ctx.accounts.user.key();
+        new_user_stake.pool = new_staking_pool.key();
+        new_user_stake.position_mint = Pubkey::default();
+        new_user_stake.pending_owner = Pubkey::default();
+        new_user_stake.reward_destination = user_stake.reward_destination;
+        new_user_stake.frozen_until = 0;
+        new_user_stake.frozen_reward = 0;
+        new_user_stake.reward_owed = 0;
+        new_user_stake.slashed_receipts = 0;
+        new_user_stake.unbonding_queue_ticket = 0;
+        new_user_stake.position_index = position_index;
+        new_user_stake.bump = *ctx.bumps.get("new_user_stake").unwrap();
+        new_user_stake.stake_amount = amount;
+        new_user_stake.start_timestamp = user_stake.start_timestamp;
+        new_user_stake.end_timestamp = user_stake.end_timestamp;
+        new_user_stake.lock_duration = user_stake.lock_duration;
+        new_user_stake.auto_relock = user_stake.auto_relock;
+        new_user_stake.claimed_reward = user_stake.claimed_reward;
+        new_user_stake.last_claim_timestamp = user_stake.last_claim_timestamp;
+        new_user_stake.withdrawn = false;
+        new_user_stake.unbonding_amount = 0;
+        new_user_stake.unbonding_ends_at = 0;
+        new_user_stake.secondary_reward_owed = 0;
+        new_user_stake.nft_boost_mint = Pubkey::default();
+        new_user_stake.nft_boost_bps = 0;
+        
+        // Rate and voting power come from the new pool's tiers
+        let tier = ctx.accounts.new_tier_config.tier_for(new_user_stake.lock_duration);
+        new_user_stake.reputation_boost = tier.reputation_boost;
+        let voting_power = voting_power_for(new_staking_pool.tokens_per_vote, amount, &tier)?;
+        set_voting_power(new_staking_pool, new_user_stake, voting_power)?;
+        new_user_stake.reward_multiplier_bps = tier.reward_multiplier_bps;
+        
+        // The carried-over rewards are owed to the new position apart from the accumulator,
+        // which may not have grown enough in the new pool to hold them
+        sync_reward_debt(new_staking_pool, new_user_stake)?;
+        new_user_stake.reward_owed = pending;
+        user_stake.reward_owed = 0;
+        
+        new_staking_pool.total_staked = new_staking_pool.total_staked.try_add(amount)?;
+        add_reward_weight(new_staking_pool, amount, new_user_stake.reward_multiplier_bps)?;
+        open_staker_position(new_staking_pool, staker_info, clock.unix_timestamp)?;
+        
+        emit!(StakeMigratedEvent {
+            user: ctx.accounts.user.key(),
+            old_pool: staking_pool.key(),
+            old_position_index: user_stake.position_index,
+            new_pool: new_staking_pool.key(),
+            new_position_index: position_index,
+            amount,
+            carried_reward: pending,
+        });
+        
+        Ok(())
+    }
+
+    // Route a position's rewards to token accounts owned by `reward_destination`, e.g. an
+    // operational wallet (default key pays the owner's chosen accounts again)
+    pub fn set_reward_destination(ctx: Context<SetAutoRelock>, reward_destination: Pubkey) -> Result<()> {
+        let user_stake = &mut ctx.accounts.user_stake;
+        
+        // Tokenized positions are controlled by whoever holds the position NFT
+        require_position_holder(
+            user_stake,
+            ctx.accounts.user.key(),
+            ctx.accounts.position_token_account.as_ref(),
+        )?;
+        
+        // Ensure stake is still active
+        require!(!user_stake.withdrawn, StakingError::StakeAlreadyWithdrawn);
+        
+        user_stake.reward_destination = reward_destination;
+        
+        emit!(RewardDestinationUpdateEvent {
+            user: ctx.accounts.user.key(),
+            position_index: user_stake.position_index,
+            reward_destination,
+        });
+        
+        Ok(())
+    }
+
+    // Opt a position in or out of rolling into a new lock of the same duration at expiry
+    pub fn set_auto_relock(ctx: Context<SetAutoRelock>, enabled: bool) -> Result<()> {
+        let user_stake = &mut ctx.accounts.user_stake;
+        
+        // Tokenized positions are controlled by whoever holds the position NFT
+        require_position_holder(
+            user_stake,
+            ctx.accounts.user.key(),
+            ctx.accounts.position_token_account.as_ref(),
+        )?;
+        
+        // Ensure stake is still active
+        require!(!user_stake.withdrawn, StakingError::StakeAlreadyWithdrawn);
+        
+        user_stake.auto_relock = enabled;
+        
+        emit!(AutoRelockUpdateEvent {
+            user: ctx.accounts.user.key(),
+            position_index: user_stake.position_index,
+            enabled,
+        });
+        
+        Ok(())
+    }
+
+    // Roll an expired auto-relock position into a new lock of the same duration. Anyone can
+    // crank this; boost, voting power and reward rate carry over unchanged.
+    pub fn relock(ctx: Context<Relock>) -> Result<()> {
+        let staking_pool = &ctx.accounts.staking_pool;
+        let user_stake = &mut ctx.accounts.user_stake;
+        let clock = Clock::get()?;
+        
+        require!(user_stake.auto_relock, StakingError::AutoRelockDisabled);
+        
+        // Retired pools take no new locks
+        require!(staking_pool.retire_at == 0, StakingError::PoolRetired);
+        
+        // Ensure stake is still active
+        require!(!user_stake.withdrawn, StakingError::StakeAlreadyWithdrawn);
+        require!(user_stake.stake_amount > 0, StakingError::StakeAlreadyWithdrawn);
+        
+        // Only expired locks roll over
+        require!(
+            clock.unix_timestamp >= user_stake.end_timestamp,
+            StakingError::StakeLockNotExpired
+        );
+        
+        // The pool's duration bounds may have moved since the position locked
+        let duration = user_stake.lock_duration;
+        require!(
+            duration >= staking_pool.min_stake_duration && duration <= staking_pool.max_stake_duration,
+            StakingError::InvalidStakeDuration
+        );
+        
+        // Continue from the old expiry, or from now if the crank ran more than a full lock late
+        let old_end_timestamp = user_stake.end_timestamp;
+        let mut new_end_timestamp = old_end_timestamp.try_add(duration)?;
+        if new_end_timestamp <= clock.unix_timestamp {
+            new_end_timestamp = clock.unix_timestamp.try_add(duration)?;
+        }
+        user_stake.start_timestamp = new_end_timestamp - duration;
+        user_stake.end_timestamp = new_end_timestamp;
+        
+        emit!(PositionRelockedEvent {
+            user: user_stake.owner,
+            position_index: user_stake.position_index,
+            old_end_timestamp,
+            new_end_timestamp,
+        });
+        
+        Ok(())
+    }
+
+    // Close out an expired position whose owner never came back for it. Anyone can crank
+    // this: rewards are settled to the owner's reward destination and the principal goes to
+    // the owner's associated token account, so voting power and pool totals stop counting
+    // it. The owner opts in by approving the pool as delegate over their stWCT receipts.
+    // Pools with an unbonding period or queue are left to request_unstake and withdraw. A
+    // registered position is deregistered as it is finalized, with the realm accounts passed
+    // as remaining accounts (see deregister_exiting_position).
+    pub fn finalize_expired_position<'info>(
+        ctx: Context<'_, '_, '_, 'info, FinalizeExpiredPosition<'info>>,
+    ) -> Result<()> {
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        let user_stake = &mut ctx.accounts.user_stake;
+        let clock = Clock::get()?;
+        
+        // Check the pool's circuit breaker
+        require!(staking_pool.paused_flags & PAUSE_UNSTAKING == 0, StakingError::UnstakingPaused);
+        
+        // Auto-relock positions roll over through relock instead
+        require!(!user_stake.auto_relock, StakingError::AutoRelockEnabled);
+        
+        // Ensure stake is still active
+        require!(!user_stake.withdrawn, StakingError::StakeAlreadyWithdrawn);
+        require!(user_stake.stake_amount > 0, StakingError::StakeAlreadyWithdrawn);
+        
+        // Whoever holds the position NFT may not be the recorded owner yet
+        require!(user_stake.position_mint == Pubkey::default(), StakingError::PositionTokenized);
+        
+        // Frozen positions stay untouched until the freeze is lifted or runs out
+        settle_freeze(staking_pool, user_stake, clock.unix_timestamp)?;
+        
+        // Only expired locks are finalized, or any lock once the pool has retired
+        require!(
+            lock_released(staking_pool, user_stake, clock.unix_timestamp),
+            StakingError::StakeLockNotExpired
+        );
+        
+        // Pools with an unbonding period or queue exit through request_unstake and withdraw
+        require!(
+            staking_pool.unbonding_period == 0 && staking_pool.unbonding_budget == 0,
+            StakingError::UnbondingRequired
+        );
+        
+        // Registered governance power comes off with the position
+        deregister_exiting_position(
+            staking_pool,
+            user_stake,
+            ctx.remaining_accounts,
+            ctx.accounts.caller.to_account_info(),
+        )?;
+        
+        let amount = user_stake.stake_amount;
+        let receipt_amount = receipts_to_burn(user_stake, amount)?;
+        
+        // The owner must have delegated enough receipts to the pool to cover the burn
+        let owner_receipt_account = &ctx.accounts.owner_receipt_account;
+        require!(
+            owner_receipt_account.delegate == COption::Some(staking_pool.key())
+                && owner_receipt_account.delegated_amount >= receipt_amount,
+            StakingError::ReceiptDelegationRequired
+        );
+        
+        // Settle rewards earned on the full balance so far
+        update_reward_accumulator(staking_pool, clock.unix_timestamp)?;
+        let final_reward = pending_reward(staking_pool, user_stake)?;
+        bank_secondary_reward(staking_pool, user_stake)?;
+        if final_reward > 0 {
+            user_stake.claimed_reward = user_stake.claimed_reward.try_add(final_reward)?;
+            user_stake.last_claim_timestamp = clock.unix_timestamp;
+            
+            // Pay final reward wherever the owner routed rewards
+            require_reward_destination(user_stake, &ctx.accounts.owner_reward_account)?;
+            distribute_reward(
+                staking_pool,
+                ctx.accounts.reward_mint.as_ref(),
+                &ctx.accounts.rewards_vault,
+                ctx.accounts.fee_vault.as_ref(),
+                ctx.accounts.vesting_vault.as_ref(),
+                ctx.accounts.reward_vesting.as_mut(),
+                &ctx.accounts.owner_reward_account,
+                &ctx.accounts.token_program,
+                final_reward,
+            )?;
+        }
+        
+        let pool_seeds = &[
+            b"staking_pool".as_ref(),
+            staking_pool.token_mint.as_ref(),
+            &staking_pool.pool_id.to_le_bytes(),
+            &[staking_pool.bump],
+        ];
+        
+        // Burn the delegated receipts for the tokens leaving the pool
+        token::burn(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::Burn {
+                    mint: ctx.accounts.receipt_mint.to_account_info(),
+                    from: ctx.accounts.owner_receipt_account.to_account_info(),
+                    authority: staking_pool.to_account_info(),
+                },
+                &[pool_seeds],
+            ),
+            receipt_amount,
+        )?;
+        
+        // Return staked tokens to the owner
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.staking_vault.to_account_info(),
+                    to: ctx.accounts.owner_token_account.to_account_info(),
+                    authority: staking_pool.to_account_info(),
+                },
+                &[pool_seeds],
+            ),
+            amount,
+        )?;
+        
+        reduce_position(staking_pool, user_stake, amount)?;
+        sync_reward_debt(staking_pool, user_stake)?;
+        
+        // Update staking pool
+        staking_pool.total_staked = staking_pool.total_staked.try_sub(amount)?;
+        remove_reward_weight(staking_pool, amount, user_stake.reward_multiplier_bps)?;
+        
+        // Mark stake as withdrawn once nothing is left, including tokens still unbonding
+        if user_stake.unbonding_amount == 0 {
+            close_staker_position(staking_pool, &mut ctx.accounts.staker_info)?;
+            user_stake.withdrawn = true;
+        }
+        
+        emit!(ExpiredPositionFinalizedEvent {
+            owner: user_stake.owner,
+            position: user_stake.key(),
+            position_index: user_stake.position_index,
+            amount,
+            final_reward,
+            finalized_by: ctx.accounts.caller.key(),
+            withdrawn: user_stake.withdrawn,
+            total_staked: staking_pool.total_staked,
+        });
+        
+        Ok(())
+    }
+
+    // Update reward parameters (admin only)
+    pub fn update_reward_params(
+        ctx: Context<UpdateRewardParams>,
+        new_reward_rate: u64,
+        new_min_duration: i64,
+        new_max_duration: i64,
+    ) -> Result<()> {
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        let clock = Clock::get()?;
+        
+        // Keep the rate below the cap and the duration range well-formed
+        require!(new_reward_rate <= MAX_REWARD_RATE, StakingError::InvalidRewardRate);
+        require!(
+            new_min_duration >= 0 && new_min_duration <= new_max_duration,
+            StakingError::InvalidStakeDuration
+        );
+        
+        // Without a timelock the new parameters take effect right away
+        if staking_pool.params_timelock == 0 {
+            return set_reward_params(
+                staking_pool,
+                new_reward_rate,
+                new_min_duration,
+                new_max_duration,
+                clock.unix_timestamp,
+            );
+        }
+        
+        // Otherwise queue them, replacing any change already queued
+        let effective_at = clock.unix_timestamp.try_add(staking_pool.params_timelock)?;
+        staking_pool.pending_reward_rate = new_reward_rate;
+        staking_pool.pending_min_stake_duration = new_min_duration;
+        staking_pool.pending_max_stake_duration = new_max_duration;
+        staking_pool.pending_params_at = effective_at;
+        
+        emit!(ParamsQueuedEvent {
+            old_reward_rate: staking_pool.reward_rate,
+            old_min_stake_duration: staking_pool.min_stake_duration,
+            old_max_stake_duration: staking_pool.max_stake_duration,
+            reward_rate: new_reward_rate,
+            min_stake_duration: new_min_duration,
+            max_stake_duration: new_max_duration,
+            effective_at,
+        });
+        
+        Ok(())
+    }
+
+    // Apply reward parameters queued by update_reward_params once their timelock has passed.
+    // Anyone can crank this.
+    pub fn apply_reward_params(ctx: Context<UpdatePool>) -> Result<()> {
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        let clock = Clock::get()?;
+        
+        // Only a queued change whose timelock has run out
+        require!(staking_pool.pending_params_at != 0, StakingError::NoPendingParams);
+        require!(
+            clock.unix_timestamp >= staking_pool.pending_params_at,
+            StakingError::ParamsTimelocked
+        );
+        
+        staking_pool.pending_params_at = 0;
+        let reward_rate = staking_pool.pending_reward_rate;
+        let min_stake_duration = staking_pool.pending_min_stake_duration;
+        let max_stake_duration = staking_pool.pending_max_stake_duration;
+        set_reward_params(
+            staking_pool,
+            reward_rate,
+            min_stake_duration,
+            max_stake_duration,
+            clock.unix_timestamp,
+        )
+    }
+
+    // Drop reward parameters queued by update_reward_params (pool authority or the pool's
+    // governance)
+    pub fn cancel_reward_params(ctx: Context<SetPoolPaused>) -> Result<()> {
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        
+        require!(staking_pool.pending_params_at != 0, StakingError::NoPendingParams);
+        staking_pool.pending_params_at = 0;
+        
+        emit!(ParamsCancelledEvent {
+            reward_rate: staking_pool.pending_reward_rate,
+            min_stake_duration: staking_pool.pending_min_stake_duration,
+            max_stake_duration: staking_pool.pending_max_stake_duration,
+        });
+        
+        Ok(())
+    }
+
+    // Set how long reward parameter changes wait before they can be applied (pool authority
+    // or the pool's governance; only governance may shorten it)
+    pub fn set_params_timelock(ctx: Context<SetPoolPaused>, params_timelock: i64) -> Result<()> {
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        
+        require!(
+            params_timelock >= 0 && params_timelock <= MAX_PARAMS_TIMELOCK,
+            StakingError::InvalidParamsTimelock
+        );
+        
+        // The admin key alone can't strip the protection the timelock gives
+        require!(
+            params_timelock >= staking_pool.params_timelock
+                || (staking_pool.governance != Pubkey::default()
+                    && ctx.accounts.authority.key() == staking_pool.governance),
+            StakingError::InvalidParamsTimelock
+        );
+        
+        staking_pool.params_timelock = params_timelock;
+        
+        emit!(ParamsTimelockUpdateEvent { params_timelock });
+        
+        Ok(())
+    }
+
+    // Replace the pool's duration tiers (pool authority, manager or the pool's governance).
+    // Voting multipliers are part of the voting formula and only change via set_voting_formula.
+    pub fn update_tier_config(ctx: Context<UpdateTierConfig>, tiers: Vec<DurationTier>) -> Result<()> {
+        let tier_config = &mut ctx.accounts.tier_config;
+        let previous_tiers = (**tier_config).clone();
+        
+        // Existing positions keep the boost and voting power they were granted
+        tier_config.set_tiers(&tiers)?;
+        
+        // Every lock duration must keep the voting multiplier it had
+        require!(
+            previous_tiers.same_voting_multipliers(tier_config),
+            StakingError::VotingFormulaGoverned
+        );
+        
+        emit!(TierConfigUpdateEvent { tiers });
+        
+        Ok(())
+    }
+
+    // Set the voting power formula: staked base units per vote and each tier's voting
+    // multiplier, in tier order. Only the pool's governance can, through an approved proposal.
+    // Existing positions keep their old voting power, and total_voting_power mixes both formulas,
+    // until each open position is cranked through refresh_voting_power.
+    pub fn set_voting_formula(
+        ctx: Context<SetVotingFormula>,
+        tokens_per_vote: u64,
+        voting_multipliers_bps: Vec<u16>,
+    ) -> Result<()> {
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        let tier_config = &mut ctx.accounts.tier_config;
+        
+        require!(
+            tokens_per_vote > 0
+                && voting_multipliers_bps.len() == tier_config.tier_count as usize
+                && voting_multipliers_bps.iter().all(|multiplier_bps| *multiplier_bps > 0),
+            StakingError::InvalidVotingFormula
+        );
+        
+        staking_pool.tokens_per_vote = tokens_per_vote;
+        for (tier, multiplier_bps) in tier_config.tiers.iter_mut().zip(voting_multipliers_bps.iter()) {
+            tier.voting_multiplier_bps = *multiplier_bps;
+        }
+        
+        emit!(VotingFormulaUpdateEvent {
+            tokens_per_vote,
+            voting_multipliers_bps,
+        });
+        
+        Ok(())
+    }
+
+    // Recalculate a position's voting power under the pool's current formula, from its balance,
+    // its lock duration's tier and any NFT boost. Anyone can crank this to migrate positions
+    // after set_voting_formula; it changes nothing for positions already on the current formula.
+    pub fn refresh_voting_power(ctx: Context<RefreshVotingPower>) -> Result<()> {
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        let user_stake = &mut ctx.accounts.user_stake;
+        
+        require!(!user_stake.withdrawn, StakingError::StakeAlreadyWithdrawn);
+        
+        let tier = ctx.accounts.tier_config.tier_for(user_stake.lock_duration);
+        let tokens_per_vote = staking_pool.tokens_per_vote;
+        let voting_power = boosted_voting_power(
+            voting_power_for(tokens_per_vote, user_stake.stake_amount, &tier)?,
+            user_stake.nft_boost_bps,
+        )?;
+        let old_voting_power = user_stake.voting_power;
+        set_voting_power(staking_pool, user_stake, voting_power)?;
+        
+        emit!(VotingPowerRefreshedEvent {
+            owner: user_stake.owner,
+            position_index: user_stake.position_index,
+            old_voting_power,
+            voting_power,
+            total_voting_power: staking_pool.total_voting_power,
+        });
+        
+        Ok(())
+    }
+
+    // Deposit tokens into the rewards vault and credit them to the reward reserve. Anyone can
+    // fund rewards.
+    pub fn fund_rewards(ctx: Context<FundRewards>, amount: u64) -> Result<()> {
+        require!(amount > 0, StakingError::InvalidFundingAmount);
+        
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.funder_token_account.to_account_info(),
+                    to: ctx.accounts.rewards_vault.to_account_info(),
+                    authority: ctx.accounts.funder.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+        
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        staking_pool.reward_reserve = staking_pool.reward_reserve.try_add(amount)?;
+        
+        emit!(RewardsFundedEvent {
+            funder: ctx.accounts.funder.key(),
+            amount,
+            reward_reserve: staking_pool.reward_reserve,
+        });
+        
+        Ok(())
+    }
+
+    // Settle the pool's reward accumulator up to now. Anyone can crank this to keep accrual
+    // current while nobody else transacts.
+    pub fn update_pool(ctx: Context<UpdatePool>) -> Result<()> {
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        let clock = Clock::get()?;
+        
+        update_reward_accumulator(staking_pool, clock.unix_timestamp)?;
+        
+        // Keep the pool's yield oracle current when it's passed
+        if let Some(yield_oracle) = ctx.accounts.yield_oracle.as_mut() {
+            refresh_yield_oracle(yield_oracle, staking_pool, clock.unix_timestamp)?;
+            
+            emit!(YieldOracleUpdatedEvent {
+                rate_7d_bps: yield_oracle.rate_7d_bps,
+                rate_30d_bps: yield_oracle.rate_30d_bps,
+                timestamp: clock.unix_timestamp,
+            });
+        }
+        
+        emit!(PoolUpdatedEvent {
+            reward_per_token_stored: staking_pool.reward_per_token_stored,
+            secondary_reward_per_token_stored: staking_pool.secondary_reward_per_token_stored,
+            timestamp: clock.unix_timestamp,
+        });
+        
+        Ok(())
+    }
+
+    // Report the pool's total voting power to the linked governance realm's registry for the
+    // staked token, which uses it for quorum. Anyone can crank this.
+    pub fn sync_governance_voting_power(ctx: Context<SyncGovernanceVotingPower>) -> Result<()> {
+        let staking_pool = &ctx.accounts.staking_pool;
+        
+        // report_staked_voting_power(total_voting_power), signed by the pool
+        let mut data = hash(b"global:report_staked_voting_power").to_bytes()[..8].to_vec();
+        data.extend_from_slice(&staking_pool.total_voting_power.to_le_bytes());
+        
+        let ix = Instruction {
+            program_id: ctx.accounts.governance_program.key(),
+            accounts: vec![
+                AccountMeta::new(ctx.accounts.governance.key(), false),
+                AccountMeta::new(ctx.accounts.voting_power_registry.key(), false),
+                AccountMeta::new_readonly(staking_pool.key(), true),
+            ],
+            data,
+        };
+        
+        let pool_seeds = &[
+            b"staking_pool".as_ref(),
+            staking_pool.token_mint.as_ref(),
+            &staking_pool.pool_id.to_le_bytes(),
+            &[staking_pool.bump],
+        ];
+        
+        invoke_signed(
+            &ix,
+            &[
+                ctx.accounts.governance.to_account_info(),
+                ctx.accounts.voting_power_registry.to_account_info(),
+                staking_pool.to_account_info(),
+            ],
+            &[pool_seeds],
+        )?;
+        
+        emit!(VotingPowerSyncedEvent {
+            governance: ctx.accounts.governance.key(),
+            total_voting_power: staking_pool.total_voting_power,
+        });
+        
+        Ok(())
+    }
+
+    // Register a position's current voting power and reputation boost with the linked realm,
+    // replacing what it contributed to its voter before. Anyone can crank this, which also moves
+    // the power off a previous owner after a transfer and off withdrawn positions; only the owner
+    // can deregister an open position (`register` = false), which unstaking requires.
+    pub fn sync_position_voting_power(ctx: Context<SyncPositionVotingPower>, register: bool) -> Result<()> {
+        let staking_pool = &ctx.accounts.staking_pool;
+        let user_stake = &mut ctx.accounts.user_stake;
+        
+        require!(
+            register || ctx.accounts.caller.key() == user_stake.owner,
+            StakingError::NotPositionOwner
+        );
+        
+        // Power registered to a previous owner comes off first; a second call registers it to
+        // the new owner
+        let moving_voter =
+            user_stake.registered_voting_power > 0 && user_stake.registered_voter != user_stake.owner;
+        let (voter, voting_power, reputation_boost) = if moving_voter {
+            (user_stake.registered_voter, 0, 0)
+        } else if !register || user_stake.withdrawn {
+            (user_stake.owner, 0, 0)
+        } else {
+            // Tokenized positions change hands outside the program, so they can't carry power
+            require!(user_stake.position_mint == Pubkey::default(), StakingError::PositionTokenized);
+            (user_stake.owner, user_stake.voting_power, user_stake.reputation_boost)
+        };
+        
+        let realm = RealmRegistration {
+            governance_program: ctx.accounts.governance_program.to_account_info(),
+            governance: ctx.accounts.governance.to_account_info(),
+            voting_power_registry: ctx.accounts.voting_power_registry.to_account_info(),
+            voter_power: ctx.accounts.voter_power.to_account_info(),
+            voting_power_page: ctx.accounts.voting_power_page.to_account_info(),
+            payer: ctx.accounts.caller.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            rent: ctx.accounts.rent.to_account_info(),
+        };
+        register_position_power(staking_pool, user_stake, &realm, voter, voting_power, reputation_boost)?;
+        
+        Ok(())
+    }
+
+    // Point the pool at the program that mints its achievement badges (pool authority or the
+    // pool's governance, default turns badges off)
+    pub fn set_badge_program(ctx: Context<SetPoolPaused>, badge_program: Pubkey) -> Result<()> {
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        staking_pool.badge_program = badge_program;
+        
+        emit!(BadgeProgramUpdateEvent {
+            badge_program,
+            updated_by: ctx.accounts.authority.key(),
+        });
+        
+        Ok(())
+    }
+
+    // Mint a soulbound achievement badge once the wallet has reached its milestone. The badge
+    // program's mint_badge(badge) is called with the pool as signing issuer and the wallet as
+    // recipient and payer; any accounts it needs beyond those follow in remaining_accounts.
+    // Each badge is minted at most once per wallet and pool.
+    pub fn claim_staker_badge<'info>(
+        ctx: Context<'_, '_, '_, 'info, ClaimStakerBadge<'info>>,
+        badge: u8,
+    ) -> Result<()> {
+        let staking_pool = &ctx.accounts.staking_pool;
+        let staker_info = &ctx.accounts.staker_info;
+        let staker_badges = &mut ctx.accounts.staker_badges;
+        let owner = ctx.accounts.owner.key();
+        let clock = Clock::get()?;
+        
+        require!(badge <= BADGE_100K_LOCKED, StakingError::InvalidBadge);
+        require!(staker_badges.minted & (1 << badge) == 0, StakingError::BadgeAlreadyMinted);
+        
+        let reached = match badge {
+            BADGE_FIRST_STAKE => staker_info.position_count > 0,
+            BADGE_ONE_YEAR_STAKED => {
+                staker_info.streak_start != 0
+                    && clock.unix_timestamp.try_sub(staker_info.streak_start)? >= BADGE_STREAK_DURATION
+            }
+            _ => ctx
+                .accounts
+                .user_stake
+                .as_ref()
+                .map_or(false, |user_stake| {
+                    !user_stake.withdrawn && user_stake.stake_amount >= BADGE_LOCKED_AMOUNT
+                }),
+        };
+        require!(reached, StakingError::BadgeMilestoneNotReached);
+        
+        staker_badges.pool = staking_pool.key();
+        staker_badges.owner = owner;
+        staker_badges.bump = *ctx.bumps.get("staker_badges").unwrap();
+        staker_badges.minted |= 1 << badge;
+        
+        // mint_badge(badge), signed by the pool
+        let mut data = hash(b"global:mint_badge").to_bytes()[..8].to_vec();
+        data.push(badge);
+        
+        let mut accounts = vec![
+            AccountMeta::new_readonly(staking_pool.key(), true),
+            AccountMeta::new(owner, true),
+        ];
+        let mut account_infos = vec![
+            staking_pool.to_account_info(),
+            ctx.accounts.owner.to_account_info(),
+        ];
+        for account_info in ctx.remaining_accounts {
+            accounts.push(AccountMeta {
+                pubkey: account_info.key(),
+                is_signer: account_info.is_signer,
+                is_writable: account_info.is_writable,
+            });
+            account_infos.push(account_info.clone());
+        }
+        
+        let ix = Instruction {
+            program_id: ctx.accounts.badge_program.key(),
+            accounts,
+            data,
+        };
+        
+        let pool_seeds = &[
+            b"staking_pool".as_ref(),
+            staking_pool.token_mint.as_ref(),
+            &staking_pool.pool_id.to_le_bytes(),
+            &[staking_pool.bump],
+        ];
+        
+        invoke_signed(&ix, &account_infos, &[pool_seeds])?;
+        
+        emit!(StakerBadgeMintedEvent {
+            owner,
+            pool: staking_pool.key(),
+            badge,
+        });
+        
+        Ok(())
+    }
+
+    // Create the pool's yield oracle, seeded with the accumulator as of now. Anyone can create
+    // it; update_pool keeps it current from then on.
+    pub fn initialize_yield_oracle(ctx: Context<InitializeYieldOracle>) -> Result<()> {
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        let clock = Clock::get()?;
+        
+        update_reward_accumulator(staking_pool, clock.unix_timestamp)?;
+        
+        let yield_oracle = &mut ctx.accounts.yield_oracle;
+        yield_oracle.pool = staking_pool.key();
+        yield_oracle.samples = [YieldSample::default(); MAX_YIELD_SAMPLES];
+        yield_oracle.sample_count = 0;
+        yield_oracle.bump = *ctx.bumps.get("yield_oracle").unwrap();
+        refresh_yield_oracle(yield_oracle, staking_pool, clock.unix_timestamp)?;
+        
+        Ok(())
+    }
+
+    // Record the pool's state for the current snapshot epoch, for APY history. Anyone can crank
+    // this, once per epoch.
+    pub fn snapshot_epoch(ctx: Context<SnapshotEpoch>, epoch: u64) -> Result<()> {
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        let clock = Clock::get()?;
+        
+        // Only the epoch we're in can be recorded
+        require!(
+            epoch == (clock.unix_timestamp / SNAPSHOT_EPOCH_DURATION) as u64,
+            StakingError::InvalidSnapshotEpoch
+        );
+        
+        // Capture the accumulator as of now
+        update_reward_accumulator(staking_pool, clock.unix_timestamp)?;
+        
+        let epoch_snapshot = &mut ctx.accounts.epoch_snapshot;
+        epoch_snapshot.pool = staking_pool.key();
+        epoch_snapshot.epoch = epoch;
+        epoch_snapshot.timestamp = clock.unix_timestamp;
+        epoch_snapshot.total_staked = staking_pool.total_staked;
+        epoch_snapshot.reward_reserve = staking_pool.reward_reserve;
+        epoch_snapshot.reward_per_token_stored = staking_pool.reward_per_token_stored;
+        epoch_snapshot.effective_rate_bps = effective_rate_bps(staking_pool, clock.unix_timestamp)?;
+        epoch_snapshot.bump = *ctx.bumps.get("epoch_snapshot").unwrap();
+        
+        Ok(())
+    }
+
+    // Set up or retune the secondary reward, streamed at `reward_rate` tokens per second and
+    // shared by stake weight (admin only). The secondary mint can't change once set.
+    pub fn configure_secondary_rewards(ctx: Context<ConfigureSecondaryRewards>, reward_rate: u64) -> Result<()> {
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        let secondary_mint = ctx.accounts.secondary_mint.key();
+        let clock = Clock::get()?;
+        
+        require!(
+            secondary_mint != staking_pool.token_mint && secondary_mint != staking_pool.reward_mint,
+            StakingError::InvalidSecondaryMint
+        );
+        require!(
+            staking_pool.secondary_reward_mint == Pubkey::default()
+                || staking_pool.secondary_reward_mint == secondary_mint,
+            StakingError::InvalidSecondaryMint
+        );
+        
+        // Settle everything streamed at the previous rate
+        update_reward_accumulator(staking_pool, clock.unix_timestamp)?;
+        
+        staking_pool.secondary_reward_mint = secondary_mint;
+        staking_pool.secondary_rewards_vault = ctx.accounts.secondary_rewards_vault.key();
+        staking_pool.secondary_reward_rate = reward_rate;
+        
+        emit!(SecondaryRewardsUpdateEvent {
+            secondary_mint,
+            reward_rate,
+        });
+        
+        Ok(())
+    }
+
+    // Deposit secondary reward tokens and credit them to the secondary reserve. Anyone can fund.
+    pub fn fund_secondary_rewards(ctx: Context<FundSecondaryRewards>, amount: u64) -> Result<()> {
+        require!(amount > 0, StakingError::InvalidFundingAmount);
+        
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.funder_token_account.to_account_info(),
+                    to: ctx.accounts.secondary_rewards_vault.to_account_info(),
+                    authority: ctx.accounts.funder.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+        
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        staking_pool.secondary_reward_reserve = staking_pool.secondary_reward_reserve.try_add(amount)?;
+        
+        emit!(SecondaryRewardsFundedEvent {
+            funder: ctx.accounts.funder.key(),
+            amount,
+            secondary_reward_reserve: staking_pool.secondary_reward_reserve,
+        });
+        
+        Ok(())
+    }
+
+    // Claim only the secondary reward. Works after the position is withdrawn so nothing banked
+    // is stranded.
+    pub fn claim_secondary_reward(ctx: Context<ClaimSecondaryReward>) -> Result<()> {
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        let user_stake = &mut ctx.accounts.user_stake;
+        let clock = Clock::get()?;
+        
+        // Check the pool's circuit breaker
+        require!(staking_pool.paused_flags & PAUSE_CLAIMS == 0, StakingError::ClaimsPaused);
+        
+        // Permissioned pools only serve wallets their compliance list lets through
+        require_compliant(
+            staking_pool,
+            ctx.accounts.user.key(),
+            ctx.accounts.compliance_entry.as_ref(),
+            COMPLIANCE_CLAIMS,
+        )?;
+        
+        // Tokenized positions are controlled by whoever holds the position NFT
+        require_position_holder(
+            user_stake,
+            ctx.accounts.user.key(),
+            ctx.accounts.position_token_account.as_ref(),
+        )?;
+        
+        // Frozen positions stay untouched until the freeze is lifted or runs out
+        settle_freeze(staking_pool, user_stake, clock.unix_timestamp)?;
+        
+        // Rewards go wherever the owner routed them
+        require_reward_destination(user_stake, &ctx.accounts.user_secondary_token_account)?;
+        
+        update_reward_accumulator(staking_pool, clock.unix_timestamp)?;
+        bank_secondary_reward(staking_pool, user_stake)?;
+        
+        let amount = pay_secondary_reward(
+            staking_pool,
+            user_stake,
+            &ctx.accounts.secondary_rewards_vault,
+            ctx.accounts.user_secondary_token_account.to_account_info(),
+            &ctx.accounts.token_program,
+        )?;
+        require!(amount > 0, StakingError::NoRewardsYet);
+        
+        emit!(SecondaryRewardEvent {
+            user: ctx.accounts.user.key(),
+            position_index: user_stake.position_index,
+            amount,
+        });
+        
+        Ok(())
+    }
+
+    // Mint rewards fresh instead of paying them from the rewards vault, up to a hard cap on
+    // everything ever minted (pool authority or the pool's governance). The governance that
+    // holds the mint authority must first hand it to the staking pool PDA.
+    pub fn set_mint_rewards(ctx: Context<SetMintRewards>, enabled: bool, emission_cap: u64) -> Result<()> {
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        
+        if enabled {
+            require!(
+                ctx.accounts.reward_mint.mint_authority == COption::Some(staking_pool.key()),
+                StakingError::MintAuthorityNotDelegated
+            );
+            require!(
+                emission_cap >= staking_pool.total_minted,
+                StakingError::EmissionCapExceeded
+            );
+        }
+        
+        staking_pool.mint_rewards = enabled;
+        staking_pool.emission_cap = emission_cap;
+        
+        emit!(MintRewardsUpdateEvent {
+            enabled,
+            emission_cap,
+            total_minted: staking_pool.total_minted,
+        });
+        
+        Ok(())
+    }
+
+    // Switch the pool to a fixed emission budget per epoch, shared by stake weight and
+    // optionally halved every `halving_interval` epochs (admin only). A zero budget returns the
+    // pool to the flat reward rate.
+    pub fn configure_emissions(
+        ctx: Context<UpdateRewardParams>,
+        emission_per_epoch: u64,
+        epoch_duration: i64,
+        halving_interval: u64,
+    ) -> Result<()> {
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        let clock = Clock::get()?;
+        
+        require!(
+            emission_per_epoch == 0 || epoch_duration > 0,
+            StakingError::InvalidEmissionSchedule
+        );
+        
+        // Settle everything earned under the previous schedule
+        update_reward_accumulator(staking_pool, clock.unix_timestamp)?;
+        
+        // The new schedule's first epoch starts now
+        staking_pool.emission_per_epoch = emission_per_epoch;
+        staking_pool.emission_epoch_duration = epoch_duration;
+        staking_pool.emission_start = clock.unix_timestamp;
+        staking_pool.halving_interval = halving_interval;
+        
+        emit!(EmissionScheduleUpdateEvent {
+            emission_per_epoch,
+            epoch_duration,
+            halving_interval,
+            emission_start: clock.unix_timestamp,
+        });
+        
+        Ok(())
+    }
+
+    // Hand day-to-day operations (tiers, caps, pauses) to a manager key, or take them back with
+    // the default key (pool authority or the pool's governance)
+    pub fn set_manager(ctx: Context<SetPoolPaused>, manager: Pubkey) -> Result<()> {
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        let old_manager = staking_pool.manager;
+        staking_pool.manager = manager;
+        
+        emit!(ManagerUpdateEvent {
+            old_manager,
+            manager,
+            updated_by: ctx.accounts.authority.key(),
+        });
+        
+        Ok(())
+    }
+
+    // Pause or resume staking, claims and unstaking independently (pool authority, manager or
+    // the pool's governance). `paused_flags` is a combination of the PAUSE_* bits.
+    pub fn set_pool_paused(ctx: Context<ManagePool>, paused_flags: u8) -> Result<()> {
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        
+        require!(paused_flags & !PAUSE_ALL == 0, StakingError::InvalidPauseFlags);
+        staking_pool.paused_flags = paused_flags;
+        
+        emit!(PoolPauseEvent {
+            paused_flags,
+            updated_by: ctx.accounts.authority.key(),
+        });
+        
+        Ok(())
+    }
+
+    // Let positions move to `migration_target`, a newer pool staking the same mint (pool
+    // authority or the pool's governance, default stops migrations)
+    pub fn set_migration_target(ctx: Context<SetPoolPaused>, migration_target: Pubkey) -> Result<()> {
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        require!(migration_target != staking_pool.key(), StakingError::InvalidMigrationTarget);
+        staking_pool.migration_target = migration_target;
+        
+        emit!(MigrationTargetUpdateEvent {
+            migration_target,
+            updated_by: ctx.accounts.authority.key(),
+        });
+        
+        Ok(())
+    }
+
+    // Restrict the pool to an allowlist, or block a denylist, for the instructions in `scope`
+    // (pool authority or the pool's governance). `compliance_authority` keeps the list.
+    pub fn set_compliance_config(
+        ctx: Context<SetPoolPaused>,
+        compliance_authority: Pubkey,
+        mode: u8,
+        scope: u8,
+    ) -> Result<()> {
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        
+        require!(mode <= COMPLIANCE_DENYLIST, StakingError::InvalidComplianceConfig);
+        require!(
+            scope & !(COMPLIANCE_STAKING | COMPLIANCE_CLAIMS) == 0,
+            StakingError::InvalidComplianceConfig
+        );
+        
+        // A list that is switched on needs someone to keep it and something to apply to
+        require!(
+            mode == COMPLIANCE_NONE || (compliance_authority != Pubkey::default() && scope != 0),
+            StakingError::InvalidComplianceConfig
+        );
+        
+        staking_pool.compliance_authority = compliance_authority;
+        staking_pool.compliance_mode = mode;
+        staking_pool.compliance_scope = scope;
+        
+        emit!(ComplianceConfigEvent {
+            compliance_authority,
+            mode,
+            scope,
+            updated_by: ctx.accounts.authority.key(),
+        });
+        
+        Ok(())
+    }
+
+    // Put a wallet on or take it off the pool's compliance list (compliance authority only)
+    pub fn set_compliance_status(
+        ctx: Context<SetComplianceStatus>,
+        wallet: Pubkey,
+        listed: bool,
+    ) -> Result<()> {
+        let compliance_entry = &mut ctx.accounts.compliance_entry;
+        compliance_entry.pool = ctx.accounts.staking_pool.key();
+        compliance_entry.wallet = wallet;
+        compliance_entry.listed = listed;
+        compliance_entry.bump = *ctx.bumps.get("compliance_entry").unwrap();
+        
+        emit!(ComplianceStatusEvent {
+            wallet,
+            listed,
+            updated_by: ctx.accounts.compliance_authority.key(),
+        });
+        
+        Ok(())
+    }
+
+    // Flag or clear an incident on the pool. Only the pool's governance can, through an approved
+    // proposal. While it is flagged, any staker may leave early with incident_exit.
+    pub fn set_incident_exit(ctx: Context<SetIncidentExit>, enabled: bool) -> Result<()> {
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        staking_pool.incident_exit_enabled = enabled;
+        
+        emit!(IncidentExitModeEvent { enabled });
+        
+        Ok(())
+    }
+
+    // Turn emergency mode on or off (pool authority or the pool's governance). While it is on,
+    // every staker can pull their principal out with emergency_withdraw.
+    pub fn set_emergency_mode(ctx: Context<SetPoolPaused>, enabled: bool) -> Result<()> {
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        staking_pool.emergency_mode = enabled;
+        
+        emit!(EmergencyModeEvent {
+            enabled,
+            updated_by: ctx.accounts.authority.key(),
+        });
+        
+        Ok(())
+    }
+
+    // Wind the pool down (pool authority or the pool's governance). New stake stops straight
+    // away, rewards stop accruing at `cutoff`, and from then on every lock may exit early
+    // without penalty. Retirement can't be undone.
+    pub fn retire_pool(ctx: Context<SetPoolPaused>, cutoff: i64) -> Result<()> {
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        let clock = Clock::get()?;
+        
+        require!(staking_pool.retire_at == 0, StakingError::PoolRetired);
+        require!(cutoff >= clock.unix_timestamp, StakingError::InvalidRetireCutoff);
+        
+        // Settle accrual up to now under the current parameters
+        update_reward_accumulator(staking_pool, clock.unix_timestamp)?;
+        staking_pool.retire_at = cutoff;
+        
+        emit!(PoolRetiredEvent {
+            cutoff,
+            retired_by: ctx.accounts.authority.key(),
+        });
+        
+        Ok(())
+    }
+
+    // Send a retired pool's leftover reward reserve to its treasury once every position has
+    // exited (permissionless)
+    pub fn sweep_retired_rewards(ctx: Context<SweepRetiredRewards>) -> Result<()> {
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        let clock = Clock::get()?;
+        
+        require!(
+            staking_pool.retire_at != 0 && clock.unix_timestamp >= staking_pool.retire_at,
+            StakingError::PoolNotRetired
+        );
+        
+        // Every position, including tokens still unbonding, has to be out first
+        require!(
+            staking_pool.total_staked == 0 && staking_pool.staker_count == 0,
+            StakingError::PoolNotEmpty
+        );
+        
+        let amount = staking_pool.reward_reserve;
+        staking_pool.reward_reserve = 0;
+        
+        if amount > 0 {
+            let pool_seeds = &[
+                b"staking_pool".as_ref(),
+                staking_pool.token_mint.as_ref(),
+                &staking_pool.pool_id.to_le_bytes(),
+                &[staking_pool.bump],
+            ];
+            
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    token::Transfer {
+                        from: ctx.accounts.rewards_vault.to_account_info(),
+                        to: ctx.accounts.treasury_token_account.to_account_info(),
+                        authority: staking_pool.to_account_info(),
+                    },
+                    &[pool_seeds],
+                ),
+                amount,
+            )?;
+        }
+        
+        emit!(RetiredRewardsSweptEvent { amount });
+        
+        Ok(())
+    }
+
+    // Cap the pool's total staked amount (pool authority, manager or the pool's governance, 0
+    // removes the cap). Lowering it below the current total only blocks new stake.
+    pub fn set_max_total_staked(ctx: Context<ManagePool>, max_total_staked: u64) -> Result<()> {
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        staking_pool.max_total_staked = max_total_staked;
+        
+        emit!(TvlCapUpdateEvent {
+            max_total_staked,
+            updated_by: ctx.accounts.authority.key(),
+        });
+        
+        Ok(())
+    }
+
+    // Set the smallest amount a new position may stake (pool authority, manager or the pool's
+    // governance)
+    pub fn set_min_stake_amount(ctx: Context<ManagePool>, min_stake_amount: u64) -> Result<()> {
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        staking_pool.min_stake_amount = min_stake_amount;
+        
+        emit!(MinStakeAmountUpdateEvent { min_stake_amount });
+        
+        Ok(())
+    }
+
+    // Set the share of every reward payout that goes to the DAO's fee vault. Only the pool's
+    // governance can change it, through an approved proposal.
+    pub fn set_performance_fee(ctx: Context<SetPerformanceFee>, fee_bps: u16) -> Result<()> {
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        
+        require!(fee_bps <= MAX_PERFORMANCE_FEE_BPS, StakingError::InvalidPerformanceFee);
+        
+        staking_pool.performance_fee_bps = fee_bps;
+        staking_pool.fee_vault = ctx.accounts.fee_vault.key();
+        
+        emit!(PerformanceFeeUpdateEvent {
+            fee_bps,
+            fee_vault: staking_pool.fee_vault,
+        });
+        
+        Ok(())
+    }
+
+    // Stream claimed rewards to stakers linearly over `vesting_period` seconds instead of paying
+    // them out at once (admin only, 0 pays rewards immediately again)
+    pub fn set_reward_vesting_period(ctx: Context<SetRewardVestingPeriod>, vesting_period: i64) -> Result<()> {
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        
+        require!(vesting_period >= 0, StakingError::InvalidVestingPeriod);
+        staking_pool.reward_vesting_period = vesting_period;
+        staking_pool.vesting_vault = ctx.accounts.vesting_vault.key();
+        
+        emit!(RewardVestingUpdateEvent { vesting_period });
+        
+        Ok(())
+    }
+
+    // Withdraw whatever has vested on the caller's reward vesting schedule
+    pub fn claim_vested(ctx: Context<ClaimVested>) -> Result<()> {
+        let reward_vesting = &mut ctx.accounts.reward_vesting;
+        let clock = Clock::get()?;
+        
+        let amount = reward_vesting.releasable(clock.unix_timestamp)?;
+        require!(amount > 0, StakingError::NoRewardsYet);
+        reward_vesting.released = reward_vesting.released.try_add(amount)?;
+        
+        release_vested(
+            &ctx.accounts.staking_pool,
+            &ctx.accounts.vesting_vault,
+            ctx.accounts.user_reward_account.to_account_info(),
+            &ctx.accounts.token_program,
+            amount,
+        )?;
+        
+        emit!(VestedRewardEvent {
+            user: ctx.accounts.user.key(),
+            amount,
+            remaining_amount: reward_vesting.total.try_sub(reward_vesting.released)?,
+        });
+        
+        Ok(())
+    }
+
+    // Set the shortest time between two claims on a position (admin only, 0 disables)
+    pub fn set_min_claim_interval(ctx: Context<UpdateRewardParams>, min_claim_interval: i64) -> Result<()> {
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        
+        require!(min_claim_interval >= 0, StakingError::InvalidClaimInterval);
+        staking_pool.min_claim_interval = min_claim_interval;
+        
+        emit!(ClaimIntervalUpdateEvent { min_claim_interval });
+        
+        Ok(())
+    }
+
+    // Cap how many unbonding tokens the queue releases per unbonding epoch (pool authority,
+    // manager or the pool's governance, 0 turns the queue off and releases what's in it)
+    pub fn set_unbonding_budget(ctx: Context<ManagePool>, unbonding_budget: u64) -> Result<()> {
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        staking_pool.unbonding_budget = unbonding_budget;
+        
+        emit!(UnbondingBudgetUpdateEvent {
+            unbonding_budget,
+            updated_by: ctx.accounts.authority.key(),
+        });
+        
+        Ok(())
+    }
+
+    // Release the front of the unbonding queue up to the current epoch's budget. withdraw does
+    // this too; anyone can crank it to keep the queue moving.
+    pub fn process_unbonding_queue(ctx: Context<UpdatePool>) -> Result<()> {
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        let clock = Clock::get()?;
+        
+        advance_unbonding_queue(staking_pool, clock.unix_timestamp)?;
+        
+        emit!(UnbondingQueueEvent {
+            queue_head: staking_pool.unbonding_queue_head,
+            queue_tail: staking_pool.unbonding_queue_tail,
+            budget_used: staking_pool.unbonding_budget_used,
+        });
+        
+        Ok(())
+    }
+
+    // Set the cooldown between request_unstake and withdraw (admin only, 0 allows direct unstake)
+    pub fn set_unbonding_period(ctx: Context<UpdateRewardParams>, unbonding_period: i64) -> Result<()> {
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        
+        require!(unbonding_period >= 0, StakingError::InvalidUnbondingPeriod);
+        staking_pool.unbonding_period = unbonding_period;
+        
+        emit!(UnbondingPeriodUpdateEvent { unbonding_period });
+        
+        Ok(())
+    }
+
+    // Link the pool to a governance realm. The pool authority can do this once while the pool
+    // is unlinked; after that only the linked governance PDA can move the pool to another realm,
+    // so the authority can never name itself governance.
+    pub fn set_pool_governance(
+        ctx: Context<SetPoolGovernance>,
+        governance_program: Pubkey,
+        governance: Pubkey,
+    ) -> Result<()> {
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        
+        require!(
+            governance_program != Pubkey::default() && governance != Pubkey::default(),
+            StakingError::InvalidGovernanceAccounts
+        );
+        
+        staking_pool.governance_program = governance_program;
+        staking_pool.governance = governance;
+        
+        emit!(PoolGovernanceUpdateEvent {
+            governance_program,
+            governance,
+            updated_by: ctx.accounts.authority.key(),
+        });
+        
+        Ok(())
+    }
+
+    // Configure the reward bonus for stakers who voted in at least `min_votes` of the last
+    // `window` proposals of the linked realm (admin only, a zero bonus disables it)
+    pub fn set_participation_bonus(
+        ctx: Context<UpdateRewardParams>,
+        min_votes: u8,
+        window: u8,
+        bonus_bps: u16,
+    ) -> Result<()> {
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        
+        require!(
+            min_votes > 0 && min_votes <= window && window <= MAX_PARTICIPATION_WINDOW,
+            StakingError::InvalidParticipationBonus
+        );
+        require!(bonus_bps <= MAX_PARTICIPATION_BONUS_BPS, StakingError::InvalidParticipationBonus);
+        
+        // Participation is read from the linked realm
+        require!(
+            bonus_bps == 0 || staking_pool.governance != Pubkey::default(),
+            StakingError::InvalidGovernanceAccounts
+        );
+        
+        staking_pool.participation_min_votes = min_votes;
+        staking_pool.participation_window = window;
+        staking_pool.participation_bonus_bps = bonus_bps;
+        
+        emit!(ParticipationBonusUpdateEvent {
+            min_votes,
+            window,
+            bonus_bps,
+        });
+        
+        Ok(())
+    }
+}
+
+// Largest NFT boost a collection can give: +1x reward multiplier, +100% voting power
+pub const MAX_NFT_BOOST_BPS: u16 = 10000;
+
+// Highest performance fee governance can set: 50% of each reward
+pub const MAX_PERFORMANCE_FEE_BPS: u16 = 5000;
+
+// Highest reward rate update_reward_params accepts: 1% per day
+pub const MAX_REWARD_RATE: u64 = 100;
+
+// Longest timelock reward parameter changes can be held behind: 30 days
+pub const MAX_PARAMS_TIMELOCK: i64 = 30 * 24 * 60 * 60;
+
+// Length of the epochs the unbonding budget applies to: one day
+pub const UNBONDING_EPOCH_DURATION: i64 = 24 * 60 * 60;
+
+// Longest governance can freeze a position for at once: 90 days
+pub const MAX_FREEZE_DURATION: i64 = 90 * 24 * 60 * 60;
+
+// Longest a session key can stay valid: 30 days
+pub const MAX_SESSION_DURATION: i64 = 30 * 24 * 60 * 60;
+
+// Loyalty streaks grow one step per week of continuous staking
+pub const LOYALTY_EPOCH_DURATION: i64 = 7 * 24 * 60 * 60;
+
+// Loyalty bonus per week of the streak, and its cap: +0.25% a week, up to +10%
+pub const LOYALTY_BONUS_BPS_PER_EPOCH: u16 = 25;
+pub const MAX_LOYALTY_BONUS_BPS: u16 = 1000;
+
+// Achievement badges, by milestone: first stake, a year of continuous staking, 100k WCT in one
+// position
+pub const BADGE_FIRST_STAKE: u8 = 0;
+pub const BADGE_ONE_YEAR_STAKED: u8 = 1;
+pub const BADGE_100K_LOCKED: u8 = 2;
+
+// Continuous staking needed for BADGE_ONE_YEAR_STAKED
+pub const BADGE_STREAK_DURATION: i64 = 365 * 24 * 60 * 60;
+
+// Position size needed for BADGE_100K_LOCKED: 100k WCT (9 decimals)
+pub const BADGE_LOCKED_AMOUNT: u64 = 100_000 * 1_000_000_000;
+
+// Default minimum position size: 1 WCT (9 decimals)
+pub const DEFAULT_MIN_STAKE_AMOUNT: u64 = 1_000_000_000;
+
+// Open positions a wallet's stake registry can list
+pub const MAX_REGISTERED_POSITIONS: usize = 64;
+
+// Most positions stake_ladder opens in one transaction
+pub const MAX_LADDER_RUNGS: usize = 8;
+
+// Modes for StakingPool.compliance_mode
+pub const COMPLIANCE_NONE: u8 = 0;
+pub const COMPLIANCE_ALLOWLIST: u8 = 1;
+pub const COMPLIANCE_DENYLIST: u8 = 2;
+
+// Bits for StakingPool.compliance_scope: opening stake, and claiming rewards
+pub const COMPLIANCE_STAKING: u8 = 1 << 0;
+pub const COMPLIANCE_CLAIMS: u8 = 1 << 1;
+
+// Circuit breaker bits for StakingPool.paused_flags
+pub const PAUSE_STAKING: u8 = 1 << 0;
+pub const PAUSE_CLAIMS: u8 = 1 << 1;
+pub const PAUSE_UNSTAKING: u8 = 1 << 2;
+pub const PAUSE_ALL: u8 = PAUSE_STAKING | PAUSE_CLAIMS | PAUSE_UNSTAKING;
+
+// Length of the epochs snapshot_epoch records: one day
+pub const SNAPSHOT_EPOCH_DURATION: i64 = 24 * 60 * 60;
+
+// Number of reward rate changes kept on the pool
+pub const MAX_RATE_CHECKPOINTS: usize = 16;
+
+// The yield oracle samples the accumulator at most daily and keeps 30 days plus today
+pub const YIELD_SAMPLE_INTERVAL: i64 = 24 * 60 * 60;
+pub const MAX_YIELD_SAMPLES: usize = 31;
+
+// Scale of reward_per_token_stored, so small per-token rewards keep their precision
+pub const REWARD_PRECISION: u128 = 1_000_000_000_000;
+
+// Checked arithmetic that fails with MathOverflow/MathUnderflow instead of panicking
+trait CheckedMath: Sized {
+    fn try_add(self, rhs: Self) -> Result<Self>;
+    fn try_sub(self, rhs: Self) -> Result<Self>;
+    fn try_mul(self, rhs: Self) -> Result<Self>;
+    fn try_div(self, rhs: Self) -> Result<Self>;
+}
+
+macro_rules! impl_checked_math {
+    ($($t:ty),*) => {
+        $(
+            impl CheckedMath for $t {
+                fn try_add(self, rhs: Self) -> Result<Self> {
+                    self.checked_add(rhs).ok_or_else(|| error!(StakingError::MathOverflow))
+                }
+                
+                fn try_sub(self, rhs: Self) -> Result<Self> {
+                    self.checked_sub(rhs).ok_or_else(|| error!(StakingError::MathUnderflow))
+                }
+                
+                fn try_mul(self, rhs: Self) -> Result<Self> {
+                    self.checked_mul(rhs).ok_or_else(|| error!(StakingError::MathOverflow))
+                }
+                
+                // Only a zero divisor fails
+                fn try_div(self, rhs: Self) -> Result<Self> {
+                    self.checked_div(rhs).ok_or_else(|| error!(StakingError::MathOverflow))
+                }
+            }
+        )*
+    };
+}
+
+impl_checked_math!(u8, u16, u32, u64, u128, i64);
+
+// Bring the pool's reward-per-token accumulator up to `now`
+// With an emission schedule, the tokens emitted in the period are shared by stake weight:
+// reward_per_token += emitted * 10000 / total_reward_weight
+// Otherwise the flat rate applies:
+// reward_per_token += reward_rate * time_elapsed / (365 * 24 * 60 * 60 * 10000)
+// reward_rate is in basis points (1/100 of a percent)
+// Nothing accrues past a retiring pool's cutoff
+fn update_reward_accumulator(staking_pool: &mut StakingPool, now: i64) -> Result<()> {
+    let now = if staking_pool.retire_at != 0 { now.min(staking_pool.retire_at) } else { now };
+    if now <= staking_pool.last_update_timestamp {
+        return Ok(());
+    }
+    let time_elapsed = now.try_sub(staking_pool.last_update_timestamp)?;
+    
+    let accrued = if staking_pool.emission_per_epoch > 0 {
+        // Emissions with nobody staked are left undistributed
+        if staking_pool.total_reward_weight == 0 {
+            0
+        } else {
+            emitted_between(staking_pool, staking_pool.last_update_timestamp, now)?
+                .try_mul(REWARD_PRECISION * 10000)?
+                .try_div(staking_pool.total_reward_weight)?
+        }
+    } else {
+        (staking_pool.reward_rate as u128)
+            .try_mul(time_elapsed as u128)?
+            .try_mul(REWARD_PRECISION)?
+            .try_div((365 * 24 * 60 * 60 * 10000) as u128)?
+    };
+    
+    staking_pool.reward_per_token_stored = staking_pool.reward_per_token_stored.try_add(accrued)?;
+    
+    // The secondary reward streams at its own rate, shared by stake weight
+    if staking_pool.secondary_reward_rate > 0 && staking_pool.total_reward_weight > 0 {
+        let secondary_accrued = (staking_pool.secondary_reward_rate as u128)
+            .try_mul(time_elapsed as u128)?
+            .try_mul(REWARD_PRECISION * 10000)?
+            .try_div(staking_pool.total_reward_weight)?;
+        staking_pool.secondary_reward_per_token_stored = staking_pool
+            .secondary_reward_per_token_stored
+            .try_add(secondary_accrued)?;
+    }
+    
+    staking_pool.last_update_timestamp = now;
+    Ok(())
+}
+
+// Tokens the emission schedule releases between `from` and `to`. Each epoch releases
+// emission_per_epoch, halved every halving_interval epochs, spread evenly over the epoch.
+fn emitted_between(staking_pool: &StakingPool, from: i64, to: i64) -> Result<u128> {
+    let start = staking_pool.emission_start;
+    let epoch_duration = staking_pool.emission_epoch_duration;
+    let halving_period = if staking_pool.halving_interval > 0 {
+        epoch_duration.saturating_mul(staking_pool.halving_interval as i64)
+    } else {
+        i64::MAX
+    };
+    
+    let mut emitted: u128 = 0;
+    let mut t = from.max(start);
+    while t < to {
+        // Rate is constant within a halving period
+        let halvings = (t - start) / halving_period;
+        if halvings >= 64 {
+            break;
+        }
+        let period_end = start.saturating_add((halvings + 1).saturating_mul(halving_period));
+        let segment_end = period_end.min(to);
+        
+        emitted = emitted
+            .try_add(
+                ((staking_pool.emission_per_epoch >> halvings) as u128)
+                    .try_mul((segment_end - t) as u128)?
+                    .try_div(epoch_duration as u128)?,
+            )?;
+        t = segment_end;
+    }
+    Ok(emitted)
+}
+
+// Annual base reward rate, in basis points, that a 1x position earns right now. With an
+// emission schedule this is the next year's emissions over the current stake weight.
+fn effective_rate_bps(staking_pool: &StakingPool, now: i64) -> Result<u64> {
+    if staking_pool.retire_at != 0 && now >= staking_pool.retire_at {
+        return Ok(0);
+    }
+    if staking_pool.emission_per_epoch == 0 {
+        return Ok(staking_pool.reward_rate);
+    }
+    if staking_pool.total_reward_weight == 0 {
+        return Ok(0);
+    }
+    Ok(emitted_between(staking_pool, now, now.saturating_add(365 * 24 * 60 * 60))?
+        .try_mul(10000 * 10000)?
+        .try_div(staking_pool.total_reward_weight)?
+        .min(u64::MAX as u128) as u64)
+}
+
+// Take a reward payout out of the funded reserve, failing cleanly before any transfer if the
+// reserve can't cover it
+fn draw_reward_reserve(staking_pool: &mut StakingPool, amount: u64) -> Result<()> {
+    require!(amount <= staking_pool.reward_reserve, StakingError::RewardReserveDepleted);
+    staking_pool.reward_reserve = staking_pool.reward_reserve.try_sub(amount)?;
+    Ok(())
+}
+
+// Pay a reward to `to`, less the pool's performance fee which goes to the fee vault. In mint
+// mode it is minted fresh up to the pool's emission cap, otherwise it comes out of the funded
+// rewards vault. Limits are checked before any CPI so an exhausted pool fails cleanly. Returns
+// the amount `to` received.
+fn pay_reward<'info>(
+    staking_pool: &mut Account<'info, StakingPool>,
+    reward_mint: Option<&Account<'info, Mint>>,
+    rewards_vault: &Account<'info, TokenAccount>,
+    fee_vault: Option<&Account<'info, TokenAccount>>,
+    to: AccountInfo<'info>,
+    token_program: &Program<'info, Token>,
+    amount: u64,
+) -> Result<u64> {
+    charge_reward(staking_pool, amount)?;
+    
+    let fee = (amount as u128)
+        .try_mul(staking_pool.performance_fee_bps as u128)?
+        .try_div(10000)? as u64;
+    if fee > 0 {
+        let fee_vault = fee_vault.ok_or(StakingError::FeeVaultRequired)?;
+        send_reward(staking_pool, reward_mint, rewards_vault, fee_vault.to_account_info(), token_program, fee)?;
+    }
+    
+    let net_amount = amount.try_sub(fee)?;
+    send_reward(staking_pool, reward_mint, rewards_vault, to, token_program, net_amount)?;
+    Ok(net_amount)
+}
+
+// Pay a reward to `to`'s owner: straight away, or while the pool vests rewards, into the
+// vesting vault on a fresh linear schedule. Whatever had already vested on the owner's previous
+// schedule is released first and the rest rolls into the new one. Returns the amount after the
+// performance fee.
+#[allow(clippy::too_many_arguments)]
+fn distribute_reward<'info>(
+    staking_pool: &mut Account<'info, StakingPool>,
+    reward_mint: Option<&Account<'info, Mint>>,
+    rewards_vault: &Account<'info, TokenAccount>,
+    fee_vault: Option<&Account<'info, TokenAccount>>,
+    vesting_vault: Option<&Account<'info, TokenAccount>>,
+    reward_vesting: Option<&mut Account<'info, RewardVesting>>,
+    to: &Account<'info, TokenAccount>,
+    token_program: &Program<'info, Token>,
+    amount: u64,
+) -> Result<u64> {
+    if staking_pool.reward_vesting_period == 0 {
+        return pay_reward(
+            staking_pool,
+            reward_mint,
+            rewards_vault,
+            fee_vault,
+            to.to_account_info(),
+            token_program,
+            amount,
+        );
+    }
+    
+    let vesting_vault = vesting_vault.ok_or(StakingError::VestingAccountsRequired)?;
+    let reward_vesting = reward_vesting.ok_or(StakingError::VestingAccountsRequired)?;
+    let now = Clock::get()?.unix_timestamp;
+    
+    let net_amount = pay_reward(
+        staking_pool,
+        reward_mint,
+        rewards_vault,
+        fee_vault,
+        vesting_vault.to_account_info(),
+        token_program,
+        amount,
+    )?;
+    
+    // First use of the schedule account
+    if reward_vesting.pool == Pubkey::default() {
+        let (_, bump) = Pubkey::find_program_address(
+            &[b"reward_vesting".as_ref(), staking_pool.key().as_ref(), to.owner.as_ref()],
+            &crate::ID,
+        );
+        reward_vesting.beneficiary = to.owner;
+        reward_vesting.pool = staking_pool.key();
+        reward_vesting.bump = bump;
+    }
+    
+    let releasable = reward_vesting.releasable(now)?;
+    if releasable > 0 {
+        release_vested(staking_pool, vesting_vault, to.to_account_info(), token_program, releasable)?;
+    }
+    let locked = reward_vesting
+        .total
+        .try_sub(reward_vesting.released)?
+        .try_sub(releasable)?;
+    
+    reward_vesting.total = locked.try_add(net_amount)?;
+    reward_vesting.released = 0;
+    reward_vesting.start_timestamp = now;
+    reward_vesting.end_timestamp = now.try_add(staking_pool.reward_vesting_period)?;
+    
+    Ok(net_amount)
+}
+
+// Transfer vested rewards out of the vesting vault
+fn release_vested<'info>(
+    staking_pool: &Account<'info, StakingPool>,
+    vesting_vault: &Account<'info, TokenAccount>,
+    to: AccountInfo<'info>,
+    token_program: &Program<'info, Token>,
+    amount: u64,
+) -> Result<()> {
+    let token_mint_key = staking_pool.token_mint;
+    let pool_id = staking_pool.pool_id.to_le_bytes();
+    let bump = [staking_pool.bump];
+    let pool_seeds = &[b"staking_pool".as_ref(), token_mint_key.as_ref(), &pool_id, &bump];
+    
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            token::Transfer {
+                from: vesting_vault.to_account_info(),
+                to,
+                authority: staking_pool.to_account_info(),
+            },
+            &[pool_seeds],
+        ),
+        amount,
+    )
+}
+
+// Count a newly opened position against the wallet that opened it, adding the wallet to the
+// pool's stakers and starting its loyalty streak on its first open position
+fn open_staker_position(
+    staking_pool: &mut StakingPool,
+    staker_info: &mut StakerInfo,
+    now: i64,
+) -> Result<()> {
+    if staker_info.active_positions == 0 {
+        staking_pool.staker_count = staking_pool.staker_count.try_add(1)?;
+    }
+    if staker_info.active_positions == 0 || staker_info.streak_start == 0 {
+        staker_info.streak_start = now;
+    }
+    staker_info.active_positions = staker_info.active_positions.try_add(1)?;
+    Ok(())
+}
+
+// Count a withdrawn position, dropping the wallet from the pool's stakers and ending its
+// loyalty streak with its last one
+fn close_staker_position(staking_pool: &mut StakingPool, staker_info: &mut StakerInfo) -> Result<()> {
+    staker_info.active_positions = staker_info.active_positions.try_sub(1)?;
+    if staker_info.active_positions == 0 {
+        staking_pool.staker_count = staking_pool.staker_count.try_sub(1)?;
+        staker_info.streak_start = 0;
+    }
+    Ok(())
+}
+
+// Record a newly opened position in its creator's registry
+fn register_position(
+    stake_registry: &mut UserStakeRegistry,
+    owner: Pubkey,
+    position: Pubkey,
+    bump: u8,
+) -> Result<()> {
+    require!(
+        stake_registry.positions.len() < MAX_REGISTERED_POSITIONS,
+        StakingError::StakeRegistryFull
+    );
+    stake_registry.owner = owner;
+    stake_registry.bump = bump;
+    stake_registry.positions.push(position);
+    Ok(())
+}
+
+// Count a reward payout against the emission cap in mint mode, or draw it from the reserve
+fn charge_reward(staking_pool: &mut StakingPool, amount: u64) -> Result<()> {
+    if staking_pool.mint_rewards {
+        let total_minted = staking_pool.total_minted.try_add(amount)?;
+        require!(
+            total_minted <= staking_pool.emission_cap,
+            StakingError::EmissionCapExceeded
+        );
+        staking_pool.total_minted = total_minted;
+        Ok(())
+    } else {
+        draw_reward_reserve(staking_pool, amount)
+    }
+}
+
+// Mint or transfer reward tokens to `to`, once the pool's limits have been checked
+fn send_reward<'info>(
+    staking_pool: &Account<'info, StakingPool>,
+    reward_mint: Option<&Account<'info, Mint>>,
+    rewards_vault: &Account<'info, TokenAccount>,
+    to: AccountInfo<'info>,
+    token_program: &Program<'info, Token>,
+    amount: u64,
+) -> Result<()> {
+    let token_mint_key = staking_pool.token_mint;
+    let pool_id = staking_pool.pool_id.to_le_bytes();
+    let bump = [staking_pool.bump];
+    let pool_seeds = &[b"staking_pool".as_ref(), token_mint_key.as_ref(), &pool_id, &bump];
+    
+    if staking_pool.mint_rewards {
+        let reward_mint = reward_mint.ok_or(StakingError::MintAccountRequired)?;
+        token::mint_to(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                token::MintTo {
+                    mint: reward_mint.to_account_info(),
+                    to,
+                    authority: staking_pool.to_account_info(),
+                },
+                &[pool_seeds],
+            ),
+            amount,
+        )
+    } else {
+        token::transfer(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                token::Transfer {
+                    from: rewards_vault.to_account_info(),
+                    to,
+                    authority: staking_pool.to_account_info(),
+                },
+                &[pool_seeds],
+            ),
+            amount,
+        )
+    }
+}
+
+// stWCT receipts to burn for `amount` of principal leaving a position, along with any receipts
+// a slash left without principal, which the position stops owing
+fn receipts_to_burn(user_stake: &mut UserStake, amount: u64) -> Result<u64> {
+    let receipt_amount = amount.try_add(user_stake.slashed_receipts)?;
+    user_stake.slashed_receipts = 0;
+    Ok(receipt_amount)
+}
+
+// Take a position's whole balance, tokens still unbonding included, out of the pool's
+// accounting and mark it withdrawn. Returns the tokens leaving the staking vault and the stWCT
+// receipts to burn for them; unbonding tokens already had their receipts burned.
+fn exit_position(staking_pool: &mut StakingPool, user_stake: &mut UserStake) -> Result<(u64, u64)> {
+    let stake_amount = user_stake.stake_amount;
+    let amount = stake_amount.try_add(user_stake.unbonding_amount)?;
+    let receipt_amount = receipts_to_burn(user_stake, stake_amount)?;
+    
+    staking_pool.total_staked = staking_pool.total_staked.try_sub(stake_amount)?;
+    remove_reward_weight(staking_pool, stake_amount, user_stake.reward_multiplier_bps)?;
+    
+    user_stake.stake_amount = 0;
+    user_stake.unbonding_amount = 0;
+    set_voting_power(staking_pool, user_stake, 0)?;
+    user_stake.withdrawn = true;
+    Ok((amount, receipt_amount))
+}
+
+// Mint stWCT receipts for principal entering the pool
+fn mint_receipt<'info>(
+    staking_pool: &Account<'info, StakingPool>,
+    receipt_mint: &Account<'info, Mint>,
+    to: AccountInfo<'info>,
+    token_program: &Program<'info, Token>,
+    amount: u64,
+) -> Result<()> {
+    let token_mint_key = staking_pool.token_mint;
+    let pool_id = staking_pool.pool_id.to_le_bytes();
+    let bump = [staking_pool.bump];
+    let pool_seeds = &[b"staking_pool".as_ref(), token_mint_key.as_ref(), &pool_id, &bump];
+    
+    token::mint_to(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            token::MintTo {
+                mint: receipt_mint.to_account_info(),
+                to,
+                authority: staking_pool.to_account_info(),
+            },
+            &[pool_seeds],
+        ),
+        amount,
+    )
+}
+
+// Burn the holder's stWCT receipts for principal leaving the pool
+fn burn_receipt<'info>(
+    receipt_mint: &Account<'info, Mint>,
+    from: &Account<'info, TokenAccount>,
+    authority: &Signer<'info>,
+    token_program: &Program<'info, Token>,
+    amount: u64,
+) -> Result<()> {
+    token::burn(
+        CpiContext::new(
+            token_program.to_account_info(),
+            token::Burn {
+                mint: receipt_mint.to_account_info(),
+                from: from.to_account_info(),
+                authority: authority.to_account_info(),
+            },
+        ),
+        amount,
+    )
+}
+
+// Add tokens earning at `reward_multiplier_bps` to the pool's total reward weight
+fn add_reward_weight(staking_pool: &mut StakingPool, amount: u64, reward_multiplier_bps: u16) -> Result<()> {
+    staking_pool.total_reward_weight = staking_pool
+        .total_reward_weight
+        .try_add((amount as u128).try_mul(reward_multiplier_bps as u128)?)?;
+    Ok(())
+}
+
+// Remove tokens earning at `reward_multiplier_bps` from the pool's total reward weight
+fn remove_reward_weight(staking_pool: &mut StakingPool, amount: u64, reward_multiplier_bps: u16) -> Result<()> {
+    staking_pool.total_reward_weight = staking_pool
+        .total_reward_weight
+        .try_sub((amount as u128).try_mul(reward_multiplier_bps as u128)?)?;
+    Ok(())
+}
+
+// Sample the pool's accumulator into its yield oracle, at most once per YIELD_SAMPLE_INTERVAL,
+// and refresh the trailing rates against the accumulator as of `now`
+fn refresh_yield_oracle(
+    yield_oracle: &mut PoolYieldOracle,
+    staking_pool: &StakingPool,
+    now: i64,
+) -> Result<()> {
+    let count = yield_oracle.sample_count as usize;
+    let current = YieldSample {
+        timestamp: now,
+        reward_per_token_stored: staking_pool.reward_per_token_stored,
+    };
+    
+    if count == 0 || now.try_sub(yield_oracle.samples[count - 1].timestamp)? >= YIELD_SAMPLE_INTERVAL {
+        if count < MAX_YIELD_SAMPLES {
+            yield_oracle.samples[count] = current;
+            yield_oracle.sample_count += 1;
+        } else {
+            yield_oracle.samples.rotate_left(1);
+            yield_oracle.samples[MAX_YIELD_SAMPLES - 1] = current;
+        }
+    }
+    
+    yield_oracle.rate_7d_bps = trailing_rate_bps(yield_oracle, &current, 7 * 24 * 60 * 60)?;
+    yield_oracle.rate_30d_bps = trailing_rate_bps(yield_oracle, &current, 30 * 24 * 60 * 60)?;
+    yield_oracle.updated_at = now;
+    Ok(())
+}
+
+// Annual base rate a 1x position realized from the newest sample at least `window` old (the
+// oldest one while the history is shorter) up to `current`
+fn trailing_rate_bps(yield_oracle: &PoolYieldOracle, current: &YieldSample, window: i64) -> Result<u64> {
+    let samples = &yield_oracle.samples[..yield_oracle.sample_count as usize];
+    let cutoff = current.timestamp.try_sub(window)?;
+    let start = match samples
+        .iter()
+        .rev()
+        .find(|sample| sample.timestamp <= cutoff)
+        .or_else(|| samples.first())
+    {
+        Some(sample) if sample.timestamp < current.timestamp => sample,
+        _ => return Ok(0),
+    };
+    
+    let elapsed = current.timestamp.try_sub(start.timestamp)?;
+    Ok(current
+        .reward_per_token_stored
+        .try_sub(start.reward_per_token_stored)?
+        .try_mul(365 * 24 * 60 * 60 * 10000)?
+        .try_div(elapsed as u128)?
+        .try_div(REWARD_PRECISION)? as u64)
+}
+
+// Record that the pool's current reward rate applies from `now`. The accumulator is settled
+// at every checkpoint, so accrual is exact per segment; the oldest checkpoint is dropped once
+// the history is full.
+fn record_rate_checkpoint(staking_pool: &mut StakingPool, now: i64) {
+    let count = staking_pool.rate_checkpoint_count as usize;
+    let checkpoint = RateCheckpoint {
+        timestamp: now,
+        reward_rate: staking_pool.reward_rate,
+    };
+    
+    if count < MAX_RATE_CHECKPOINTS {
+        staking_pool.rate_checkpoints[count] = checkpoint;
+        staking_pool.rate_checkpoint_count += 1;
+    } else {
+        staking_pool.rate_checkpoints.rotate_left(1);
+        staking_pool.rate_checkpoints[MAX_RATE_CHECKPOINTS - 1] = checkpoint;
+    }
+}
+
+// Settle accrual at the old rate, then switch the pool to new reward parameters
+fn set_reward_params(
+    staking_pool: &mut StakingPool,
+    reward_rate: u64,
+    min_stake_duration: i64,
+    max_stake_duration: i64,
+    now: i64,
+) -> Result<()> {
+    update_reward_accumulator(staking_pool, now)?;
+    
+    let old_reward_rate = staking_pool.reward_rate;
+    let old_min_stake_duration = staking_pool.min_stake_duration;
+    let old_max_stake_duration = staking_pool.max_stake_duration;
+    
+    staking_pool.reward_rate = reward_rate;
+    staking_pool.min_stake_duration = min_stake_duration;
+    staking_pool.max_stake_duration = max_stake_duration;
+    record_rate_checkpoint(staking_pool, now);
+    
+    emit!(ParamsUpdateEvent {
+        old_reward_rate,
+        old_min_stake_duration,
+        old_max_stake_duration,
+        reward_rate,
+        min_stake_duration,
+        max_stake_duration,
+    });
+    
+    Ok(())
+}
+
+// Rewards a position has earned but not been paid, against an up-to-date accumulator
+fn pending_reward(staking_pool: &StakingPool, user_stake: &UserStake) -> Result<u64> {
+    earned_reward(staking_pool, user_stake)?.try_add(user_stake.reward_owed)
+}
+
+// Part of pending_reward that comes from the pool's accumulator, without rewards owed from elsewhere
+fn earned_reward(staking_pool: &StakingPool, user_stake: &UserStake) -> Result<u64> {
+    Ok(accumulated_reward(staking_pool, user_stake)?
+        .try_sub(user_stake.reward_debt)? as u64)
+}
+
+// Mark everything the position has earned so far as paid, after a claim or a balance change
+fn sync_reward_debt(staking_pool: &StakingPool, user_stake: &mut UserStake) -> Result<()> {
+    user_stake.reward_debt = accumulated_reward(staking_pool, user_stake)?;
+    user_stake.reward_owed = 0;
+    user_stake.secondary_reward_debt = accumulated_secondary_reward(staking_pool, user_stake)?;
+    Ok(())
+}
+
+// Move the position's pending secondary reward into secondary_reward_owed, so it survives a
+// balance change and can be paid out separately from the primary reward
+fn bank_secondary_reward(staking_pool: &StakingPool, user_stake: &mut UserStake) -> Result<()> {
+    let accumulated = accumulated_secondary_reward(staking_pool, user_stake)?;
+    let pending = accumulated.try_sub(user_stake.secondary_reward_debt)? as u64;
+    user_stake.secondary_reward_owed = user_stake.secondary_reward_owed.try_add(pending)?;
+    user_stake.secondary_reward_debt = accumulated;
+    Ok(())
+}
+
+// Position's share of the secondary accumulator, weighted like the primary one
+fn accumulated_secondary_reward(staking_pool: &StakingPool, user_stake: &UserStake) -> Result<u128> {
+    (user_stake.stake_amount as u128)
+        .try_mul(user_stake.reward_multiplier_bps as u128)?
+        .try_mul(staking_pool.secondary_reward_per_token_stored)?
+        .try_div(REWARD_PRECISION * 10000)
+}
+
+// Pay out a position's banked secondary reward from the secondary rewards vault
+fn pay_secondary_reward<'info>(
+    staking_pool: &mut Account<'info, StakingPool>,
+    user_stake: &mut UserStake,
+    secondary_rewards_vault: &Account<'info, TokenAccount>,
+    to: AccountInfo<'info>,
+    token_program: &Program<'info, Token>,
+) -> Result<u64> {
+    let amount = user_stake.secondary_reward_owed;
+    if amount == 0 {
+        return Ok(0);
+    }
+    require!(
+        amount <= staking_pool.secondary_reward_reserve,
+        StakingError::RewardReserveDepleted
+    );
+    staking_pool.secondary_reward_reserve = staking_pool.secondary_reward_reserve.try_sub(amount)?;
+    user_stake.secondary_reward_owed = 0;
+    
+    let token_mint_key = staking_pool.token_mint;
+    let pool_id = staking_pool.pool_id.to_le_bytes();
+    let bump = [staking_pool.bump];
+    let pool_seeds = &[b"staking_pool".as_ref(), token_mint_key.as_ref(), &pool_id, &bump];
+    
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            token::Transfer {
+                from: secondary_rewards_vault.to_account_info(),
+                to,
+                authority: staking_pool.to_account_info(),
+            },
+            &[pool_seeds],
+        ),
+        amount,
+    )?;
+    
+    Ok(amount)
+}
+
+// Move a position to a new tier reward rate from now on, keeping what it has already earned
+// at the old rate pending. Only used to raise the rate, so the new debt cannot underflow.
+fn set_reward_multiplier(
+    staking_pool: &mut StakingPool,
+    user_stake: &mut UserStake,
+    reward_multiplier_bps: u16,
+) -> Result<()> {
+    let pending = earned_reward(staking_pool, user_stake)? as u128;
+    bank_secondary_reward(staking_pool, user_stake)?;
+    remove_reward_weight(staking_pool, user_stake.stake_amount, user_stake.reward_multiplier_bps)?;
+    add_reward_weight(staking_pool, user_stake.stake_amount, reward_multiplier_bps)?;
+    user_stake.reward_multiplier_bps = reward_multiplier_bps;
+    user_stake.reward_debt = accumulated_reward(staking_pool, user_stake)?
+        .try_sub(pending)?;
+    user_stake.secondary_reward_debt = accumulated_secondary_reward(staking_pool, user_stake)?;
+    Ok(())
+}
+
+// Position's share of the accumulator: its balance weighted by its tier's reward multiplier
+fn accumulated_reward(staking_pool: &StakingPool, user_stake: &UserStake) -> Result<u128> {
+    (user_stake.stake_amount as u128)
+        .try_mul(user_stake.reward_multiplier_bps as u128)?
+        .try_mul(staking_pool.reward_per_token_stored)?
+        .try_div(REWARD_PRECISION * 10000)
+}
+
+// Tokenized positions can only be used by the wallet holding the position NFT; the owner
+// field follows the NFT through sync_position_owner
+fn require_position_holder(
+    user_stake: &UserStake,
+    user: Pubkey,
+    position_token_account: Option<&Account<TokenAccount>>,
+) -> Result<()> {
+    if user_stake.position_mint == Pubkey::default() {
+        return Ok(());
+    }
+    let position_token_account = position_token_account.ok_or(StakingError::PositionNftRequired)?;
+    require!(
+        position_token_account.mint == user_stake.position_mint
+            && position_token_account.owner == user
+            && position_token_account.amount == 1,
+        StakingError::PositionNftRequired
+    );
+    Ok(())
+}
+
+// Release the front of the unbonding queue, up to what's left of the current epoch's budget.
+// The queue is strictly first in, first out: tickets are released in the order they were issued.
+fn advance_unbonding_queue(staking_pool: &mut StakingPool, now: i64) -> Result<()> {
+    // With the queue turned off, everything still in it is released at once
+    if staking_pool.unbonding_budget == 0 {
+        staking_pool.unbonding_queue_head = staking_pool.unbonding_queue_tail;
+        return Ok(());
+    }
+    
+    let epoch = (now / UNBONDING_EPOCH_DURATION) as u64;
+    if epoch != staking_pool.unbonding_budget_epoch {
+        staking_pool.unbonding_budget_epoch = epoch;
+        staking_pool.unbonding_budget_used = 0;
+    }
+    
+    let queued = staking_pool
+        .unbonding_queue_tail
+        .try_sub(staking_pool.unbonding_queue_head)?;
+    let available = staking_pool
+        .unbonding_budget
+        .saturating_sub(staking_pool.unbonding_budget_used);
+    let released = queued.min(available);
+    staking_pool.unbonding_queue_head = staking_pool.unbonding_queue_head.try_add(released)?;
+    staking_pool.unbonding_budget_used = staking_pool.unbonding_budget_used.try_add(released)?;
+    Ok(())
+}
+
+// Fail until the unbonding queue has released the position's ticket
+fn require_unbonding_released(staking_pool: &StakingPool, user_stake: &UserStake) -> Result<()> {
+    if user_stake.unbonding_queue_ticket > staking_pool.unbonding_queue_head {
+        msg!(
+            "Unbonding queued: {} tokens ahead of this position's release",
+            user_stake.unbonding_queue_ticket - staking_pool.unbonding_queue_head
+        );
+        return err!(StakingError::UnbondingQueued);
+    }
+    Ok(())
+}
+
+// Whether a position may exit: its lock has run out, or its pool has passed its retirement cutoff
+fn lock_released(staking_pool: &StakingPool, user_stake: &UserStake, now: i64) -> bool {
+    now >= user_stake.end_timestamp || (staking_pool.retire_at != 0 && now >= staking_pool.retire_at)
+}
+
+// Realm accounts register_voting_power takes besides the pool
+struct RealmRegistration<'info> {
+    governance_program: AccountInfo<'info>,
+    governance: AccountInfo<'info>,
+    voting_power_registry: AccountInfo<'info>,
+    voter_power: AccountInfo<'info>,
+    voting_power_page: AccountInfo<'info>,
+    payer: AccountInfo<'info>, // Pays for the voter's power record if it doesn't exist yet
+    system_program: AccountInfo<'info>,
+    rent: AccountInfo<'info>,
+}
+
+// Replace what a position contributed to its registered voter with `voting_power` and
+// `reputation_boost` for `voter`, through the realm's register_voting_power signed by the pool
+fn register_position_power<'info>(
+    staking_pool: &Account<'info, StakingPool>,
+    user_stake: &mut UserStake,
+    realm: &RealmRegistration<'info>,
+    voter: Pubkey,
+    voting_power: u64,
+    reputation_boost: u64,
+) -> Result<()> {
+    require!(
+        staking_pool.governance_program != Pubkey::default()
+            && realm.governance_program.key() == staking_pool.governance_program
+            && realm.governance.key() == staking_pool.governance,
+        StakingError::InvalidGovernanceAccounts
+    );
+    
+    // register_voting_power(voter, old power, old boost, power, boost, position start), signed
+    // by the pool. Governance only counts added power once the position has warmed up.
+    let mut data = hash(b"global:register_voting_power").to_bytes()[..8].to_vec();
+    data.extend_from_slice(voter.as_ref());
+    data.extend_from_slice(&user_stake.registered_voting_power.to_le_bytes());
+    data.extend_from_slice(&user_stake.registered_reputation_boost.to_le_bytes());
+    data.extend_from_slice(&voting_power.to_le_bytes());
+    data.extend_from_slice(&reputation_boost.to_le_bytes());
+    data.extend_from_slice(&user_stake.start_timestamp.to_le_bytes());
+    
+    let ix = Instruction {
+        program_id: realm.governance_program.key(),
+        accounts: vec![
+            AccountMeta::new(realm.governance.key(), false),
+            AccountMeta::new(realm.voting_power_registry.key(), false),
+            AccountMeta::new(realm.voter_power.key(), false),
+            AccountMeta::new(realm.voting_power_page.key(), false),
+            AccountMeta::new_readonly(staking_pool.key(), true),
+            AccountMeta::new(realm.payer.key(), true),
+            AccountMeta::new_readonly(realm.system_program.key(), false),
+            AccountMeta::new_readonly(realm.rent.key(), false),
+        ],
+        data,
+    };
+    
+    let pool_seeds = &[
+        b"staking_pool".as_ref(),
+        staking_pool.token_mint.as_ref(),
+        &staking_pool.pool_id.to_le_bytes(),
+        &[staking_pool.bump],
+    ];
+    
+    invoke_signed(
+        &ix,
+        &[
+            realm.governance.clone(),
+            realm.voting_power_registry.clone(),
+            realm.voter_power.clone(),
+            realm.voting_power_page.clone(),
+            staking_pool.to_account_info(),
+            realm.payer.clone(),
+            realm.system_program.clone(),
+            realm.rent.clone(),
+        ],
+        &[pool_seeds],
+    )?;
+    
+    user_stake.registered_voter = if voting_power > 0 { voter } else { Pubkey::default() };
+    user_stake.registered_voting_power = voting_power;
+    user_stake.registered_reputation_boost = reputation_boost;
+    
+    emit!(PositionVotingPowerSyncedEvent {
+        voter,
+        position_index: user_stake.position_index,
+        voting_power,
+        reputation_boost,
+    });
+    
+    Ok(())
+}
+
+// Take `slash_bps` of a position's stake out of its and the pool's accounting. Pending base
+// rewards on the position are forfeited; the secondary reward stays banked. The owner's stWCT
+// receipts for the slashed tokens no longer have principal behind them and are owed back on
+// the position's next exit. Returns the slashed amount.
+fn apply_slash(staking_pool: &mut StakingPool, user_stake: &mut UserStake, slash_bps: u16, now: i64) -> Result<u64> {
+    let amount = (user_stake.stake_amount as u128)
+        .try_mul(slash_bps as u128)?
+        .try_div(10000)? as u64;
+    require!(amount > 0, StakingError::InvalidSlashAmount);
+    
+    update_reward_accumulator(staking_pool, now)?;
+    bank_secondary_reward(staking_pool, user_stake)?;
+    
+    reduce_position(staking_pool, user_stake, amount)?;
+    user_stake.slashed_receipts = user_stake.slashed_receipts.try_add(amount)?;
+    sync_reward_debt(staking_pool, user_stake)?;
+    
+    staking_pool.total_staked = staking_pool.total_staked.try_sub(amount)?;
+    remove_reward_weight(staking_pool, amount, user_stake.reward_multiplier_bps)?;
+    Ok(amount)
+}
+
+// Action hash a slash proposal approves: the position and the share of its stake to take
+fn slash_action_hash(position: &Pubkey, slash_bps: u16) -> [u8; 32] {
+    hashv(&[b"slash_stake".as_ref(), position.as_ref(), &slash_bps.to_le_bytes()]).to_bytes()
+}
+
+// Take a position's registered governance power off its voter as the position exits. While it
+// is registered, the realm accounts follow as the instruction's remaining accounts: governance
+// program, governance, voting power registry, voter power, voting power page, system program
+// and rent.
+fn deregister_exiting_position<'info>(
+    staking_pool: &Account<'info, StakingPool>,
+    user_stake: &mut UserStake,
+    remaining_accounts: &[AccountInfo<'info>],
+    payer: AccountInfo<'info>,
+) -> Result<()> {
+    if user_stake.registered_voting_power == 0 {
+        return Ok(());
+    }
+    require!(remaining_accounts.len() == 7, StakingError::InvalidGovernanceAccounts);
+    
+    let realm = RealmRegistration {
+        governance_program: remaining_accounts[0].clone(),
+        governance: remaining_accounts[1].clone(),
+        voting_power_registry: remaining_accounts[2].clone(),
+        voter_power: remaining_accounts[3].clone(),
+        voting_power_page: remaining_accounts[4].clone(),
+        payer,
+        system_program: remaining_accounts[5].clone(),
+        rent: remaining_accounts[6].clone(),
+    };
+    let voter = user_stake.registered_voter;
+    register_position_power(staking_pool, user_stake, &realm, voter, 0, 0)
+}
+
+// Fail while the position still counts towards a voter's registered governance power
+fn require_unregistered(user_stake: &UserStake) -> Result<()> {
+    require!(
+        user_stake.registered_voting_power == 0,
+        StakingError::VotingPowerRegistered
+    );
+    Ok(())
+}
+
+// Fail while a freeze on the position is in force
+fn require_not_frozen(user_stake: &UserStake, now: i64) -> Result<()> {
+    require!(
+        user_stake.frozen_until == 0 || now >= user_stake.frozen_until,
+        StakingError::PositionFrozen
+    );
+    Ok(())
+}
+
+// Fail while a freeze on the position is in force, and lift one that has run out
+fn settle_freeze(staking_pool: &mut StakingPool, user_stake: &mut UserStake, now: i64) -> Result<()> {
+    if user_stake.frozen_until == 0 {
+        return Ok(());
+    }
+    require_not_frozen(user_stake, now)?;
+    lift_freeze(staking_pool, user_stake, now)
+}
+
+// Put a frozen position back to earning, with the reward it had pending when it was frozen.
+// Nothing accrued while it was frozen.
+fn lift_freeze(staking_pool: &mut StakingPool, user_stake: &mut UserStake, now: i64) -> Result<()> {
+    update_reward_accumulator(staking_pool, now)?;
+    add_reward_weight(staking_pool, user_stake.stake_amount, user_stake.reward_multiplier_bps)?;
+    user_stake.reward_debt = accumulated_reward(staking_pool, user_stake)?
+        .try_sub(user_stake.frozen_reward as u128)?;
+    user_stake.secondary_reward_debt = accumulated_secondary_reward(staking_pool, user_stake)?;
+    user_stake.frozen_until = 0;
+    user_stake.frozen_reward = 0;
+    Ok(())
+}
+
+// Wallet a position's rewards are paid to: its reward destination, or else its owner
+fn reward_payee(user_stake: &UserStake) -> Pubkey {
+    if user_stake.reward_destination == Pubkey::default() {
+        user_stake.owner
+    } else {
+        user_stake.reward_destination
+    }
+}
+
+// Check a reward payout goes to the position's reward destination, when the owner set one
+fn require_reward_destination(user_stake: &UserStake, to: &Account<TokenAccount>) -> Result<()> {
+    require!(
+        user_stake.reward_destination == Pubkey::default()
+            || to.owner == user_stake.reward_destination,
+        StakingError::InvalidRewardDestination
+    );
+    Ok(())
+}
+
+// Check `wallet` against the pool's compliance list for instructions in `scope`. Wallets
+// without an entry are off the list; an empty account is only bound to `wallet` by the
+// caller's seeds constraint, so those must derive it from `wallet`.
+fn require_compliant(
+    staking_pool: &StakingPool,
+    wallet: Pubkey,
+    compliance_entry: Option<&UncheckedAccount>,
+    scope: u8,
+) -> Result<()> {
+    if staking_pool.compliance_mode == COMPLIANCE_NONE || staking_pool.compliance_scope & scope == 0 {
+        return Ok(());
+    }
+    let compliance_entry = compliance_entry.ok_or(StakingError::ComplianceEntryRequired)?;
+    let listed = if compliance_entry.data_is_empty() {
+        false
+    } else {
+        let entry = Account::<ComplianceEntry>::try_from(&compliance_entry.to_account_info())?;
+        require!(entry.wallet == wallet, StakingError::ComplianceEntryMismatch);
+        entry.listed
+    };
+    let allowed = if staking_pool.compliance_mode == COMPLIANCE_ALLOWLIST {
+        listed
+    } else {
+        !listed
+    };
+    require!(allowed, StakingError::WalletNotPermitted);
+    Ok(())
+}
+
+// Reject a claim made before the pool's minimum claim interval has passed, logging when the
+// position can next be claimed
+fn require_claim_interval(staking_pool: &StakingPool, user_stake: &UserStake, now: i64) -> Result<()> {
+    let next_claim_at = user_stake
+        .last_claim_timestamp
+        .saturating_add(staking_pool.min_claim_interval);
+    if now < next_claim_at {
+        msg!("Claim too soon: next claim allowed at {}", next_claim_at);
+        return err!(StakingError::ClaimTooSoon);
+    }
+    Ok(())
+}
+
+// Reject new stake that would push the pool past its TVL cap
+fn require_within_tvl_cap(staking_pool: &StakingPool, amount: u64) -> Result<()> {
+    if staking_pool.max_total_staked == 0 {
+        return Ok(());
+    }
+    let new_total = staking_pool.total_staked.try_add(amount)?;
+    require!(new_total <= staking_pool.max_total_staked, StakingError::TvlCapExceeded);
+    Ok(())
+}
+
+// Loyalty bonus on top of a base reward: LOYALTY_BONUS_BPS_PER_EPOCH for every full loyalty
+// epoch the wallet has kept a position open, up to MAX_LOYALTY_BONUS_BPS
+fn loyalty_bonus_on(
+    staker_info: Option<&Account<StakerInfo>>,
+    reward_amount: u64,
+    now: i64,
+) -> Result<u64> {
+    let streak_start = match staker_info {
+        Some(staker_info) if staker_info.active_positions > 0 && staker_info.streak_start != 0 => {
+            staker_info.streak_start
+        }
+        _ => return Ok(0),
+    };
+    let epochs = now.try_sub(streak_start)?.try_div(LOYALTY_EPOCH_DURATION)? as u64;
+    let bonus_bps = epochs
+        .try_mul(LOYALTY_BONUS_BPS_PER_EPOCH as u64)?
+        .min(MAX_LOYALTY_BONUS_BPS as u64);
+    Ok((reward_amount as u128)
+        .try_mul(bonus_bps as u128)?
+        .try_div(10000)? as u64)
+}
+
+// Participation bonus on top of a base reward
+fn participation_bonus_on(staking_pool: &StakingPool, reward_amount: u64) -> Result<u64> {
+    Ok((reward_amount as u128)
+        .try_mul(staking_pool.participation_bonus_bps as u128)?
+        .try_div(10000)? as u64)
+}
+
+// Drop a position's NFT boost unless `holder` still has the boosting NFT
+fn refresh_nft_boost(
+    staking_pool: &mut StakingPool,
+    user_stake: &mut UserStake,
+    holder: Pubkey,
+    boost_nft_account: Option<&Account<TokenAccount>>,
+) -> Result<()> {
+    if user_stake.nft_boost_mint == Pubkey::default() {
+        return Ok(());
+    }
+    let boost_nft_account = boost_nft_account.map(|account| &**account);
+    if !holds_boost_nft(user_stake.nft_boost_mint, holder, boost_nft_account) {
+        remove_nft_boost(staking_pool, user_stake)?;
+    }
+    Ok(())
+}
+
+// Whether `boost_nft_account` is `holder`'s account holding the boosting NFT
+fn holds_boost_nft(nft_boost_mint: Pubkey, holder: Pubkey, boost_nft_account: Option<&TokenAccount>) -> bool {
+    boost_nft_account.map_or(false, |account| {
+        account.mint == nft_boost_mint && account.owner == holder && account.amount == 1
+    })
+}
+
+// Take a position's NFT boost off its reward multiplier and voting power. The accumulator must
+// be up to date; rewards earned so far are kept.
+fn remove_nft_boost(staking_pool: &mut StakingPool, user_stake: &mut UserStake) -> Result<()> {
+    if user_stake.nft_boost_mint == Pubkey::default() {
+        return Ok(());
+    }
+    let boost_bps = user_stake.nft_boost_bps;
+    let reward_multiplier_bps = user_stake.reward_multiplier_bps.try_sub(boost_bps)?;
+    set_reward_multiplier(staking_pool, user_stake, reward_multiplier_bps)?;
+    let voting_power = (user_stake.voting_power as u128)
+        .try_mul(10000)?
+        .try_div(10000 + boost_bps as u128)? as u64;
+    set_voting_power(staking_pool, user_stake, voting_power)?;
+    
+    emit!(NftBoostRemovedEvent {
+        user: user_stake.owner,
+        position_index: user_stake.position_index,
+        nft_mint: user_stake.nft_boost_mint,
+        voting_power: user_stake.voting_power,
+    });
+    
+    user_stake.nft_boost_mint = Pubkey::default();
+    user_stake.nft_boost_bps = 0;
+    Ok(())
+}
+
+// Voting power with an NFT boost of `boost_bps` applied
+fn boosted_voting_power(voting_power: u64, boost_bps: u16) -> Result<u64> {
+    Ok((voting_power as u128)
+        .try_mul(10000 + boost_bps as u128)?
+        .try_div(10000)? as u64)
+}
+
+// Collection a token metadata account's NFT is a verified member of, read from the account's
+// Borsh layout: key, update authority, mint, name, symbol, uri, seller fee, creators, primary
+// sale flag, mutability flag, edition nonce, token standard, collection
+fn verified_collection(data: &[u8]) -> Option<Pubkey> {
+    let read_u32 = |offset: usize| -> Option<usize> {
+        Some(u32::from_le_bytes(data.get(offset..offset + 4)?.try_into().ok()?) as usize)
+    };
+    
+    let mut offset = 1 + 32 + 32;
+    for _ in 0..3 {
+        offset += 4 + read_u32(offset)?;
+    }
+    offset += 2;
+    
+    // Creators: Option<Vec<Creator>>, 34 bytes per creator
+    if *data.get(offset)? == 1 {
+        offset += 4 + read_u32(offset + 1)? * 34;
+    }
+    offset += 1;
+    offset += 2;
+    
+    // Edition nonce and token standard: Option<u8> each
+    for _ in 0..2 {
+        if *data.get(offset)? == 1 {
+            offset += 1;
+        }
+        offset += 1;
+    }
+    
+    // Collection: Option<{ verified: bool, key: Pubkey }>
+    if *data.get(offset)? != 1 || *data.get(offset + 1)? != 1 {
+        return None;
+    }
+    Some(Pubkey::new_from_array(data.get(offset + 2..offset + 34)?.try_into().ok()?))
+}
+
+// Remove tokens from a position, scaling its voting power down with the remaining balance so
+// the position keeps its tier. Returns the remaining balance.
+fn reduce_position(staking_pool: &mut StakingPool, user_stake: &mut UserStake, amount: u64) -> Result<u64> {
+    let remaining_amount = user_stake.stake_amount.try_sub(amount)?;
+    let voting_power = (user_stake.voting_power as u128)
+        .try_mul(remaining_amount as u128)?
+        .try_div(user_stake.stake_amount as u128)? as u64;
+    set_voting_power(staking_pool, user_stake, voting_power)?;
+    user_stake.stake_amount = remaining_amount;
+    Ok(remaining_amount)
+}
+
+// Change a position's voting power, keeping the pool's total in step
+fn set_voting_power(staking_pool: &mut StakingPool, user_stake: &mut UserStake, voting_power: u64) -> Result<()> {
+    staking_pool.total_voting_power = staking_pool
+        .total_voting_power
+        .try_sub(user_stake.voting_power)?
+        .try_add(voting_power)?;
+    user_stake.voting_power = voting_power;
+    Ok(())
+}
+
+// Voting power for a stake: 1 vote per `tokens_per_vote` base units, multiplied by its tier's
+// voting multiplier
+fn voting_power_for(tokens_per_vote: u64, amount: u64, tier: &DurationTier) -> Result<u64> {
+    Ok((amount as u128)
+        .try_mul(tier.voting_multiplier_bps as u128)?
+        .try_div((tokens_per_vote as u128).try_mul(10000)?)? as u64)
+}
+
+// Base units staked per vote in new pools, as in the original fixed formula
+pub const DEFAULT_TOKENS_PER_VOTE: u64 = 1_000_000_000;
+
+// Most tiers a TierConfig can hold
+pub const MAX_DURATION_TIERS: usize = 8;
+
+// 30 days: 1x votes, 10% boost; 90 days: 1.5x, 20%; 180 days: 2x, 30%; 365 days: 3x, 50%
+fn default_duration_tiers() -> Vec<DurationTier> {
+    vec![
+        DurationTier {
+            min_duration: 0,
+            reward_multiplier_bps: 10000,
+            voting_multiplier_bps: 10000,
+            reputation_boost: 10,
+        },
+        DurationTier {
+            min_duration: 90 * 24 * 60 * 60,
+            reward_multiplier_bps: 10000,
+            voting_multiplier_bps: 15000,
+            reputation_boost: 20,
+        },
+        DurationTier {
+            min_duration: 180 * 24 * 60 * 60,
+            reward_multiplier_bps: 10000,
+            voting_multiplier_bps: 20000,
+            reputation_boost: 30,
+        },
+        DurationTier {
+            min_duration: 365 * 24 * 60 * 60,
+            reward_multiplier_bps: 10000,
+            voting_multiplier_bps: 30000,
+            reputation_boost: 50,
+        },
+    ]
+}
+
+// Metaplex token metadata program, which holds position NFT metadata
+pub const TOKEN_METADATA_PROGRAM_ID: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+// Token metadata program's CreateMetadataAccountV3 instruction
+pub const CREATE_METADATA_ACCOUNT_V3_INSTRUCTION: u8 = 33;
+
+// Symbol for position NFTs
+pub const POSITION_NFT_SYMBOL: &str = "WCTPOS";
+
+// Longest URI the token metadata program accepts
+pub const MAX_METADATA_URI_LENGTH: usize = 200;
+
+// Most recent proposals the governance program tracks per voter
+pub const MAX_PARTICIPATION_WINDOW: u8 = 64;
+
+// Highest participation bonus on top of the base reward (100%)
+pub const MAX_PARTICIPATION_BONUS_BPS: u16 = 10000;
+
+// Borsh index of ProposalType::Other in the governance program, the type slash proposals
+// (ProposalPayload::SlashStake) are created with
+pub const OTHER_PROPOSAL_TYPE: u8 = 2;
+
+// Helper function to check, via CPI into the governance program, whether the staker voted in
+// enough recent proposals to earn the participation bonus. Without the governance accounts the
+// staker simply doesn't get the bonus.
+fn has_participation_bonus<'info>(
+    staking_pool: &StakingPool,
+    user: Pubkey,
+    governance_program: Option<&UncheckedAccount<'info>>,
+    governance: Option<&UncheckedAccount<'info>>,
+    voter_record: Option<&UncheckedAccount<'info>>,
+) -> Result<bool> {
+    if staking_pool.participation_bonus_bps == 0 {
+        return Ok(false);
+    }
+    let (governance_program, governance, voter_record) = match (governance_program, governance, voter_record) {
+        (Some(program), Some(governance), Some(voter_record)) => (program, governance, voter_record),
+        _ => return Ok(false),
+    };
+    
+    require!(
+        governance_program.key() == staking_pool.governance_program
+            && governance.key() == staking_pool.governance,
+        StakingError::InvalidGovernanceAccounts
+    );
+    
+    // The voter record must be the staker's own
+    let (expected_voter_record, _) = Pubkey::find_program_address(
+        &[b"voter_record".as_ref(), governance.key().as_ref(), user.as_ref()],
+        &staking_pool.governance_program,
+    );
+    require!(
+        voter_record.key() == expected_voter_record,
+        StakingError::InvalidGovernanceAccounts
+    );
+    
+    // get_participation(window) returns the voter's recent vote count
+    let mut data = hash(b"global:get_participation").to_bytes()[..8].to_vec();
+    data.push(staking_pool.participation_window);
+    
+    let ix = Instruction {
+        program_id: governance_program.key(),
+        accounts: vec![
+            AccountMeta::new_readonly(governance.key(), false),
+            AccountMeta::new_readonly(voter_record.key(), false),
+        ],
+        data,
+    };
+    invoke(&ix, &[governance.to_account_info(), voter_record.to_account_info()])?;
+    
+    let (program_id, return_data) = get_return_data().ok_or(StakingError::InvalidGovernanceAccounts)?;
+    require!(
+        program_id == staking_pool.governance_program && return_data.len() == 1,
+        StakingError::InvalidGovernanceAccounts
+    );
+    
+    Ok(return_data[0] >= staking_pool.participation_min_votes)
+}
+
+#[derive(Accounts)]
+#[instruction(pool_id: u64)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + StakingPool::LEN,
+        seeds = [b"staking_pool".as_ref(), token_mint.key().as_ref(), &pool_id.to_le_bytes()],
+        bump
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + TierConfig::LEN,
+        seeds = [b"tier_config".as_ref(), staking_pool.key().as_ref()],
+        bump
+    )]
+    pub tier_config: Account<'info, TierConfig>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub token_mint: Account<'info, Mint>,
+    
+    // Token rewards are paid in; the same as token_mint except for e.g. LP token pools
+    pub reward_mint: Account<'info, Mint>,
+    
+    // Receives slashed stake. A PDA rather than the pool's ATA, which is the staking vault.
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"treasury".as_ref(), staking_pool.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = staking_pool,
+    )]
+    pub treasury_token_account: Account<'info, TokenAccount>,
+    
+    // Holds staked principal
+    #[account(
+        init,
+        payer = authority,
         associated_token::mint = token_mint,
         associated_token::authority = staking_pool,
     )]
     pub staking_vault: Account<'info, TokenAccount>,
     
-    pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    pub rent: Sysvar<'info, Rent>,
+    // Holds funded rewards, kept apart from staked principal and the treasury
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"rewards_vault".as_ref(), staking_pool.key().as_ref()],
+        bump,
+        token::mint = reward_mint,
+        token::authority = staking_pool,
+    )]
+    pub rewards_vault: Account<'info, TokenAccount>,
+    
+    // stWCT receipt mint, only mintable by the pool
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"receipt_mint".as_ref(), staking_pool.key().as_ref()],
+        bump,
+        mint::decimals = token_mint.decimals,
+        mint::authority = staking_pool,
+    )]
+    pub receipt_mint: Account<'info, Mint>,
+    
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct Stake<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref(), &staking_pool.pool_id.to_le_bytes()],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        seeds = [b"tier_config".as_ref(), staking_pool.key().as_ref()],
+        bump = tier_config.bump,
+    )]
+    pub tier_config: Account<'info, TierConfig>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + StakerInfo::LEN,
+        seeds = [b"staker_info".as_ref(), user.key().as_ref(), staking_pool.key().as_ref()],
+        bump,
+    )]
+    pub staker_info: Account<'info, StakerInfo>,
+    
+    #[account(
+        init,
+        payer = user,
+        space = 8 + UserStake::LEN,
+        seeds = [
+            b"user_stake".as_ref(),
+            user.key().as_ref(),
+            staking_pool.key().as_ref(),
+            &staker_info.position_count.to_le_bytes()
+        ],
+        bump,
+    )]
+    pub user_stake: Account<'info, UserStake>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserStakeRegistry::LEN,
+        seeds = [b"stake_registry".as_ref(), user.key().as_ref()],
+        bump,
+    )]
+    pub stake_registry: Account<'info, UserStakeRegistry>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = user_token_account.mint == staking_pool.token_mint,
+        constraint = user_token_account.owner == user.key(),
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = staking_vault.key() == staking_pool.staking_vault,
+    )]
+    pub staking_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = receipt_mint.key() == staking_pool.receipt_mint,
+    )]
+    pub receipt_mint: Account<'info, Mint>,
+    
+    #[account(
+        mut,
+        constraint = user_receipt_account.mint == staking_pool.receipt_mint,
+        constraint = user_receipt_account.owner == user.key(),
+    )]
+    pub user_receipt_account: Account<'info, TokenAccount>,
+    
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+    
+    /// CHECK: The caller's compliance entry PDA, which may not exist yet; required while the
+    /// pool's compliance list applies to this instruction
+    #[account(
+        seeds = [b"compliance_entry".as_ref(), staking_pool.key().as_ref(), user.key().as_ref()],
+        bump,
+    )]
+    pub compliance_entry: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct StakeLadder<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref(), &staking_pool.pool_id.to_le_bytes()],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        seeds = [b"tier_config".as_ref(), staking_pool.key().as_ref()],
+        bump = tier_config.bump,
+    )]
+    pub tier_config: Account<'info, TierConfig>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + StakerInfo::LEN,
+        seeds = [b"staker_info".as_ref(), user.key().as_ref(), staking_pool.key().as_ref()],
+        bump,
+    )]
+    pub staker_info: Account<'info, StakerInfo>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserStakeRegistry::LEN,
+        seeds = [b"stake_registry".as_ref(), user.key().as_ref()],
+        bump,
+    )]
+    pub stake_registry: Account<'info, UserStakeRegistry>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = user_token_account.mint == staking_pool.token_mint,
+        constraint = user_token_account.owner == user.key(),
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = staking_vault.key() == staking_pool.staking_vault,
+    )]
+    pub staking_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = receipt_mint.key() == staking_pool.receipt_mint,
+    )]
+    pub receipt_mint: Account<'info, Mint>,
+    
+    #[account(
+        mut,
+        constraint = user_receipt_account.mint == staking_pool.receipt_mint,
+        constraint = user_receipt_account.owner == user.key(),
+    )]
+    pub user_receipt_account: Account<'info, TokenAccount>,
+    
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+    
+    /// CHECK: The caller's compliance entry PDA, which may not exist yet; required while the
+    /// pool's compliance list applies to this instruction
+    #[account(
+        seeds = [b"compliance_entry".as_ref(), staking_pool.key().as_ref(), user.key().as_ref()],
+        bump,
+    )]
+    pub compliance_entry: Option<UncheckedAccount<'info>>,
+    
+    // remaining_accounts: the new positions' UserStake PDAs, writable, in position index order
+}
+
+#[derive(Accounts)]
+pub struct ClaimReward<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref(), &staking_pool.pool_id.to_le_bytes()],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"user_stake".as_ref(),
+            user_stake.creator.as_ref(),
+            staking_pool.key().as_ref(),
+            &user_stake.position_index.to_le_bytes()
+        ],
+        bump = user_stake.bump,
+        constraint = user_stake.owner == user.key() || session_key.is_some(),
+    )]
+    pub user_stake: Account<'info, UserStake>,
+    
+    // The position owner, or a session key the owner authorized
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    // The owner's staker info, for the loyalty bonus
+    #[account(
+        seeds = [b"staker_info".as_ref(), user_stake.owner.as_ref(), staking_pool.key().as_ref()],
+        bump = staker_info.bump,
+    )]
+    pub staker_info: Option<Account<'info, StakerInfo>>,
+    
+    // Required when `user` is a session key rather than the owner
+    #[account(
+        seeds = [b"session_key".as_ref(), user_stake.owner.as_ref(), user.key().as_ref()],
+        bump = session_key.bump,
+    )]
+    pub session_key: Option<Account<'info, SessionKey>>,
+    
+    // Any reward-mint account the caller chooses to be paid into
+    #[account(
+        mut,
+        constraint = user_token_account.mint == staking_pool.reward_mint,
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = rewards_vault.key() == staking_pool.rewards_vault,
+    )]
+    pub rewards_vault: Account<'info, TokenAccount>,
+    
+    // Required when the pool mints rewards
+    #[account(
+        mut,
+        constraint = reward_mint.key() == staking_pool.reward_mint,
+    )]
+    pub reward_mint: Option<Account<'info, Mint>>,
+    
+    // Required while the pool charges a performance fee
+    #[account(
+        mut,
+        constraint = fee_vault.key() == staking_pool.fee_vault,
+    )]
+    pub fee_vault: Option<Account<'info, TokenAccount>>,
+    
+    // Required while the pool vests rewards
+    #[account(
+        mut,
+        constraint = vesting_vault.key() == staking_pool.vesting_vault,
+    )]
+    pub vesting_vault: Option<Account<'info, TokenAccount>>,
+    
+    // The caller's vesting schedule, required while the pool vests rewards
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + RewardVesting::LEN,
+        seeds = [b"reward_vesting".as_ref(), staking_pool.key().as_ref(), user_stake.owner.as_ref()],
+        bump,
+    )]
+    pub reward_vesting: Option<Account<'info, RewardVesting>>,
+    
+    pub system_program: Program<'info, System>,
+    
+    pub token_program: Program<'info, Token>,
+    
+    /// CHECK: Governance program, verified against the pool's configuration
+    pub governance_program: Option<UncheckedAccount<'info>>,
+    
+    /// CHECK: Governance account, verified against the pool's configuration
+    pub governance: Option<UncheckedAccount<'info>>,
+    
+    /// CHECK: The user's voter record, validated by the governance program
+    pub voter_record: Option<UncheckedAccount<'info>>,
+    
+    #[account(
+        mut,
+        constraint = secondary_rewards_vault.key() == staking_pool.secondary_rewards_vault,
+    )]
+    pub secondary_rewards_vault: Option<Account<'info, TokenAccount>>,
+    
+    // Any secondary-reward-mint account the caller chooses to be paid into
+    #[account(
+        mut,
+        constraint = user_secondary_token_account.mint == staking_pool.secondary_reward_mint,
+    )]
+    pub user_secondary_token_account: Option<Account<'info, TokenAccount>>,
+    
+    // Required while the position is tokenized
+    pub position_token_account: Option<Account<'info, TokenAccount>>,
+    
+    // The boosting NFT; without it a boosted position loses its boost
+    pub boost_nft_account: Option<Account<'info, TokenAccount>>,
+    
+    /// CHECK: The position owner's compliance entry PDA, which may not exist yet; required while
+    /// the pool's compliance list applies to this instruction. Session-key claims are checked
+    /// against the owner, not the session key.
+    #[account(
+        seeds = [b"compliance_entry".as_ref(), staking_pool.key().as_ref(), user_stake.owner.as_ref()],
+        bump,
+    )]
+    pub compliance_entry: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimAll<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref(), &staking_pool.pool_id.to_le_bytes()],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    // The caller's staker info, for the loyalty bonus
+    #[account(
+        seeds = [b"staker_info".as_ref(), user.key().as_ref(), staking_pool.key().as_ref()],
+        bump = staker_info.bump,
+    )]
+    pub staker_info: Option<Account<'info, StakerInfo>>,
+    
+    #[account(
+        mut,
+        constraint = user_token_account.mint == staking_pool.reward_mint,
+        constraint = user_token_account.owner == user.key(),
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = rewards_vault.key() == staking_pool.rewards_vault,
+    )]
+    pub rewards_vault: Account<'info, TokenAccount>,
+    
+    // Required when the pool mints rewards
+    #[account(
+        mut,
+        constraint = reward_mint.key() == staking_pool.reward_mint,
+    )]
+    pub reward_mint: Option<Account<'info, Mint>>,
+    
+    // Required while the pool charges a performance fee
+    #[account(
+        mut,
+        constraint = fee_vault.key() == staking_pool.fee_vault,
+    )]
+    pub fee_vault: Option<Account<'info, TokenAccount>>,
+    
+    // Required while the pool vests rewards
+    #[account(
+        mut,
+        constraint = vesting_vault.key() == staking_pool.vesting_vault,
+    )]
+    pub vesting_vault: Option<Account<'info, TokenAccount>>,
+    
+    // The caller's vesting schedule, required while the pool vests rewards
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + RewardVesting::LEN,
+        seeds = [b"reward_vesting".as_ref(), staking_pool.key().as_ref(), user.key().as_ref()],
+        bump,
+    )]
+    pub reward_vesting: Option<Account<'info, RewardVesting>>,
+    
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    
+    /// CHECK: Governance program, verified against the pool's configuration
+    pub governance_program: Option<UncheckedAccount<'info>>,
+    
+    /// CHECK: Governance account, verified against the pool's configuration
+    pub governance: Option<UncheckedAccount<'info>>,
+    
+    /// CHECK: The user's voter record, validated by the governance program
+    pub voter_record: Option<UncheckedAccount<'info>>,
+    
+    /// CHECK: The caller's compliance entry PDA, which may not exist yet; required while the
+    /// pool's compliance list applies to this instruction
+    #[account(
+        seeds = [b"compliance_entry".as_ref(), staking_pool.key().as_ref(), user.key().as_ref()],
+        bump,
+    )]
+    pub compliance_entry: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct Compound<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref(), &staking_pool.pool_id.to_le_bytes()],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"user_stake".as_ref(),
+            user_stake.creator.as_ref(),
+            staking_pool.key().as_ref(),
+            &user_stake.position_index.to_le_bytes()
+        ],
+        bump = user_stake.bump,
+        constraint = user_stake.owner == owner.key(),
+    )]
+    pub user_stake: Account<'info, UserStake>,
+    
+    /// CHECK: Owner of the position, checked against user_stake
+    pub owner: UncheckedAccount<'info>,
+    
+    // The owner or a keeper
+    pub caller: Signer<'info>,
+    
+    // The owner's staker info, for the loyalty bonus
+    #[account(
+        seeds = [b"staker_info".as_ref(), owner.key().as_ref(), staking_pool.key().as_ref()],
+        bump = staker_info.bump,
+    )]
+    pub staker_info: Option<Account<'info, StakerInfo>>,
+    
+    #[account(
+        mut,
+        constraint = rewards_vault.key() == staking_pool.rewards_vault,
+    )]
+    pub rewards_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = staking_vault.key() == staking_pool.staking_vault,
+    )]
+    pub staking_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = receipt_mint.key() == staking_pool.receipt_mint,
+    )]
+    pub receipt_mint: Account<'info, Mint>,
+    
+    #[account(
+        mut,
+        constraint = owner_receipt_account.mint == staking_pool.receipt_mint,
+        constraint = owner_receipt_account.owner == owner.key(),
+    )]
+    pub owner_receipt_account: Account<'info, TokenAccount>,
+    
+    // Required when the pool mints rewards
+    #[account(
+        mut,
+        constraint = reward_mint.key() == staking_pool.reward_mint,
+    )]
+    pub reward_mint: Option<Account<'info, Mint>>,
+    
+    // Required while the pool charges a performance fee
+    #[account(
+        mut,
+        constraint = fee_vault.key() == staking_pool.fee_vault,
+    )]
+    pub fee_vault: Option<Account<'info, TokenAccount>>,
+    
+    pub token_program: Program<'info, Token>,
+    
+    /// CHECK: Governance program, verified against the pool's configuration
+    pub governance_program: Option<UncheckedAccount<'info>>,
+    
+    /// CHECK: Governance account, verified against the pool's configuration
+    pub governance: Option<UncheckedAccount<'info>>,
+    
+    /// CHECK: The owner's voter record, validated by the governance program
+    pub voter_record: Option<UncheckedAccount<'info>>,
+    
+    // The boosting NFT; without it a boosted position loses its boost
+    pub boost_nft_account: Option<Account<'info, TokenAccount>>,
+    
+    /// CHECK: The owner's compliance entry PDA, which may not exist yet; required while the
+    /// pool's compliance list applies to this instruction
+    #[account(
+        seeds = [b"compliance_entry".as_ref(), staking_pool.key().as_ref(), owner.key().as_ref()],
+        bump,
+    )]
+    pub compliance_entry: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimAndLock<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref(), &staking_pool.pool_id.to_le_bytes()],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        seeds = [b"tier_config".as_ref(), staking_pool.key().as_ref()],
+        bump = tier_config.bump,
+    )]
+    pub tier_config: Account<'info, TierConfig>,
+    
+    // Position whose rewards are claimed
+    #[account(
+        mut,
+        seeds = [
+            b"user_stake".as_ref(),
+            user_stake.creator.as_ref(),
+            staking_pool.key().as_ref(),
+            &user_stake.position_index.to_le_bytes()
+        ],
+        bump = user_stake.bump,
+        constraint = user_stake.owner == user.key(),
+    )]
+    pub user_stake: Account<'info, UserStake>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + StakerInfo::LEN,
+        seeds = [b"staker_info".as_ref(), user.key().as_ref(), staking_pool.key().as_ref()],
+        bump,
+    )]
+    pub staker_info: Account<'info, StakerInfo>,
+    
+    #[account(
+        init,
+        payer = user,
+        space = 8 + UserStake::LEN,
+        seeds = [
+            b"user_stake".as_ref(),
+            user.key().as_ref(),
+            staking_pool.key().as_ref(),
+            &staker_info.position_count.to_le_bytes()
+        ],
+        bump,
+    )]
+    pub new_user_stake: Account<'info, UserStake>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserStakeRegistry::LEN,
+        seeds = [b"stake_registry".as_ref(), user.key().as_ref()],
+        bump,
+    )]
+    pub stake_registry: Account<'info, UserStakeRegistry>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = staking_vault.key() == staking_pool.staking_vault,
+    )]
+    pub staking_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = rewards_vault.key() == staking_pool.rewards_vault,
+    )]
+    pub rewards_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = receipt_mint.key() == staking_pool.receipt_mint,
+    )]
+    pub receipt_mint: Account<'info, Mint>,
+    
+    #[account(
+        mut,
+        constraint = user_receipt_account.mint == staking_pool.receipt_mint,
+        constraint = user_receipt_account.owner == user.key(),
+    )]
+    pub user_receipt_account: Account<'info, TokenAccount>,
+    
+    // Required when the pool mints rewards
+    #[account(
+        mut,
+        constraint = reward_mint.key() == staking_pool.reward_mint,
+    )]
+    pub reward_mint: Option<Account<'info, Mint>>,
+    
+    // Required while the pool charges a performance fee
+    #[account(
+        mut,
+        constraint = fee_vault.key() == staking_pool.fee_vault,
+    )]
+    pub fee_vault: Option<Account<'info, TokenAccount>>,
+    
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    
+    /// CHECK: Governance program, verified against the pool's configuration
+    pub governance_program: Option<UncheckedAccount<'info>>,
+    
+    /// CHECK: Governance account, verified against the pool's configuration
+    pub governance: Option<UncheckedAccount<'info>>,
+    
+    /// CHECK: The user's voter record, validated by the governance program
+    pub voter_record: Option<UncheckedAccount<'info>>,
+    
+    // Required while the position is tokenized
+    pub position_token_account: Option<Account<'info, TokenAccount>>,
+    
+    // The boosting NFT; without it a boosted position loses its boost
+    pub boost_nft_account: Option<Account<'info, TokenAccount>>,
+    
+    /// CHECK: The caller's compliance entry PDA, which may not exist yet; required while the
+    /// pool's compliance list applies to this instruction
+    #[account(
+        seeds = [b"compliance_entry".as_ref(), staking_pool.key().as_ref(), user.key().as_ref()],
+        bump,
+    )]
+    pub compliance_entry: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct Unstake<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref(), &staking_pool.pool_id.to_le_bytes()],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"user_stake".as_ref(),
+            user_stake.creator.as_ref(),
+            staking_pool.key().as_ref(),
+            &user_stake.position_index.to_le_bytes()
+        ],
+        bump = user_stake.bump,
+        constraint = user_stake.owner == user.key(),
+    )]
+    pub user_stake: Account<'info, UserStake>,
+    
+    // Open position count of the wallet that opened the position
+    #[account(
+        mut,
+        seeds = [b"staker_info".as_ref(), user_stake.creator.as_ref(), staking_pool.key().as_ref()],
+        bump = staker_info.bump,
+    )]
+    pub staker_info: Account<'info, StakerInfo>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    // Any staking-mint account the caller chooses to receive the unstaked tokens
+    #[account(
+        mut,
+        constraint = user_token_account.mint == staking_pool.token_mint,
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+    
+    // Receives rewards; any reward-mint account the caller chooses, which may be the same
+    // account as user_token_account when the pool pays rewards in the staking mint
+    #[account(
+        mut,
+        constraint = user_reward_account.mint == staking_pool.reward_mint,
+    )]
+    pub user_reward_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = staking_vault.key() == staking_pool.staking_vault,
+    )]
+    pub staking_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = receipt_mint.key() == staking_pool.receipt_mint,
+    )]
+    pub receipt_mint: Account<'info, Mint>,
+    
+    #[account(
+        mut,
+        constraint = user_receipt_account.mint == staking_pool.receipt_mint,
+        constraint = user_receipt_account.owner == user.key(),
+    )]
+    pub user_receipt_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = rewards_vault.key() == staking_pool.rewards_vault,
+    )]
+    pub rewards_vault: Account<'info, TokenAccount>,
+    
+    // Required when the pool mints rewards
+    #[account(
+        mut,
+        constraint = reward_mint.key() == staking_pool.reward_mint,
+    )]
+    pub reward_mint: Option<Account<'info, Mint>>,
+    
+    // Required while the pool charges a performance fee
+    #[account(
+        mut,
+        constraint = fee_vault.key() == staking_pool.fee_vault,
+    )]
+    pub fee_vault: Option<Account<'info, TokenAccount>>,
+    
+    // Required while the pool vests rewards
+    #[account(
+        mut,
+        constraint = vesting_vault.key() == staking_pool.vesting_vault,
+    )]
+    pub vesting_vault: Option<Account<'info, TokenAccount>>,
+    
+    // The caller's vesting schedule, required while the pool vests rewards
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + RewardVesting::LEN,
+        seeds = [b"reward_vesting".as_ref(), staking_pool.key().as_ref(), user.key().as_ref()],
+        bump,
+    )]
+    pub reward_vesting: Option<Account<'info, RewardVesting>>,
+    
+    pub system_program: Program<'info, System>,
+    
+    pub token_program: Program<'info, Token>,
+    
+    // Required while the position is tokenized
+    pub position_token_account: Option<Account<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref(), &staking_pool.pool_id.to_le_bytes()],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"user_stake".as_ref(),
+            user_stake.creator.as_ref(),
+            staking_pool.key().as_ref(),
+            &user_stake.position_index.to_le_bytes()
+        ],
+        bump = user_stake.bump,
+        constraint = user_stake.owner == user.key(),
+    )]
+    pub user_stake: Account<'info, UserStake>,
+    
+    // Open position count of the wallet that opened the position
+    #[account(
+        mut,
+        seeds = [b"staker_info".as_ref(), user_stake.creator.as_ref(), staking_pool.key().as_ref()],
+        bump = staker_info.bump,
+    )]
+    pub staker_info: Account<'info, StakerInfo>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = user_token_account.mint == staking_pool.token_mint,
+        constraint = user_token_account.owner == user.key(),
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = staking_vault.key() == staking_pool.staking_vault,
+    )]
+    pub staking_vault: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+    
+    // Required while the position is tokenized
+    pub position_token_account: Option<Account<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct CloseStakeAccount<'info> {
+    #[account(
+        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref(), &staking_pool.pool_id.to_le_bytes()],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"user_stake".as_ref(),
+            user_stake.creator.as_ref(),
+            staking_pool.key().as_ref(),
+            &user_stake.position_index.to_le_bytes()
+        ],
+        bump = user_stake.bump,
+        constraint = user_stake.owner == user.key(),
+        close = user,
+    )]
+    pub user_stake: Account<'info, UserStake>,
+    
+    // Registry of the wallet that opened the position
+    #[account(
+        mut,
+        seeds = [b"stake_registry".as_ref(), user_stake.creator.as_ref()],
+        bump = stake_registry.bump,
+    )]
+    pub stake_registry: Account<'info, UserStakeRegistry>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    // Required while the position is tokenized
+    pub position_token_account: Option<Account<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct TokenizePosition<'info> {
+    #[account(
+        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref(), &staking_pool.pool_id.to_le_bytes()],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"user_stake".as_ref(),
+            user_stake.creator.as_ref(),
+            staking_pool.key().as_ref(),
+            &user_stake.position_index.to_le_bytes()
+        ],
+        bump = user_stake.bump,
+        constraint = user_stake.owner == user.key(),
+    )]
+    pub user_stake: Account<'info, UserStake>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    #[account(
+        init,
+        payer = user,
+        seeds = [b"position_mint".as_ref(), user_stake.key().as_ref()],
+        bump,
+        mint::decimals = 0,
+        mint::authority = staking_pool,
+    )]
+    pub position_mint: Account<'info, Mint>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = position_mint,
+        associated_token::authority = user,
+    )]
+    pub user_position_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: Metadata PDA for the position mint, verified in the handler and created by the
+    /// token metadata program
+    #[account(mut)]
+    pub metadata: UncheckedAccount<'info>,
+    
+    /// CHECK: Token metadata program
+    #[account(address = TOKEN_METADATA_PROGRAM_ID)]
+    pub token_metadata_program: UncheckedAccount<'info>,
+    
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct SyncPositionOwner<'info> {
+    #[account(
+        mut,
+        constraint = user_stake.position_mint != Pubkey::default() @ StakingError::PositionNotTokenized,
+    )]
+    pub user_stake: Account<'info, UserStake>,
+    
+    // The account currently holding the position NFT
+    #[account(
+        constraint = position_token_account.mint == user_stake.position_mint,
+        constraint = position_token_account.amount == 1,
+    )]
+    pub position_token_account: Account<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct TransferStakeOwnership<'info> {
+    #[account(
+        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref(), &staking_pool.pool_id.to_le_bytes()],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"user_stake".as_ref(),
+            user_stake.creator.as_ref(),
+            staking_pool.key().as_ref(),
+            &user_stake.position_index.to_le_bytes()
+        ],
+        bump = user_stake.bump,
+        constraint = user_stake.owner == user.key(),
+    )]
+    pub user_stake: Account<'info, UserStake>,
+    
+    pub user: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptStakeOwnership<'info> {
+    #[account(
+        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref(), &staking_pool.pool_id.to_le_bytes()],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"user_stake".as_ref(),
+            user_stake.creator.as_ref(),
+            staking_pool.key().as_ref(),
+            &user_stake.position_index.to_le_bytes()
+        ],
+        bump = user_stake.bump,
+    )]
+    pub user_stake: Account<'info, UserStake>,
+    
+    pub new_owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(session_signer: Pubkey)]
+pub struct CreateSessionKey<'info> {
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + SessionKey::LEN,
+        seeds = [b"session_key".as_ref(), owner.key().as_ref(), session_signer.as_ref()],
+        bump,
+    )]
+    pub session_key: Account<'info, SessionKey>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeSessionKey<'info> {
+    #[account(
+        mut,
+        seeds = [b"session_key".as_ref(), owner.key().as_ref(), session_key.session_signer.as_ref()],
+        bump = session_key.bump,
+        close = owner,
+    )]
+    pub session_key: Account<'info, SessionKey>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExtendLock<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref(), &staking_pool.pool_id.to_le_bytes()],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        seeds = [b"tier_config".as_ref(), staking_pool.key().as_ref()],
+        bump = tier_config.bump,
+    )]
+    pub tier_config: Account<'info, TierConfig>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"user_stake".as_ref(),
+            user_stake.creator.as_ref(),
+            staking_pool.key().as_ref(),
+            &user_stake.position_index.to_le_bytes()
+        ],
+        bump = user_stake.bump,
+        constraint = user_stake.owner == user.key(),
+    )]
+    pub user_stake: Account<'info, UserStake>,
+    
+    pub user: Signer<'info>,
+    
+    // Required while the position is tokenized
+    pub position_token_account: Option<Account<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterBoostCollection<'info> {
+    #[account(
+        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref(), &staking_pool.pool_id.to_le_bytes()],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + BoostCollection::LEN,
+        seeds = [b"boost_collection".as_ref(), staking_pool.key().as_ref(), collection_mint.key().as_ref()],
+        bump,
+    )]
+    pub boost_collection: Account<'info, BoostCollection>,
+    
+    // Mint of the collection NFT
+    pub collection_mint: Account<'info, Mint>,
+    
+    #[account(
+        mut,
+        constraint = authority.key() == staking_pool.authority,
+    )]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApplyNftBoost<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref(), &staking_pool.pool_id.to_le_bytes()],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"user_stake".as_ref(),
+            user_stake.creator.as_ref(),
+            staking_pool.key().as_ref(),
+            &user_stake.position_index.to_le_bytes()
+        ],
+        bump = user_stake.bump,
+        constraint = user_stake.owner == user.key(),
+    )]
+    pub user_stake: Account<'info, UserStake>,
+    
+    pub user: Signer<'info>,
+    
+    #[account(
+        seeds = [
+            b"boost_collection".as_ref(),
+            staking_pool.key().as_ref(),
+            boost_collection.collection_mint.as_ref()
+        ],
+        bump = boost_collection.bump,
+    )]
+    pub boost_collection: Account<'info, BoostCollection>,
+    
+    #[account(
+        constraint = nft_token_account.owner == user.key(),
+        constraint = nft_token_account.amount == 1,
+    )]
+    pub nft_token_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: Metadata PDA for the NFT's mint, verified and parsed in the handler
+    pub nft_metadata: UncheckedAccount<'info>,
+    
+    // Required while the position is tokenized
+    pub position_token_account: Option<Account<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct SplitStake<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref(), &staking_pool.pool_id.to_le_bytes()],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"user_stake".as_ref(),
+            user_stake.creator.as_ref(),
+            staking_pool.key().as_ref(),
+            &user_stake.position_index.to_le_bytes()
+        ],
+        bump = user_stake.bump,
+        constraint = user_stake.owner == user.key(),
+    )]
+    pub user_stake: Account<'info, UserStake>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + StakerInfo::LEN,
+        seeds = [b"staker_info".as_ref(), user.key().as_ref(), staking_pool.key().as_ref()],
+        bump,
+    )]
+    pub staker_info: Account<'info, StakerInfo>,
+    
+    #[account(
+        init,
+        payer = user,
+        space = 8 + UserStake::LEN,
+        seeds = [
+            b"user_stake".as_ref(),
+            user.key().as_ref(),
+            staking_pool.key().as_ref(),
+            &staker_info.position_count.to_le_bytes()
+        ],
+        bump,
+    )]
+    pub new_user_stake: Account<'info, UserStake>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserStakeRegistry::LEN,
+        seeds = [b"stake_registry".as_ref(), user.key().as_ref()],
+        bump,
+    )]
+    pub stake_registry: Account<'info, UserStakeRegistry>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    // Required while the position is tokenized
+    pub position_token_account: Option<Account<'info, TokenAccount>>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateStake<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref(), &staking_pool.pool_id.to_le_bytes()],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"staking_pool".as_ref(),
+            new_staking_pool.token_mint.as_ref(),
+            &new_staking_pool.pool_id.to_le_bytes()
+        ],
+        bump = new_staking_pool.bump,
+        constraint = staking_pool.migration_target != Pubkey::default()
+            && new_staking_pool.key() == staking_pool.migration_target @ StakingError::InvalidMigrationTarget,
+    )]
+    pub new_staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        seeds = [b"tier_config".as_ref(), new_staking_pool.key().as_ref()],
+        bump = new_tier_config.bump,
+    )]
+    pub new_tier_config: Account<'info, TierConfig>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"user_stake".as_ref(),
+            user_stake.creator.as_ref(),
+            staking_pool.key().as_ref(),
+            &user_stake.position_index.to_le_bytes()
+        ],
+        bump = user_stake.bump,
+        constraint = user_stake.owner == user.key(),
+    )]
+    pub user_stake: Account<'info, UserStake>,
+    
+    // Open position count of the wallet that opened the position
+    #[account(
+        mut,
+        seeds = [b"staker_info".as_ref(), user_stake.creator.as_ref(), staking_pool.key().as_ref()],
+        bump = old_staker_info.bump,
+    )]
+    pub old_staker_info: Account<'info, StakerInfo>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + StakerInfo::LEN,
+        seeds = [b"staker_info".as_ref(), user.key().as_ref(), new_staking_pool.key().as_ref()],
+        bump,
+    )]
+    pub staker_info: Account<'info, StakerInfo>,
+    
+    #[account(
+        init,
+        payer = user,
+        space = 8 + UserStake::LEN,
+        seeds = [
+            b"user_stake".as_ref(),
+            user.key().as_ref(),
+            new_staking_pool.key().as_ref(),
+            &staker_info.position_count.to_le_bytes()
+        ],
+        bump,
+    )]
+    pub new_user_stake: Account<'info, UserStake>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserStakeRegistry::LEN,
+        seeds = [b"stake_registry".as_ref(), user.key().as_ref()],
+        bump,
+    )]
+    pub stake_registry: Account<'info, UserStakeRegistry>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = staking_vault.key() == staking_pool.staking_vault,
+    )]
+    pub staking_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = new_staking_vault.key() == new_staking_pool.staking_vault,
+    )]
+    pub new_staking_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = rewards_vault.key() == staking_pool.rewards_vault,
+    )]
+    pub rewards_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = new_rewards_vault.key() == new_staking_pool.rewards_vault,
+    )]
+    pub new_rewards_vault: Account<'info, TokenAccount>,
+    
+    // Required when the old pool mints rewards
+    #[account(
+        mut,
+        constraint = reward_mint.key() == staking_pool.reward_mint,
+    )]
+    pub reward_mint: Option<Account<'info, Mint>>,
+    
+    #[account(
+        mut,
+        constraint = receipt_mint.key() == staking_pool.receipt_mint,
+    )]
+    pub receipt_mint: Account<'info, Mint>,
+    
+    #[account(
+        mut,
+        constraint = user_receipt_account.mint == staking_pool.receipt_mint,
+        constraint = user_receipt_account.owner == user.key(),
+    )]
+    pub user_receipt_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = new_receipt_mint.key() == new_staking_pool.receipt_mint,
+    )]
+    pub new_receipt_mint: Account<'info, Mint>,
+    
+    #[account(
+        mut,
+        constraint = user_new_receipt_account.mint == new_staking_pool.receipt_mint,
+        constraint = user_new_receipt_account.owner == user.key(),
+    )]
+    pub user_new_receipt_account: Account<'info, TokenAccount>,
+    
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    
+    /// CHECK: The caller's compliance entry PDA in the new pool, which may not exist yet; required while the
+    /// pool's compliance list applies to this instruction
+    #[account(
+        seeds = [b"compliance_entry".as_ref(), new_staking_pool.key().as_ref(), user.key().as_ref()],
+        bump,
+    )]
+    pub compliance_entry: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct SetComplianceStatus<'info> {
+    #[account(
+        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref(), &staking_pool.pool_id.to_le_bytes()],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        init_if_needed,
+        payer = compliance_authority,
+        space = 8 + ComplianceEntry::LEN,
+        seeds = [b"compliance_entry".as_ref(), staking_pool.key().as_ref(), wallet.as_ref()],
+        bump,
+    )]
+    pub compliance_entry: Account<'info, ComplianceEntry>,
+    
+    #[account(
+        mut,
+        constraint = staking_pool.compliance_authority != Pubkey::default()
+            && compliance_authority.key() == staking_pool.compliance_authority,
+    )]
+    pub compliance_authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetAutoRelock<'info> {
+    #[account(
+        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref(), &staking_pool.pool_id.to_le_bytes()],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"user_stake".as_ref(),
+            user_stake.creator.as_ref(),
+            staking_pool.key().as_ref(),
+            &user_stake.position_index.to_le_bytes()
+        ],
+        bump = user_stake.bump,
+        constraint = user_stake.owner == user.key(),
+    )]
+    pub user_stake: Account<'info, UserStake>,
+    
+    pub user: Signer<'info>,
+    
+    // Required while the position is tokenized
+    pub position_token_account: Option<Account<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct Relock<'info> {
+    #[account(
+        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref(), &staking_pool.pool_id.to_le_bytes()],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"user_stake".as_ref(),
+            user_stake.creator.as_ref(),
+            staking_pool.key().as_ref(),
+            &user_stake.position_index.to_le_bytes()
+        ],
+        bump = user_stake.bump,
+    )]
+    pub user_stake: Account<'info, UserStake>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeExpiredPosition<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref(), &staking_pool.pool_id.to_le_bytes()],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"user_stake".as_ref(),
+            user_stake.creator.as_ref(),
+            staking_pool.key().as_ref(),
+            &user_stake.position_index.to_le_bytes()
+        ],
+        bump = user_stake.bump,
+    )]
+    pub user_stake: Account<'info, UserStake>,
+    
+    // Open position count of the wallet that opened the position
+    #[account(
+        mut,
+        seeds = [b"staker_info".as_ref(), user_stake.creator.as_ref(), staking_pool.key().as_ref()],
+        bump = staker_info.bump,
+    )]
+    pub staker_info: Account<'info, StakerInfo>,
+    
+    /// CHECK: The position's owner; only used to locate their token accounts
+    #[account(constraint = owner.key() == user_stake.owner)]
+    pub owner: UncheckedAccount<'info>,
+    
+    // Anyone may crank; pays for the owner's vesting schedule if one has to be created
+    #[account(mut)]
+    pub caller: Signer<'info>,
+    
+    // The owner's associated staking-mint account receives the principal
+    #[account(
+        mut,
+        associated_token::mint = staking_pool.token_mint,
+        associated_token::authority = owner,
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+    
+    // Receives rewards; checked against the position's reward destination
+    #[account(
+        mut,
+        constraint = owner_reward_account.mint == staking_pool.reward_mint,
+    )]
+    pub owner_reward_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = staking_vault.key() == staking_pool.staking_vault,
+    )]
+    pub staking_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = receipt_mint.key() == staking_pool.receipt_mint,
+    )]
+    pub receipt_mint: Account<'info, Mint>,
+    
+    // The owner's receipts, delegated to the pool
+    #[account(
+        mut,
+        constraint = owner_receipt_account.mint == staking_pool.receipt_mint,
+        constraint = owner_receipt_account.owner == owner.key(),
+    )]
+    pub owner_receipt_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = rewards_vault.key() == staking_pool.rewards_vault,
+    )]
+    pub rewards_vault: Account<'info, TokenAccount>,
+    
+    // Required when the pool mints rewards
+    #[account(
+        mut,
+        constraint = reward_mint.key() == staking_pool.reward_mint,
+    )]
+    pub reward_mint: Option<Account<'info, Mint>>,
+    
+    // Required while the pool charges a performance fee
+    #[account(
+        mut,
+        constraint = fee_vault.key() == staking_pool.fee_vault,
+    )]
+    pub fee_vault: Option<Account<'info, TokenAccount>>,
+    
+    // Required while the pool vests rewards
+    #[account(
+        mut,
+        constraint = vesting_vault.key() == staking_pool.vesting_vault,
+    )]
+    pub vesting_vault: Option<Account<'info, TokenAccount>>,
+    
+    // The owner's vesting schedule, required while the pool vests rewards
+    #[account(
+        init_if_needed,
+        payer = caller,
+        space = 8 + RewardVesting::LEN,
+        seeds = [b"reward_vesting".as_ref(), staking_pool.key().as_ref(), user_stake.owner.as_ref()],
+        bump,
+    )]
+    pub reward_vesting: Option<Account<'info, RewardVesting>>,
+    
+    pub system_program: Program<'info, System>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct FundRewards<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref(), &staking_pool.pool_id.to_le_bytes()],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    pub funder: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = funder_token_account.mint == staking_pool.reward_mint,
+        constraint = funder_token_account.owner == funder.key(),
+    )]
+    pub funder_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = rewards_vault.key() == staking_pool.rewards_vault,
+    )]
+    pub rewards_vault: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SyncGovernanceVotingPower<'info> {
+    #[account(
+        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref(), &staking_pool.pool_id.to_le_bytes()],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    /// CHECK: Must be the governance program the pool is linked to
+    #[account(
+        constraint = staking_pool.governance_program != Pubkey::default()
+            && governance_program.key() == staking_pool.governance_program @ StakingError::InvalidGovernanceAccounts,
+    )]
+    pub governance_program: UncheckedAccount<'info>,
+    
+    /// CHECK: Must be the governance realm the pool is linked to
+    #[account(
+        mut,
+        constraint = governance.key() == staking_pool.governance @ StakingError::InvalidGovernanceAccounts,
+    )]
+    pub governance: UncheckedAccount<'info>,
+    
+    /// CHECK: The realm's registry for the staked token, validated by the governance program
+    #[account(mut)]
+    pub voting_power_registry: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SyncPositionVotingPower<'info> {
+    #[account(
+        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref(), &staking_pool.pool_id.to_le_bytes()],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"user_stake".as_ref(),
+            user_stake.creator.as_ref(),
+            staking_pool.key().as_ref(),
+            &user_stake.position_index.to_le_bytes()
+        ],
+        bump = user_stake.bump,
+    )]
+    pub user_stake: Account<'info, UserStake>,
+    
+    /// CHECK: Must be the governance program the pool is linked to
+    #[account(
+        constraint = staking_pool.governance_program != Pubkey::default()
+            && governance_program.key() == staking_pool.governance_program @ StakingError::InvalidGovernanceAccounts,
+    )]
+    pub governance_program: UncheckedAccount<'info>,
+    
+    /// CHECK: Must be the governance realm the pool is linked to
+    #[account(
+        mut,
+        constraint = governance.key() == staking_pool.governance @ StakingError::InvalidGovernanceAccounts,
+    )]
+    pub governance: UncheckedAccount<'info>,
+    
+    /// CHECK: The realm's registry for the staked token, validated by the governance program
+    #[account(mut)]
+    pub voting_power_registry: UncheckedAccount<'info>,
+    
+    /// CHECK: The voter's power record in the registry, validated by the governance program
+    #[account(mut)]
+    pub voter_power: UncheckedAccount<'info>,
+    
+    /// CHECK: The registry page holding the voter's entry, validated by the governance program
+    #[account(mut)]
+    pub voting_power_page: UncheckedAccount<'info>,
+    
+    // Pays for the voter's power record if it doesn't exist yet
+    #[account(mut)]
+    pub caller: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimStakerBadge<'info> {
+    #[account(
+        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref(), &staking_pool.pool_id.to_le_bytes()],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    // Positions the wallet has opened and its staking streak
+    #[account(
+        seeds = [b"staker_info".as_ref(), owner.key().as_ref(), staking_pool.key().as_ref()],
+        bump = staker_info.bump,
+    )]
+    pub staker_info: Account<'info, StakerInfo>,
+    
+    // A position the wallet owns, required for BADGE_100K_LOCKED
+    #[account(
+        seeds = [
+            b"user_stake".as_ref(),
+            user_stake.creator.as_ref(),
+            staking_pool.key().as_ref(),
+            &user_stake.position_index.to_le_bytes()
+        ],
+        bump = user_stake.bump,
+        constraint = user_stake.owner == owner.key(),
+    )]
+    pub user_stake: Option<Account<'info, UserStake>>,
+    
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + StakerBadges::LEN,
+        seeds = [b"staker_badges".as_ref(), staking_pool.key().as_ref(), owner.key().as_ref()],
+        bump,
+    )]
+    pub staker_badges: Account<'info, StakerBadges>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    /// CHECK: Must be the badge program the pool is configured with
+    #[account(
+        constraint = staking_pool.badge_program != Pubkey::default()
+            && badge_program.key() == staking_pool.badge_program @ StakingError::BadgesDisabled,
+    )]
+    pub badge_program: UncheckedAccount<'info>,
+    
+    pub system_program: Program<'info, System>,
+    
+    // remaining_accounts: any further accounts the badge program's mint_badge needs
+}
+
+#[derive(Accounts)]
+pub struct UpdatePool<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref(), &staking_pool.pool_id.to_le_bytes()],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    // Refreshed by update_pool when passed
+    #[account(
+        mut,
+        seeds = [b"yield_oracle".as_ref(), staking_pool.key().as_ref()],
+        bump = yield_oracle.bump,
+    )]
+    pub yield_oracle: Option<Account<'info, PoolYieldOracle>>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeYieldOracle<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref(), &staking_pool.pool_id.to_le_bytes()],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + PoolYieldOracle::LEN,
+        seeds = [b"yield_oracle".as_ref(), staking_pool.key().as_ref()],
+        bump,
+    )]
+    pub yield_oracle: Account<'info, PoolYieldOracle>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(epoch: u64)]
+pub struct SnapshotEpoch<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref(), &staking_pool.pool_id.to_le_bytes()],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + EpochSnapshot::LEN,
+        seeds = [b"epoch_snapshot".as_ref(), staking_pool.key().as_ref(), &epoch.to_le_bytes()],
+        bump,
+    )]
+    pub epoch_snapshot: Account<'info, EpochSnapshot>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigureSecondaryRewards<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref(), &staking_pool.pool_id.to_le_bytes()],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    pub secondary_mint: Account<'info, Mint>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        seeds = [b"secondary_rewards_vault".as_ref(), staking_pool.key().as_ref()],
+        bump,
+        token::mint = secondary_mint,
+        token::authority = staking_pool,
+    )]
+    pub secondary_rewards_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = authority.key() == staking_pool.authority,
+    )]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct FundSecondaryRewards<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref(), &staking_pool.pool_id.to_le_bytes()],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    pub funder: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = funder_token_account.mint == staking_pool.secondary_reward_mint,
+        constraint = funder_token_account.owner == funder.key(),
+    )]
+    pub funder_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = secondary_rewards_vault.key() == staking_pool.secondary_rewards_vault,
+    )]
+    pub secondary_rewards_vault: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimSecondaryReward<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref(), &staking_pool.pool_id.to_le_bytes()],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"user_stake".as_ref(),
+            user_stake.creator.as_ref(),
+            staking_pool.key().as_ref(),
+            &user_stake.position_index.to_le_bytes()
+        ],
+        bump = user_stake.bump,
+        constraint = user_stake.owner == user.key(),
+    )]
+    pub user_stake: Account<'info, UserStake>,
+    
+    pub user: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = secondary_rewards_vault.key() == staking_pool.secondary_rewards_vault,
+    )]
+    pub secondary_rewards_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = user_secondary_token_account.mint == staking_pool.secondary_reward_mint,
+        constraint = user_secondary_token_account.owner == user.key()
+            || user_secondary_token_account.owner == user_stake.reward_destination,
+    )]
+    pub user_secondary_token_account: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+    
+    // Required while the position is tokenized
+    pub position_token_account: Option<Account<'info, TokenAccount>>,
+    
+    /// CHECK: The caller's compliance entry PDA, which may not exist yet; required while the
+    /// pool's compliance list applies to this instruction
+    #[account(
+        seeds = [b"compliance_entry".as_ref(), staking_pool.key().as_ref(), user.key().as_ref()],
+        bump,
+    )]
+    pub compliance_entry: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct FreezePosition<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref(), &staking_pool.pool_id.to_le_bytes()],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"user_stake".as_ref(),
+            user_stake.creator.as_ref(),
+            staking_pool.key().as_ref(),
+            &user_stake.position_index.to_le_bytes()
+        ],
+        bump = user_stake.bump,
+    )]
+    pub user_stake: Account<'info, UserStake>,
+    
+    // The governance PDA, signing through an approved proposal's execution item
+    #[account(
+        constraint = staking_pool.governance != Pubkey::default()
+            && governance.key() == staking_pool.governance @ StakingError::InvalidGovernanceAccounts,
+    )]
+    pub governance: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SlashStake<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref(), &staking_pool.pool_id.to_le_bytes()],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"user_stake".as_ref(),
+            user_stake.creator.as_ref(),
+            staking_pool.key().as_ref(),
+            &user_stake.position_index.to_le_bytes()
+        ],
+        bump = user_stake.bump,
+    )]
+    pub user_stake: Account<'info, UserStake>,
+    
+    // Open position count of the wallet that opened the position
+    #[account(
+        mut,
+        seeds = [b"staker_info".as_ref(), user_stake.creator.as_ref(), staking_pool.key().as_ref()],
+        bump = staker_info.bump,
+    )]
+    pub staker_info: Account<'info, StakerInfo>,
+    
+    /// CHECK: Must be the governance program the pool is linked to
+    #[account(
+        executable,
+        constraint = staking_pool.governance_program != Pubkey::default()
+            && governance_program.key() == staking_pool.governance_program @ StakingError::InvalidGovernanceAccounts,
+    )]
+    pub governance_program: UncheckedAccount<'info>,
+    
+    /// CHECK: Must be the governance realm the pool is linked to; writable for the registered
+    /// power update
+    #[account(
+        mut,
+        constraint = staking_pool.governance != Pubkey::default()
+            && governance.key() == staking_pool.governance @ StakingError::InvalidGovernanceAccounts,
+    )]
+    pub governance: UncheckedAccount<'info>,
+    
+    /// CHECK: The approving proposal, validated by the governance program
+    pub proposal: UncheckedAccount<'info>,
+    
+    // Marks the proposal as applied so its slash can't be repeated
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + SlashReceipt::LEN,
+        seeds = [b"slash_receipt".as_ref(), proposal.key().as_ref()],
+        bump,
+    )]
+    pub slash_receipt: Account<'info, SlashReceipt>,
+    
+    #[account(
+        mut,
+        constraint = staking_vault.key() == staking_pool.staking_vault,
+    )]
+    pub staking_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = treasury_token_account.key() == staking_pool.treasury_token_account,
+    )]
+    pub treasury_token_account: Account<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct SetPerformanceFee<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref(), &staking_pool.pool_id.to_le_bytes()],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    // The governance PDA, signing through an approved proposal's execution item
+    #[account(
+        constraint = staking_pool.governance != Pubkey::default()
+            && governance.key() == staking_pool.governance @ StakingError::InvalidGovernanceAccounts,
+    )]
+    pub governance: Signer<'info>,
+    
+    // Governance-owned account that collects the fee, in the reward mint
+    #[account(
+        constraint = fee_vault.mint == staking_pool.reward_mint,
+        constraint = fee_vault.owner == governance.key(),
+    )]
+    pub fee_vault: Account<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct SetIncidentExit<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref(), &staking_pool.pool_id.to_le_bytes()],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    // The governance PDA, signing through an approved proposal's execution item
+    #[account(
+        constraint = staking_pool.governance != Pubkey::default()
+            && governance.key() == staking_pool.governance @ StakingError::InvalidGovernanceAccounts,
+    )]
+    pub governance: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetVotingFormula<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref(), &staking_pool.pool_id.to_le_bytes()],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        mut,
+        seeds = [b"tier_config".as_ref(), staking_pool.key().as_ref()],
+        bump = tier_config.bump,
+    )]
+    pub tier_config: Account<'info, TierConfig>,
+    
+    // The governance PDA, signing through an approved proposal's execution item
+    #[account(
+        constraint = staking_pool.governance != Pubkey::default()
+            && governance.key() == staking_pool.governance @ StakingError::InvalidGovernanceAccounts,
+    )]
+    pub governance: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RefreshVotingPower<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref(), &staking_pool.pool_id.to_le_bytes()],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        seeds = [b"tier_config".as_ref(), staking_pool.key().as_ref()],
+        bump = tier_config.bump,
+    )]
+    pub tier_config: Account<'info, TierConfig>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"user_stake".as_ref(),
+            user_stake.creator.as_ref(),
+            staking_pool.key().as_ref(),
+            &user_stake.position_index.to_le_bytes()
+        ],
+        bump = user_stake.bump,
+    )]
+    pub user_stake: Account<'info, UserStake>,
+}
+
+#[derive(Accounts)]
+pub struct SetRewardVestingPeriod<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref(), &staking_pool.pool_id.to_le_bytes()],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        constraint = reward_mint.key() == staking_pool.reward_mint,
+    )]
+    pub reward_mint: Account<'info, Mint>,
+    
+    // Holds rewards while they vest
+    #[account(
+        init_if_needed,
+        payer = authority,
+        seeds = [b"vesting_vault".as_ref(), staking_pool.key().as_ref()],
+        bump,
+        token::mint = reward_mint,
+        token::authority = staking_pool,
+    )]
+    pub vesting_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = authority.key() == staking_pool.authority,
+    )]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimVested<'info> {
+    #[account(
+        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref(), &staking_pool.pool_id.to_le_bytes()],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        mut,
+        seeds = [b"reward_vesting".as_ref(), staking_pool.key().as_ref(), user.key().as_ref()],
+        bump = reward_vesting.bump,
+    )]
+    pub reward_vesting: Account<'info, RewardVesting>,
+    
+    pub user: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = user_reward_account.mint == staking_pool.reward_mint,
+        constraint = user_reward_account.owner == user.key(),
+    )]
+    pub user_reward_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = vesting_vault.key() == staking_pool.vesting_vault,
+    )]
+    pub vesting_vault: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SetMintRewards<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref(), &staking_pool.pool_id.to_le_bytes()],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        constraint = reward_mint.key() == staking_pool.reward_mint,
+    )]
+    pub reward_mint: Account<'info, Mint>,
+    
+    // The pool authority, or the governance PDA once the pool is linked to a realm
+    #[account(
+        constraint = authority.key() == staking_pool.authority
+            || (staking_pool.governance != Pubkey::default() && authority.key() == staking_pool.governance),
+    )]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetPoolGovernance<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref(), &staking_pool.pool_id.to_le_bytes()],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    // The pool authority while no realm is linked, afterwards only the linked governance PDA
+    #[account(
+        constraint = if staking_pool.governance == Pubkey::default() {
+            authority.key() == staking_pool.authority
+        } else {
+            authority.key() == staking_pool.governance
+        } @ StakingError::InvalidGovernanceAccounts,
+    )]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetPoolPaused<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref(), &staking_pool.pool_id.to_le_bytes()],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    // The pool authority, or the governance PDA once the pool is linked to a realm
+    #[account(
+        constraint = authority.key() == staking_pool.authority
+            || (staking_pool.governance != Pubkey::default() && authority.key() == staking_pool.governance),
+    )]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SweepRetiredRewards<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref(), &staking_pool.pool_id.to_le_bytes()],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        mut,
+        constraint = rewards_vault.key() == staking_pool.rewards_vault,
+    )]
+    pub rewards_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = treasury_token_account.key() == staking_pool.treasury_token_account,
+    )]
+    pub treasury_token_account: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ManagePool<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref(), &staking_pool.pool_id.to_le_bytes()],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    // The pool authority, the pool's manager, or the governance PDA once the pool is linked to
+    // a realm
+    #[account(
+        constraint = authority.key() == staking_pool.authority
+            || (staking_pool.manager != Pubkey::default() && authority.key() == staking_pool.manager)
+            || (staking_pool.governance != Pubkey::default() && authority.key() == staking_pool.governance),
+    )]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateTierConfig<'info> {
+    #[account(
+        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref(), &staking_pool.pool_id.to_le_bytes()],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        mut,
+        seeds = [b"tier_config".as_ref(), staking_pool.key().as_ref()],
+        bump = tier_config.bump,
+    )]
+    pub tier_config: Account<'info, TierConfig>,
+    
+    // The pool authority, the pool's manager, or the governance PDA once the pool is linked to
+    // a realm
+    #[account(
+        constraint = authority.key() == staking_pool.authority
+            || (staking_pool.manager != Pubkey::default() && authority.key() == staking_pool.manager)
+            || (staking_pool.governance != Pubkey::default() && authority.key() == staking_pool.governance),
+    )]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateRewardParams<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref(), &staking_pool.pool_id.to_le_bytes()],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        constraint = authority.key() == staking_pool.authority,
+    )]
+    pub authority: Signer<'info>,
+}
+
+#[account]
+pub struct StakingPool {
+    pub authority: Pubkey,         // Admin authority
+    pub token_mint: Pubkey,        // Token mint address
+    pub treasury_token_account: Pubkey, // Pool treasury account
+    pub total_staked: u64,         // Total tokens staked
+    pub staker_count: u64,         // Wallets with at least one open position they opened
+    pub reward_rate: u64,          // Basis points per day (1/100 of 1%)
+    pub min_stake_duration: i64,   // Minimum staking duration in seconds
+    pub max_stake_duration: i64,   // Maximum staking duration in seconds
+    pub bump: u8,                  // PDA bump
+    pub governance_program: Pubkey, // Governance program of the linked realm
+    pub governance: Pubkey,        // Linked governance realm PDA (set once by the authority, then only by itself)
+    pub participation_min_votes: u8, // Votes needed within the window to earn the bonus
+    pub participation_window: u8,  // Number of most recent proposals considered
+    pub participation_bonus_bps: u16, // Reward bonus in basis points (0 = disabled)
+    pub unbonding_period: i64,     // Cooldown between request_unstake and withdraw (0 = direct unstake)
+    pub reward_per_token_stored: u128, // Rewards accrued per staked token, scaled by REWARD_PRECISION
+    pub last_update_timestamp: i64, // When reward_per_token_stored was last brought up to date
+    pub rate_checkpoints: [RateCheckpoint; MAX_RATE_CHECKPOINTS], // Reward rate history, oldest first
+    pub rate_checkpoint_count: u8, // Number of checkpoints recorded
+    pub total_reward_weight: u128, // Sum of stake_amount * reward_multiplier_bps over active positions
+    pub emission_per_epoch: u64,   // Tokens released per epoch (0 = flat reward_rate instead)
+    pub emission_epoch_duration: i64, // Epoch length in seconds
+    pub emission_start: i64,       // Start of the emission schedule's first epoch
+    pub halving_interval: u64,     // Epochs between halvings (0 = never halve)
+    pub reward_reserve: u64,       // Funded rewards not yet paid out
+    pub rewards_vault: Pubkey,     // Token account rewards are paid from
+    pub mint_rewards: bool,        // Whether rewards are minted instead of paid from the vault
+    pub emission_cap: u64,         // Most rewards that can ever be minted
+    pub total_minted: u64,         // Rewards minted so far
+    pub secondary_reward_mint: Pubkey, // Partner token paid alongside WCT (default = none)
+    pub secondary_rewards_vault: Pubkey, // Token account secondary rewards are paid from
+    pub secondary_reward_rate: u64, // Secondary tokens streamed per second across all stake
+    pub secondary_reward_per_token_stored: u128, // Secondary accumulator, scaled by REWARD_PRECISION
+    pub secondary_reward_reserve: u64, // Funded secondary rewards not yet paid out
+    pub receipt_mint: Pubkey,      // stWCT receipt mint, one receipt per staked token
+    pub paused_flags: u8,          // PAUSE_* bits for operations currently paused
+    pub emergency_mode: bool,      // Whether stakers may pull principal with emergency_withdraw
+    pub max_total_staked: u64,     // TVL cap on total_staked, 0 if uncapped
+    pub min_stake_amount: u64,     // Smallest amount a new position may stake
+    pub pool_id: u64,              // Distinguishes pools on the same mint, part of the PDA seeds
+    pub reward_mint: Pubkey,       // Token rewards are paid in (token_mint unless e.g. an LP pool)
+    pub staking_vault: Pubkey,     // Vault holding staked principal
+    pub performance_fee_bps: u16,  // Share of each reward payout sent to the fee vault
+    pub fee_vault: Pubkey,         // Governance-owned account collecting performance fees
+    pub reward_vesting_period: i64, // Seconds claimed rewards vest over, 0 if paid immediately
+    pub vesting_vault: Pubkey,     // Holds rewards while they vest
+    pub migration_target: Pubkey,  // Pool positions may migrate to (default = none)
+    pub compliance_authority: Pubkey, // Manages the pool's allow/deny list
+    pub compliance_mode: u8,       // COMPLIANCE_NONE, COMPLIANCE_ALLOWLIST or COMPLIANCE_DENYLIST
+    pub compliance_scope: u8,      // COMPLIANCE_* bits for the instructions the list applies to
+    pub min_claim_interval: i64,   // Shortest time between claims on a position, in seconds
+    pub total_voting_power: u64,   // Sum of the voting power of all open positions
+    pub params_timelock: i64,      // Seconds reward parameter changes wait before applying
+    pub pending_reward_rate: u64,  // Queued reward rate
+    pub pending_min_stake_duration: i64, // Queued minimum staking duration
+    pub pending_max_stake_duration: i64, // Queued maximum staking duration
+    pub pending_params_at: i64,    // When the queued parameters may be applied, 0 if none queued
+    pub incident_exit_enabled: bool, // Whether governance has flagged an incident, allowing incident_exit
+    pub manager: Pubkey,           // Operator for tiers, caps and pauses (default = none)
+    pub unbonding_budget: u64,     // Unbonding tokens released per epoch, 0 if there's no queue
+    pub unbonding_queue_tail: u64, // Tokens ever queued for unbonding
+    pub unbonding_queue_head: u64, // Tokens ever released from the front of the queue
+    pub unbonding_budget_epoch: u64, // Epoch unbonding_budget_used counts towards
+    pub unbonding_budget_used: u64, // Tokens released so far in that epoch
+    pub retire_at: i64,            // Emission cutoff of a retiring pool, 0 if it isn't retiring
+    pub tokens_per_vote: u64,      // Staked base units per vote, before tier voting multipliers
+    pub badge_program: Pubkey,     // Program that mints achievement badges (default = none)
+}
+
+impl StakingPool {
+    pub const LEN: usize = 32 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 1 + 32 + 32 + 1 + 1 + 2 + 8 + 16 + 8
+        + RateCheckpoint::LEN * MAX_RATE_CHECKPOINTS + 1 + 16 + 8 + 8 + 8 + 8 + 8 + 32 + 1 + 8 + 8
+        + 32 + 32 + 8 + 16 + 8 + 32 + 1 + 1 + 8 + 8 + 8 + 32 + 32 + 2 + 32 + 8 + 32 + 32
+        + 32 + 1 + 1 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 8
+        + 32;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct RateCheckpoint {
+    pub timestamp: i64,            // When the rate took effect
+    pub reward_rate: u64,          // Rate in basis points from that time on
+}
+
+impl RateCheckpoint {
+    pub const LEN: usize = 8 + 8;
+}
+
+#[account]
+pub struct TierConfig {
+    pub pool: Pubkey,              // Staking pool
+    pub tiers: [DurationTier; MAX_DURATION_TIERS], // Tiers by ascending minimum duration
+    pub tier_count: u8,            // Number of tiers in use
+    pub bump: u8,                  // PDA bump
+}
+
+impl TierConfig {
+    pub const LEN: usize = 32 + DurationTier::LEN * MAX_DURATION_TIERS + 1 + 1;
+    
+    // Replace the tiers. The first tier must start at zero so every duration has a tier.
+    pub fn set_tiers(&mut self, tiers: &[DurationTier]) -> Result<()> {
+        require!(
+            !tiers.is_empty() && tiers.len() <= MAX_DURATION_TIERS && tiers[0].min_duration == 0,
+            StakingError::InvalidTierConfig
+        );
+        for pair in tiers.windows(2) {
+            require!(
+                pair[1].min_duration > pair[0].min_duration,
+                StakingError::InvalidTierConfig
+            );
+        }
+        for tier in tiers {
+            require!(
+                tier.reward_multiplier_bps > 0 && tier.voting_multiplier_bps > 0,
+                StakingError::InvalidTierConfig
+            );
+        }
+        
+        self.tiers = [DurationTier::default(); MAX_DURATION_TIERS];
+        self.tiers[..tiers.len()].copy_from_slice(tiers);
+        self.tier_count = tiers.len() as u8;
+        Ok(())
+    }
+    
+    // Highest tier whose minimum duration the lock meets
+    pub fn tier_for(&self, duration: i64) -> DurationTier {
+        *self.tiers[..self.tier_count as usize]
+            .iter()
+            .rev()
+            .find(|tier| duration >= tier.min_duration)
+            .unwrap()
+    }
+    
+    // Whether every lock duration gets the same voting multiplier under both configs. The
+    // multiplier only changes at tier boundaries, so checking those of both is enough.
+    pub fn same_voting_multipliers(&self, other: &TierConfig) -> bool {
+        self.tiers[..self.tier_count as usize]
+            .iter()
+            .chain(other.tiers[..other.tier_count as usize].iter())
+            .all(|tier| {
+                self.tier_for(tier.min_duration).voting_multiplier_bps
+                    == other.tier_for(tier.min_duration).voting_multiplier_bps
+            })
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct DurationTier {
+    pub min_duration: i64,         // Shortest lock, in seconds, that qualifies for the tier
+    pub reward_multiplier_bps: u16, // Reward rate multiplier (10000 = 1x)
+    pub voting_multiplier_bps: u16, // Voting power multiplier (10000 = 1x)
+    pub reputation_boost: u64,     // Reputation boost in percentage
+}
+
+impl DurationTier {
+    pub const LEN: usize = 8 + 2 + 2 + 8;
+}
+
+#[account]
+pub struct StakerInfo {
+    pub owner: Pubkey,             // User wallet
+    pub pool: Pubkey,              // Staking pool
+    pub position_count: u64,       // Positions ever opened; the next position's index
+    pub bump: u8,                  // PDA bump
+    pub active_positions: u64,     // Positions opened by the wallet that aren't withdrawn yet
+    pub streak_start: i64,         // Since when the wallet has had a position open, 0 if none
+}
+
+impl StakerInfo {
+    pub const LEN: usize = 32 + 32 + 8 + 1 + 8 + 8;
+}
+
+#[account]
+pub struct StakerBadges {
+    pub pool: Pubkey,              // Staking pool
+    pub owner: Pubkey,             // Wallet the badges were minted to
+    pub minted: u8,                // Bit 1 << badge set once that badge has been minted
+    pub bump: u8,                  // PDA bump
+}
+
+impl StakerBadges {
+    pub const LEN: usize = 32 + 32 + 1 + 1;
+}
+
+#[account]
+pub struct ComplianceEntry {
+    pub pool: Pubkey,              // Staking pool
+    pub wallet: Pubkey,            // Listed wallet
+    pub listed: bool,              // On the pool's list: allowed in allowlist mode, blocked in denylist mode
+    pub bump: u8,                  // PDA bump
+}
+
+impl ComplianceEntry {
+    pub const LEN: usize = 32 + 32 + 1 + 1;
+}
+
+#[account]
+pub struct SlashReceipt {
+    pub proposal: Pubkey,          // Proposal whose slash was applied
+    pub position: Pubkey,          // Slashed position
+    pub applied_at: i64,           // When the slash was applied
+    pub bump: u8,                  // PDA bump
+}
+
+impl SlashReceipt {
+    pub const LEN: usize = 32 + 32 + 8 + 1;
+}
+
+#[account]
+pub struct UserStakeRegistry {
+    pub owner: Pubkey,             // User wallet
+    pub positions: Vec<Pubkey>,    // Open position accounts the wallet created, across pools
+    pub bump: u8,                  // PDA bump
+}
+
+impl UserStakeRegistry {
+    pub const LEN: usize = 32 + 4 + 32 * MAX_REGISTERED_POSITIONS + 1;
+}
+
+#[account]
+pub struct SessionKey {
+    pub owner: Pubkey,             // Wallet the session key acts for
+    pub session_signer: Pubkey,    // Key allowed to claim rewards for the owner's positions
+    pub expires_at: i64,           // When the session key stops working
+    pub bump: u8,                  // PDA bump
+}
+
+impl SessionKey {
+    pub const LEN: usize = 32 + 32 + 8 + 1;
+}
+
+#[account]
+pub struct RewardVesting {
+    pub beneficiary: Pubkey,       // Wallet the rewards vest to
+    pub pool: Pubkey,              // Staking pool
+    pub total: u64,                // Rewards on the current schedule
+    pub released: u64,             // Rewards already withdrawn from the current schedule
+    pub start_timestamp: i64,      // When the current schedule started
+    pub end_timestamp: i64,        // When everything on it has vested
+    pub bump: u8,                  // PDA bump
+}
+
+impl RewardVesting {
+    pub const LEN: usize = 32 + 32 + 8 + 8 + 8 + 8 + 1;
+    
+    // Vested but not yet withdrawn
+    pub fn releasable(&self, now: i64) -> Result<u64> {
+        let vested = if now >= self.end_timestamp {
+            self.total
+        } else if now <= self.start_timestamp {
+            0
+        } else {
+            (self.total as u128)
+                .try_mul((now - self.start_timestamp) as u128)?
+                .try_div((self.end_timestamp - self.start_timestamp) as u128)? as u64
+        };
+        Ok(vested.saturating_sub(self.released))
+    }
+}
+
+#[account]
+pub struct BoostCollection {
+    pub pool: Pubkey,              // Staking pool
+    pub collection_mint: Pubkey,   // Approved NFT collection
+    pub boost_bps: u16,            // Reward multiplier and voting power boost for holders
+    pub bump: u8,                  // PDA bump
+}
+
+impl BoostCollection {
+    pub const LEN: usize = 32 + 32 + 2 + 1;
+}
+
+#[account]
+pub struct EpochSnapshot {
+    pub pool: Pubkey,              // Staking pool
+    pub epoch: u64,                // Snapshot epoch (unix time / SNAPSHOT_EPOCH_DURATION)
+    pub timestamp: i64,            // When the snapshot was taken
+    pub total_staked: u64,         // Pool principal at the time
+    pub reward_reserve: u64,       // Funded rewards not yet paid out
+    pub reward_per_token_stored: u128, // Reward accumulator at the time
+    pub effective_rate_bps: u64,   // Annual base rate a 1x position was earning
+    pub bump: u8,                  // PDA bump
+}
+
+impl EpochSnapshot {
+    pub const LEN: usize = 32 + 8 + 8 + 8 + 8 + 16 + 8 + 1;
+}
+
+#[account]
+pub struct PoolYieldOracle {
+    pub pool: Pubkey,              // Staking pool
+    pub samples: [YieldSample; MAX_YIELD_SAMPLES], // Daily accumulator samples, oldest first
+    pub sample_count: u8,          // Number of samples in use
+    pub rate_7d_bps: u64,          // Annual base rate a 1x position realized over the last 7 days
+    pub rate_30d_bps: u64,         // Annual base rate a 1x position realized over the last 30 days
+    pub updated_at: i64,           // When the rates were last refreshed
+    pub bump: u8,                  // PDA bump
+}
+
+impl PoolYieldOracle {
+    pub const LEN: usize = 32 + YieldSample::LEN * MAX_YIELD_SAMPLES + 1 + 8 + 8 + 8 + 1;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct YieldSample {
+    pub timestamp: i64,            // When the sample was taken
+    pub reward_per_token_stored: u128, // Reward accumulator at the time
+}
+
+impl YieldSample {
+    pub const LEN: usize = 8 + 16;
+}
+
+#[account]
+pub struct UserStake {
+    pub owner: Pubkey,             // User wallet
+    pub pool: Pubkey,              // Staking pool
+    pub position_index: u64,       // Index of this position among the user's positions
+    pub stake_amount: u64,         // Amount staked
+    pub start_timestamp: i64,      // Start time
+    pub end_timestamp: i64,        // End time (lock expiry)
+    pub claimed_reward: u64,       // Total rewards claimed
+    pub last_claim_timestamp: i64, // Last reward claim time
+    pub reputation_boost: u64,     // Reputation boost in percentage
+    pub voting_power: u64,         // Governance voting power
+    pub withdrawn: bool,           // Whether tokens were withdrawn
+    pub bump: u8,                  // PDA bump
+    pub unbonding_amount: u64,     // Tokens requested for withdrawal, no longer earning or voting
+    pub unbonding_ends_at: i64,    // When the unbonding tokens can be withdrawn
+    pub reward_debt: u128,         // Share of the pool accumulator already paid out or not earned
+    pub reward_multiplier_bps: u16, // Tier reward rate multiplier (10000 = 1x)
+    pub secondary_reward_debt: u128, // Share of the secondary accumulator already accounted for
+    pub secondary_reward_owed: u64, // Secondary rewards earned but not yet paid
+    pub creator: Pubkey,           // Wallet that opened the position, part of its PDA seeds
+    pub position_mint: Pubkey,     // Position NFT mint (default = not tokenized)
+    pub pending_owner: Pubkey,     // Wallet offered the position (default = none)
+    pub lock_duration: i64,        // Length of the current lock, reused by relock
+    pub auto_relock: bool,         // Whether the lock rolls over at expiry
+    pub nft_boost_mint: Pubkey,    // NFT boosting the position (default = none)
+    pub nft_boost_bps: u16,        // Boost included in reward_multiplier_bps and voting_power
+    pub reward_destination: Pubkey, // Wallet rewards must be paid to (default = owner's choice)
+    pub frozen_until: i64,         // When a governance freeze runs out, 0 if not frozen
+    pub frozen_reward: u64,        // Reward pending when the position was frozen
+    pub unbonding_queue_ticket: u64, // Queue head the unbonding tokens are released at, 0 if not queued
+    pub registered_voter: Pubkey,  // Voter the position's governance power is registered to (default = none)
+    pub registered_voting_power: u64, // Voting power last registered with governance, before its boost
+    pub registered_reputation_boost: u64, // Reputation boost last registered with governance
+    pub reward_owed: u64,          // Reward carried over from another pool, paid with the next claim
+    pub slashed_receipts: u64,     // stWCT receipts left without principal by a slash, burned with the next exit
+}
+
+impl UserStake {
+    pub const LEN: usize = 32 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 1 + 8 + 8 + 16 + 2 + 16 + 8 + 32 + 32 + 32 + 8 + 1 + 32 + 2 + 32 + 8 + 8 + 8
+        + 32 + 8 + 8 + 8 + 8;
+}
+
+#[event]
+pub struct StakeLadderEvent {
+    pub user: Pubkey,
+    pub first_position_index: u64,
+    pub rungs: u8,
+    pub total_amount: u64,
+}
+
+#[event]
+pub struct StakeEvent {
+    pub user: Pubkey,
+    pub position_index: u64,
+    pub amount: u64,
+    pub duration: i64,
+    pub end_timestamp: i64,
+    pub reputation_boost: u64,
+    pub voting_power: u64,
+    pub reward_multiplier_bps: u16,
+    pub reward_debt: u128,
+    // Pool state after the instruction
+    pub total_staked: u64,
+    pub reward_reserve: u64,
+    pub reward_per_token_stored: u128,
+}
+
+#[event]
+pub struct RewardEvent {
+    pub user: Pubkey,
+    pub position_index: u64,
+    pub reward_amount: u64,
+    pub participation_bonus: u64,
+    pub loyalty_bonus: u64,
+    pub performance_fee: u64,
+    pub secondary_reward_amount: u64,
+    pub days_elapsed: u64,
+    pub total_claimed: u64,
+    // Position state after the claim
+    pub stake_amount: u64,
+    pub reward_debt: u128,
+    // Pool state after the instruction
+    pub total_staked: u64,
+    pub reward_reserve: u64,
+    pub reward_per_token_stored: u128,
+}
+
+#[event]
+pub struct ClaimAllEvent {
+    pub user: Pubkey,
+    pub position_count: u64,
+    pub reward_amount: u64,
+    pub participation_bonus: u64,
+    pub loyalty_bonus: u64,
+    pub performance_fee: u64,
+}
+
+#[event]
+pub struct CompoundEvent {
+    pub user: Pubkey,
+    pub position_index: u64,
+    pub compounded_amount: u64,
+    pub participation_bonus: u64,
+    pub loyalty_bonus: u64,
+    pub stake_amount: u64,
+    pub voting_power: u64,
+}
+
+#[event]
+pub struct UnstakeEvent {
+    pub user: Pubkey,
+    pub position_index: u64,
+    pub amount: u64,
+    pub remaining_amount: u64,
+    pub total_rewards: u64,
+    // Position state after the withdrawal
+    pub unbonding_amount: u64,
+    pub voting_power: u64,
+    pub reward_debt: u128,
+    pub withdrawn: bool,
+    // Pool state after the instruction
+    pub total_staked: u64,
+    pub reward_reserve: u64,
+    pub reward_per_token_stored: u128,
+}
+
+#[event]
+pub struct BadgeProgramUpdateEvent {
+    pub badge_program: Pubkey,
+    pub updated_by: Pubkey,
+}
+
+#[event]
+pub struct StakerBadgeMintedEvent {
+    pub owner: Pubkey,
+    pub pool: Pubkey,
+    pub badge: u8,
+}
+
+#[event]
+pub struct VotingPowerSyncedEvent {
+    pub governance: Pubkey,
+    pub total_voting_power: u64,
+}
+
+#[event]
+pub struct YieldOracleUpdatedEvent {
+    pub rate_7d_bps: u64,
+    pub rate_30d_bps: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PoolUpdatedEvent {
+    pub reward_per_token_stored: u128,
+    pub secondary_reward_per_token_stored: u128,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RewardsFundedEvent {
+    pub funder: Pubkey,
+    pub amount: u64,
+    pub reward_reserve: u64,
+}
+
+#[event]
+pub struct SecondaryRewardsUpdateEvent {
+    pub secondary_mint: Pubkey,
+    pub reward_rate: u64,
+}
+
+#[event]
+pub struct SecondaryRewardsFundedEvent {
+    pub funder: Pubkey,
+    pub amount: u64,
+    pub secondary_reward_reserve: u64,
+}
+
+#[event]
+pub struct SecondaryRewardEvent {
+    pub user: Pubkey,
+    pub position_index: u64,
+    pub amount: u64,
+}
+
+#[event]
+pub struct MintRewardsUpdateEvent {
+    pub enabled: bool,
+    pub emission_cap: u64,
+    pub total_minted: u64,
+}
+
+#[event]
+pub struct EmissionScheduleUpdateEvent {
+    pub emission_per_epoch: u64,
+    pub epoch_duration: i64,
+    pub halving_interval: u64,
+    pub emission_start: i64,
+}
+
+#[event]
+pub struct TierConfigUpdateEvent {
+    pub tiers: Vec<DurationTier>,
+}
+
+#[event]
+pub struct VotingFormulaUpdateEvent {
+    pub tokens_per_vote: u64,
+    pub voting_multipliers_bps: Vec<u16>,
+}
+
+#[event]
+pub struct PositionVotingPowerSyncedEvent {
+    pub voter: Pubkey,
+    pub position_index: u64,
+    pub voting_power: u64,
+    pub reputation_boost: u64,
+}
+
+#[event]
+pub struct VotingPowerRefreshedEvent {
+    pub owner: Pubkey,
+    pub position_index: u64,
+    pub old_voting_power: u64,
+    pub voting_power: u64,
+    pub total_voting_power: u64,
+}
+
+#[event]
+pub struct UnstakeRequestedEvent {
+    pub user: Pubkey,
+    pub position_index: u64,
+    pub amount: u64,
+    pub unbonding_amount: u64,
+    pub unbonding_ends_at: i64,
+    pub unbonding_queue_ticket: u64,
+}
+
+#[event]
+pub struct ComplianceConfigEvent {
+    pub compliance_authority: Pubkey,
+    pub mode: u8,
+    pub scope: u8,
+    pub updated_by: Pubkey,
+}
+
+#[event]
+pub struct ComplianceStatusEvent {
+    pub wallet: Pubkey,
+    pub listed: bool,
+    pub updated_by: Pubkey,
+}
+
+#[event]
+pub struct MigrationTargetUpdateEvent {
+    pub migration_target: Pubkey,
+    pub updated_by: Pubkey,
+}
+
+#[event]
+pub struct StakeMigratedEvent {
+    pub user: Pubkey,
+    pub old_pool: Pubkey,
+    pub old_position_index: u64,
+    pub new_pool: Pubkey,
+    pub new_position_index: u64,
+    pub amount: u64,
+    pub carried_reward: u64,
+}
+
+#[event]
+pub struct IncidentExitModeEvent {
+    pub enabled: bool,
+}
+
+#[event]
+pub struct IncidentExitEvent {
+    pub user: Pubkey,
+    pub position_index: u64,
+    pub amount: u64,
+    pub forfeited_reward: u64,
+    pub forfeited_secondary_reward: u64,
+}
+
+#[event]
+pub struct ExpiredPositionFinalizedEvent {
+    pub owner: Pubkey,
+    pub position: Pubkey,
+    pub position_index: u64,
+    pub amount: u64,
+    pub final_reward: u64,
+    pub finalized_by: Pubkey,
+    pub withdrawn: bool,
+    pub total_staked: u64,
+}
+
+#[event]
+pub struct ManagerUpdateEvent {
+    pub old_manager: Pubkey,
+    pub manager: Pubkey,
+    pub updated_by: Pubkey,
+}
+
+#[event]
+pub struct PositionFrozenEvent {
+    pub owner: Pubkey,
+    pub position: Pubkey,
+    pub frozen_until: i64,
+    pub frozen_reward: u64,
+}
+
+#[event]
+pub struct PositionUnfrozenEvent {
+    pub owner: Pubkey,
+    pub position: Pubkey,
+}
+
+#[event]
+pub struct EmergencyModeEvent {
+    pub enabled: bool,
+    pub updated_by: Pubkey,
+}
+
+#[event]
+pub struct PoolRetiredEvent {
+    pub cutoff: i64,
+    pub retired_by: Pubkey,
+}
+
+#[event]
+pub struct RetiredRewardsSweptEvent {
+    pub amount: u64,
+}
+
+#[event]
+pub struct EmergencyWithdrawEvent {
+    pub user: Pubkey,
+    pub position_index: u64,
+    pub amount: u64,
+}
+
+#[event]
+pub struct PoolPauseEvent {
+    pub paused_flags: u8,
+    pub updated_by: Pubkey,
+}
+
+#[event]
+pub struct TvlCapUpdateEvent {
+    pub max_total_staked: u64,
+    pub updated_by: Pubkey,
 }
 
-#[derive(Accounts)]
-pub struct Stake<'info> {
-    #[account(
-        mut,
-        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref()],
-        bump = staking_pool.bump,
-    )]
-    pub staking_pool: Account<'info, StakingPool>,
-    
-    #[account(
-        init,
-        payer = user,
-        space = 8 + UserStake::LEN,
-        seeds = [b"user_stake".as_ref(), user.key().as_ref(), staking_pool.key().as_ref()],
-        bump,
-    )]
-    pub user_stake: Account<'info, UserStake>,
-    
-    #[account(mut)]
-    pub user: Signer<'info>,
-    
-    #[account(
-        mut,
-        constraint = user_token_account.mint == staking_pool.token_mint,
-        constraint = user_token_account.owner == user.key(),
-    )]
-    pub user_token_account: Account<'info, TokenAccount>,
-    
-    #[account(
-        mut,
-        constraint = staking_vault.mint == staking_pool.token_mint,
-        constraint = staking_vault.owner == staking_pool.key(),
-    )]
-    pub staking_vault: Account<'info, TokenAccount>,
-    
-    pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
-    pub rent: Sysvar<'info, Rent>,
+#[event]
+pub struct RewardVestingUpdateEvent {
+    pub vesting_period: i64,
 }
 
-#[derive(Accounts)]
-pub struct ClaimReward<'info> {
-    #[account(
-        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref()],
-        bump = staking_pool.bump,
-    )]
-    pub staking_pool: Account<'info, StakingPool>,
-    
-    #[account(
-        mut,
-        seeds = [b"user_stake".as_ref(), user.key().as_ref(), staking_pool.key().as_ref()],
-        bump,
-        constraint = user_stake.owner == user.key(),
-    )]
-    pub user_stake: Account<'info, UserStake>,
-    
-    #[account(mut)]
-    pub user: Signer<'info>,
-    
-    #[account(
-        mut,
-        constraint = user_token_account.mint == staking_pool.token_mint,
-        constraint = user_token_account.owner == user.key(),
-    )]
-    pub user_token_account: Account<'info, TokenAccount>,
-    
-    #[account(
-        mut,
-        constraint = treasury_token_account.key() == staking_pool.treasury_token_account,
-        constraint = treasury_token_account.mint == staking_pool.token_mint,
-    )]
-    pub treasury_token_account: Account<'info, TokenAccount>,
-    
-    pub token_program: Program<'info, Token>,
+#[event]
+pub struct VestedRewardEvent {
+    pub user: Pubkey,
+    pub amount: u64,
+    pub remaining_amount: u64,
 }
 
-#[derive(Accounts)]
-pub struct Unstake<'info> {
-    #[account(
-        mut,
-        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref()],
-        bump = staking_pool.bump,
-    )]
-    pub staking_pool: Account<'info, StakingPool>,
-    
-    #[account(
-        mut,
-        seeds = [b"user_stake".as_ref(), user.key().as_ref(), staking_pool.key().as_ref()],
-        bump,
-        constraint = user_stake.owner == user.key(),
-    )]
-    pub user_stake: Account<'info, UserStake>,
-    
-    #[account(mut)]
-    pub user: Signer<'info>,
-    
-    #[account(
-        mut,
-        constraint = user_token_account.mint == staking_pool.token_mint,
-        constraint = user_token_account.owner == user.key(),
-    )]
-    pub user_token_account: Account<'info, TokenAccount>,
-    
-    #[account(
-        mut,
-        constraint = staking_vault.mint == staking_pool.token_mint,
-        constraint = staking_vault.owner == staking_pool.key(),
-    )]
-    pub staking_vault: Account<'info, TokenAccount>,
-    
-    #[account(
-        mut,
-        constraint = treasury_token_account.key() == staking_pool.treasury_token_account,
-        constraint = treasury_token_account.mint == staking_pool.token_mint,
-    )]
-    pub treasury_token_account: Account<'info, TokenAccount>,
-    
-    pub token_program: Program<'info, Token>,
+#[event]
+pub struct PerformanceFeeUpdateEvent {
+    pub fee_bps: u16,
+    pub fee_vault: Pubkey,
 }
 
-#[derive(Accounts)]
-pub struct UpdateRewardParams<'info> {
-    #[account(
-        mut,
-        seeds = [b"staking_pool".as_ref(), staking_pool.token_mint.as_ref()],
-        bump = staking_pool.bump,
-    )]
-    pub staking_pool: Account<'info, StakingPool>,
-    
-    #[account(
-        constraint = authority.key() == staking_pool.authority,
-    )]
-    pub authority: Signer<'info>,
+#[event]
+pub struct MinStakeAmountUpdateEvent {
+    pub min_stake_amount: u64,
 }
 
-#[account]
-pub struct StakingPool {
-    pub authority: Pubkey,         // Admin authority
-    pub token_mint: Pubkey,        // Token mint address
-    pub treasury_token_account: Pubkey, // Treasury account for rewards
-    pub total_staked: u64,         // Total tokens staked
-    pub staker_count: u64,         // Number of stakers
-    pub reward_rate: u64,          // Basis points per day (1/100 of 1%)
-    pub min_stake_duration: i64,   // Minimum staking duration in seconds
-    pub max_stake_duration: i64,   // Maximum staking duration in seconds
-    pub bump: u8,                  // PDA bump
+#[event]
+pub struct ClaimIntervalUpdateEvent {
+    pub min_claim_interval: i64,
 }
 
-impl StakingPool {
-    pub const LEN: usize = 32 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 1;
+#[event]
+pub struct UnbondingBudgetUpdateEvent {
+    pub unbonding_budget: u64,
+    pub updated_by: Pubkey,
 }
 
-#[account]
-pub struct UserStake {
-    pub owner: Pubkey,             // User wallet
-    pub stake_amount: u64,         // Amount staked
-    pub start_timestamp: i64,      // Start time
-    pub end_timestamp: i64,        // End time (lock expiry)
-    pub claimed_reward: u64,       // Total rewards claimed
-    pub last_claim_timestamp: i64, // Last reward claim time
-    pub reputation_boost: u64,     // Reputation boost in percentage
-    pub voting_power: u64,         // Governance voting power
-    pub withdrawn: bool,           // Whether tokens were withdrawn
+#[event]
+pub struct UnbondingQueueEvent {
+    pub queue_head: u64,
+    pub queue_tail: u64,
+    pub budget_used: u64,
 }
 
-impl UserStake {
-    pub const LEN: usize = 32 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 1;
+#[event]
+pub struct UnbondingPeriodUpdateEvent {
+    pub unbonding_period: i64,
 }
 
 #[event]
-pub struct StakeEvent {
+pub struct StakeAccountClosedEvent {
+    pub user: Pubkey,
+    pub position_index: u64,
+}
+
+#[event]
+pub struct PositionTokenizedEvent {
     pub user: Pubkey,
+    pub position_index: u64,
+    pub position_mint: Pubkey,
+}
+
+#[event]
+pub struct PositionOwnerSyncedEvent {
+    pub position_mint: Pubkey,
+    pub position_index: u64,
+    pub previous_owner: Pubkey,
+    pub new_owner: Pubkey,
+}
+
+#[event]
+pub struct StakeSlashedEvent {
+    pub owner: Pubkey,
+    pub position_index: u64,
+    pub proposal: Pubkey,
+    pub slash_bps: u16,
     pub amount: u64,
-    pub duration: i64,
-    pub end_timestamp: i64,
+    pub remaining_amount: u64,
+}
+
+#[event]
+pub struct StakeOwnershipOfferedEvent {
+    pub owner: Pubkey,
+    pub position_index: u64,
+    pub pending_owner: Pubkey,
+}
+
+#[event]
+pub struct StakeOwnershipTransferredEvent {
+    pub previous_owner: Pubkey,
+    pub new_owner: Pubkey,
+    pub position_index: u64,
+}
+
+#[event]
+pub struct SessionKeyCreatedEvent {
+    pub owner: Pubkey,
+    pub session_signer: Pubkey,
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct SessionKeyRevokedEvent {
+    pub owner: Pubkey,
+    pub session_signer: Pubkey,
+}
+
+#[event]
+pub struct LockExtendedEvent {
+    pub user: Pubkey,
+    pub position_index: u64,
+    pub old_end_timestamp: i64,
+    pub new_end_timestamp: i64,
     pub reputation_boost: u64,
     pub voting_power: u64,
 }
 
 #[event]
-pub struct RewardEvent {
+pub struct BoostCollectionUpdateEvent {
+    pub collection_mint: Pubkey,
+    pub boost_bps: u16,
+}
+
+#[event]
+pub struct NftBoostEvent {
     pub user: Pubkey,
-    pub reward_amount: u64,
-    pub days_elapsed: u64,
-    pub total_claimed: u64,
+    pub position_index: u64,
+    pub nft_mint: Pubkey,
+    pub boost_bps: u16,
+    pub voting_power: u64,
 }
 
 #[event]
-pub struct UnstakeEvent {
+pub struct NftBoostRemovedEvent {
+    pub user: Pubkey,
+    pub position_index: u64,
+    pub nft_mint: Pubkey,
+    pub voting_power: u64,
+}
+
+#[event]
+pub struct StakeSplitEvent {
     pub user: Pubkey,
+    pub position_index: u64,
+    pub new_position_index: u64,
     pub amount: u64,
-    pub total_rewards: u64,
+    pub remaining_amount: u64,
+}
+
+#[event]
+pub struct AutoRelockUpdateEvent {
+    pub user: Pubkey,
+    pub position_index: u64,
+    pub enabled: bool,
+}
+
+#[event]
+pub struct RewardDestinationUpdateEvent {
+    pub user: Pubkey,
+    pub position_index: u64,
+    pub reward_destination: Pubkey,
+}
+
+#[event]
+pub struct PositionRelockedEvent {
+    pub user: Pubkey,
+    pub position_index: u64,
+    pub old_end_timestamp: i64,
+    pub new_end_timestamp: i64,
 }
 
 #[event]
 pub struct ParamsUpdateEvent {
+    pub old_reward_rate: u64,
+    pub old_min_stake_duration: i64,
+    pub old_max_stake_duration: i64,
+    pub reward_rate: u64,
+    pub min_stake_duration: i64,
+    pub max_stake_duration: i64,
+}
+
+#[event]
+pub struct ParamsQueuedEvent {
+    pub old_reward_rate: u64,
+    pub old_min_stake_duration: i64,
+    pub old_max_stake_duration: i64,
+    pub reward_rate: u64,
+    pub min_stake_duration: i64,
+    pub max_stake_duration: i64,
+    pub effective_at: i64,
+}
+
+#[event]
+pub struct ParamsCancelledEvent {
     pub reward_rate: u64,
     pub min_stake_duration: i64,
     pub max_stake_duration: i64,
 }
 
+#[event]
+pub struct ParamsTimelockUpdateEvent {
+    pub params_timelock: i64,
+}
+
+#[event]
+pub struct PoolGovernanceUpdateEvent {
+    pub governance_program: Pubkey,
+    pub governance: Pubkey,
+    pub updated_by: Pubkey,
+}
+
+#[event]
+pub struct ParticipationBonusUpdateEvent {
+    pub min_votes: u8,
+    pub window: u8,
+    pub bonus_bps: u16,
+}
+
 #[error_code]
 pub enum StakingError {
     #[msg("Invalid stake duration. Must be between min and max duration.")]
@@ -531,4 +8253,306 @@ pub enum StakingError {
     StakeAlreadyWithdrawn,
     #[msg("No rewards available yet.")]
     NoRewardsYet,
+    #[msg("New lock must end after the current lock.")]
+    LockNotExtended,
+    #[msg("Unstake amount must be positive and no more than the staked balance.")]
+    InvalidUnstakeAmount,
+    #[msg("This pool requires request_unstake and withdraw after the unbonding period or queue.")]
+    UnbondingRequired,
+    #[msg("Nothing is unbonding for this position.")]
+    NothingUnbonding,
+    #[msg("Unbonding period has not elapsed yet.")]
+    UnbondingNotComplete,
+    #[msg("Unbonding period cannot be negative.")]
+    InvalidUnbondingPeriod,
+    #[msg("Invalid governance participation bonus configuration.")]
+    InvalidParticipationBonus,
+    #[msg("Governance accounts do not match the staking pool's configuration.")]
+    InvalidGovernanceAccounts,
+    #[msg("Tiers must start at zero duration, ascend strictly and have non-zero multipliers.")]
+    InvalidTierConfig,
+    #[msg("Emission schedule needs a positive epoch duration.")]
+    InvalidEmissionSchedule,
+    #[msg("Reward reserve cannot cover this payout.")]
+    RewardReserveDepleted,
+    #[msg("Funding amount must be positive.")]
+    InvalidFundingAmount,
+    #[msg("Minting this reward would exceed the pool's emission cap.")]
+    EmissionCapExceeded,
+    #[msg("The token mint account is required when the pool mints rewards.")]
+    MintAccountRequired,
+    #[msg("Mint authority has not been handed to the staking pool.")]
+    MintAuthorityNotDelegated,
+    #[msg("Secondary reward mint must differ from the staked token and can't change once set.")]
+    InvalidSecondaryMint,
+    #[msg("Position is already tokenized.")]
+    PositionAlreadyTokenized,
+    #[msg("Position is not tokenized.")]
+    PositionNotTokenized,
+    #[msg("This position is tokenized; the caller must hold its NFT.")]
+    PositionNftRequired,
+    #[msg("Metadata URI is too long.")]
+    InvalidMetadataUri,
+    #[msg("Metadata account does not match the position mint.")]
+    InvalidMetadataAccount,
+    #[msg("Tokenized positions change hands by transferring their NFT.")]
+    PositionTokenized,
+    #[msg("New owner must differ from the current owner.")]
+    InvalidNewOwner,
+    #[msg("Signer is not the pending owner of this position.")]
+    NotPendingOwner,
+    #[msg("Slash must be between 1 and 10000 basis points and take at least one token.")]
+    InvalidSlashAmount,
+    #[msg("Unknown pause flags.")]
+    InvalidPauseFlags,
+    #[msg("Staking is paused.")]
+    StakingPaused,
+    #[msg("Reward claims are paused.")]
+    ClaimsPaused,
+    #[msg("Unstaking is paused.")]
+    UnstakingPaused,
+    #[msg("Pool is not in emergency mode.")]
+    NotInEmergencyMode,
+    #[msg("Position has not been fully withdrawn.")]
+    StakeNotWithdrawn,
+    #[msg("Claim the position's outstanding secondary rewards first.")]
+    SecondaryRewardOutstanding,
+    #[msg("This stake would exceed the pool's TVL cap.")]
+    TvlCapExceeded,
+    #[msg("Stake amount is below the pool minimum.")]
+    StakeTooSmall,
+    #[msg("Auto-relock is not enabled for this position.")]
+    AutoRelockDisabled,
+    #[msg("Split amount must be positive and less than the staked balance.")]
+    InvalidSplitAmount,
+    #[msg("This pool's rewards are paid in a different mint than the staked token.")]
+    RewardMintMismatch,
+    #[msg("Snapshots can only be taken for the current epoch.")]
+    InvalidSnapshotEpoch,
+    #[msg("Performance fee is above the allowed maximum.")]
+    InvalidPerformanceFee,
+    #[msg("The fee vault is required while the pool charges a performance fee.")]
+    FeeVaultRequired,
+    #[msg("NFT boost is above 10000 basis points or disabled for this collection.")]
+    InvalidNftBoost,
+    #[msg("This position already has an NFT boost.")]
+    NftBoostAlreadyApplied,
+    #[msg("NFT is not a verified member of the approved collection.")]
+    NftNotInCollection,
+    #[msg("Reward vesting period cannot be negative.")]
+    InvalidVestingPeriod,
+    #[msg("The vesting vault and schedule are required while the pool vests rewards.")]
+    VestingAccountsRequired,
+    #[msg("Account is not one of the caller's positions in this pool.")]
+    InvalidPosition,
+    #[msg("Target pool is not this pool's migration target or stakes a different mint.")]
+    InvalidMigrationTarget,
+    #[msg("Withdraw the position's unbonding tokens first.")]
+    UnbondingInProgress,
+    #[msg("Too many open positions for this wallet. Close withdrawn positions first.")]
+    StakeRegistryFull,
+    #[msg("Arithmetic overflow.")]
+    MathOverflow,
+    #[msg("Arithmetic underflow.")]
+    MathUnderflow,
+    #[msg("Unknown compliance mode or scope, or a list switched on without an authority or scope.")]
+    InvalidComplianceConfig,
+    #[msg("This pool requires the wallet's compliance entry account.")]
+    ComplianceEntryRequired,
+    #[msg("Wallet is not permitted by this pool's compliance list.")]
+    WalletNotPermitted,
+    #[msg("Compliance entry belongs to a different wallet.")]
+    ComplianceEntryMismatch,
+    #[msg("Claim interval cannot be negative.")]
+    InvalidClaimInterval,
+    #[msg("Position was claimed too recently; see the log for the earliest allowed time.")]
+    ClaimTooSoon,
+    #[msg("Reward rate is above the maximum allowed.")]
+    InvalidRewardRate,
+    #[msg("Timelock is out of range, or only governance may shorten it.")]
+    InvalidParamsTimelock,
+    #[msg("No reward parameter change is queued.")]
+    NoPendingParams,
+    #[msg("Queued reward parameters are still timelocked.")]
+    ParamsTimelocked,
+    #[msg("Session duration must be positive and no longer than the maximum.")]
+    InvalidSessionDuration,
+    #[msg("Session key has expired.")]
+    SessionKeyExpired,
+    #[msg("Session keys can only pay rewards to the position owner's token accounts.")]
+    SessionPayoutMismatch,
+    #[msg("Governance has not flagged an incident on this pool.")]
+    IncidentExitNotEnabled,
+    #[msg("Rewards for this position must be paid to its reward destination.")]
+    InvalidRewardDestination,
+    #[msg("Freeze duration must be positive and no longer than the maximum.")]
+    InvalidFreezeDuration,
+    #[msg("Position is frozen by governance.")]
+    PositionFrozen,
+    #[msg("Position is not frozen.")]
+    PositionNotFrozen,
+    #[msg("Unbonding tokens are still waiting in the queue; see the log for how many are ahead.")]
+    UnbondingQueued,
+    #[msg("Auto-relock positions roll over through relock instead.")]
+    AutoRelockEnabled,
+    #[msg("The owner must delegate enough stWCT receipts to the pool to cover the position.")]
+    ReceiptDelegationRequired,
+    #[msg("The pool has been retired.")]
+    PoolRetired,
+    #[msg("Retirement cutoff can't be in the past.")]
+    InvalidRetireCutoff,
+    #[msg("The pool hasn't reached its retirement cutoff.")]
+    PoolNotRetired,
+    #[msg("Positions are still open in the pool.")]
+    PoolNotEmpty,
+    #[msg("Tokens per vote and every tier's voting multiplier must be positive, one multiplier per tier.")]
+    InvalidVotingFormula,
+    #[msg("Voting multipliers can only be changed by governance through set_voting_formula.")]
+    VotingFormulaGoverned,
+    #[msg("The pool has no badge program configured, or a different one was passed.")]
+    BadgesDisabled,
+    #[msg("Unknown badge.")]
+    InvalidBadge,
+    #[msg("This badge has already been minted to the wallet.")]
+    BadgeAlreadyMinted,
+    #[msg("The wallet hasn't reached this badge's milestone.")]
+    BadgeMilestoneNotReached,
+    #[msg("A ladder needs 1 to 8 rungs with one amount, duration and position account each, and strictly increasing durations.")]
+    InvalidLadder,
+    #[msg("Deregister the position's governance voting power first.")]
+    VotingPowerRegistered,
+    #[msg("Only the position's owner can deregister its voting power.")]
+    NotPositionOwner,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::solana_program::program_pack::Pack;
+    use anchor_spl::token::spl_token::state::{Account as SplTokenAccount, AccountState};
+    
+    fn token_account(mint: Pubkey, owner: Pubkey, amount: u64) -> TokenAccount {
+        let mut data = [0u8; SplTokenAccount::LEN];
+        SplTokenAccount {
+            mint,
+            owner,
+            amount,
+            state: AccountState::Initialized,
+            ..SplTokenAccount::default()
+        }
+        .pack_into_slice(&mut data);
+        TokenAccount::try_deserialize_unchecked(&mut &data[..]).unwrap()
+    }
+    
+    #[test]
+    fn nft_boost_is_reverified_on_claim() {
+        let nft_mint = Pubkey::new_unique();
+        let holder = Pubkey::new_unique();
+        
+        // Still holding the NFT keeps the boost
+        let held = token_account(nft_mint, holder, 1);
+        assert!(holds_boost_nft(nft_mint, holder, Some(&held)));
+        
+        // Selling it, moving it to another wallet or not passing the account drops the boost
+        let sold = token_account(nft_mint, holder, 0);
+        assert!(!holds_boost_nft(nft_mint, holder, Some(&sold)));
+        let moved = token_account(nft_mint, Pubkey::new_unique(), 1);
+        assert!(!holds_boost_nft(nft_mint, holder, Some(&moved)));
+        let other_nft = token_account(Pubkey::new_unique(), holder, 1);
+        assert!(!holds_boost_nft(nft_mint, holder, Some(&other_nft)));
+        assert!(!holds_boost_nft(nft_mint, holder, None));
+    }
+    
+    // A pool with one position of `amount` tokens at 1x rewards and one vote per 10 tokens
+    fn pool_with_position(amount: u64) -> (StakingPool, UserStake) {
+        let mut staking_pool = StakingPool::deserialize(&mut &[0u8; StakingPool::LEN][..]).unwrap();
+        let mut user_stake = UserStake::deserialize(&mut &[0u8; UserStake::LEN][..]).unwrap();
+        user_stake.stake_amount = amount;
+        user_stake.reward_multiplier_bps = 10000;
+        staking_pool.total_staked = amount;
+        add_reward_weight(&mut staking_pool, amount, 10000).unwrap();
+        set_voting_power(&mut staking_pool, &mut user_stake, amount / 10).unwrap();
+        (staking_pool, user_stake)
+    }
+    
+    #[test]
+    fn slash_leaves_receipts_owed_until_exit() {
+        let (mut staking_pool, mut user_stake) = pool_with_position(1000);
+        
+        let amount = apply_slash(&mut staking_pool, &mut user_stake, 2500, 0).unwrap();
+        assert_eq!(amount, 250);
+        assert_eq!(user_stake.stake_amount, 750);
+        assert_eq!(user_stake.voting_power, 75);
+        assert_eq!(user_stake.slashed_receipts, 250);
+        assert_eq!(staking_pool.total_staked, 750);
+        assert_eq!(staking_pool.total_voting_power, 75);
+        assert_eq!(staking_pool.total_reward_weight, 750 * 10000);
+        
+        // The exit burns receipts for the remaining stake and for the slashed tokens
+        let (amount, receipt_amount) = exit_position(&mut staking_pool, &mut user_stake).unwrap();
+        assert_eq!(amount, 750);
+        assert_eq!(receipt_amount, 1000);
+        assert_eq!(user_stake.slashed_receipts, 0);
+        
+        // A slash too small to take a single token is rejected
+        let (mut staking_pool, mut user_stake) = pool_with_position(100);
+        assert!(apply_slash(&mut staking_pool, &mut user_stake, 1, 0).is_err());
+    }
+    
+    #[test]
+    fn slash_approval_is_bound_to_position_and_share() {
+        let position = Pubkey::new_unique();
+        let action_hash = slash_action_hash(&position, 2500);
+        assert_eq!(action_hash, slash_action_hash(&position, 2500));
+        assert_ne!(action_hash, slash_action_hash(&position, 2501));
+        assert_ne!(action_hash, slash_action_hash(&Pubkey::new_unique(), 2500));
+    }
+    
+    #[test]
+    fn emergency_exit_returns_unbonding_tokens_without_their_receipts() {
+        let (mut staking_pool, mut user_stake) = pool_with_position(1000);
+        reduce_position(&mut staking_pool, &mut user_stake, 400).unwrap();
+        remove_reward_weight(&mut staking_pool, 400, 10000).unwrap();
+        staking_pool.total_staked = 600;
+        user_stake.unbonding_amount = 400;
+        
+        let (amount, receipt_amount) = exit_position(&mut staking_pool, &mut user_stake).unwrap();
+        assert_eq!(amount, 1000);
+        assert_eq!(receipt_amount, 600);
+        assert!(user_stake.withdrawn);
+        assert_eq!(user_stake.stake_amount, 0);
+        assert_eq!(user_stake.unbonding_amount, 0);
+        assert_eq!(user_stake.voting_power, 0);
+        assert_eq!(staking_pool.total_staked, 0);
+        assert_eq!(staking_pool.total_voting_power, 0);
+        assert_eq!(staking_pool.total_reward_weight, 0);
+    }
+    
+    #[test]
+    fn incident_exit_forfeits_pending_reward() {
+        let (mut staking_pool, mut user_stake) = pool_with_position(1000);
+        staking_pool.reward_per_token_stored = 2 * REWARD_PRECISION;
+        user_stake.reward_owed = 5;
+        assert_eq!(pending_reward(&staking_pool, &user_stake).unwrap(), 2005);
+        
+        exit_position(&mut staking_pool, &mut user_stake).unwrap();
+        sync_reward_debt(&staking_pool, &mut user_stake).unwrap();
+        assert_eq!(pending_reward(&staking_pool, &user_stake).unwrap(), 0);
+    }
+    
+    #[test]
+    fn migration_carries_owed_reward_and_settles_slash_debt() {
+        let (mut staking_pool, mut user_stake) = pool_with_position(1000);
+        staking_pool.reward_per_token_stored = REWARD_PRECISION;
+        user_stake.reward_debt = accumulated_reward(&staking_pool, &user_stake).unwrap();
+        
+        // Reward banked before the last balance change moves with the position
+        user_stake.reward_owed = 30;
+        assert_eq!(pending_reward(&staking_pool, &user_stake).unwrap(), 30);
+        
+        user_stake.slashed_receipts = 100;
+        let (amount, receipt_amount) = exit_position(&mut staking_pool, &mut user_stake).unwrap();
+        assert_eq!(amount, 1000);
+        assert_eq!(receipt_amount, 1100);
+    }
 }
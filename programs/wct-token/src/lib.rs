@@ -1,7 +1,14 @@
 // File: programs/wct-token/src/lib.rs
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::{hash, hashv};
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::keccak;
+use anchor_lang::solana_program::program::invoke;
+use anchor_spl::token::spl_token::instruction::AuthorityType;
 use anchor_spl::token::{self, Mint, Token, TokenAccount};
 use anchor_spl::associated_token::AssociatedToken;
+use mpl_token_metadata::state::DataV2;
+use anchor_spl::metadata::{self, CreateMetadataAccountsV3, Metadata, UpdateMetadataAccountsV2};
 
 declare_id!("YOUR_PROGRAM_ID"); // Replace with your actual program ID
 
@@ -9,10 +16,13 @@ declare_id!("YOUR_PROGRAM_ID"); // Replace with your actual program ID
 pub mod wct_token {
     use super::*;
 
-    // Initialize the token with a total supply of 100M
+    // Initialize the token with a total supply of 100M and create its Metaplex metadata
     pub fn initialize_token(
         ctx: Context<InitializeToken>,
         total_supply: u64,
+        name: String,
+        symbol: String,
+        uri: String,
     ) -> Result<()> {
         // Mint the total supply to the authority (deployer) account
         token::mint_to(
@@ -31,14 +41,85 @@ pub mod wct_token {
             total_supply,
         )?;
 
+        // Create the name/symbol/URI metadata wallets and explorers display. The metadata
+        // stays mutable by the update authority passed in.
+        metadata::create_metadata_accounts_v3(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_metadata_program.to_account_info(),
+                CreateMetadataAccountsV3 {
+                    metadata: ctx.accounts.metadata.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    mint_authority: ctx.accounts.mint.to_account_info(),
+                    payer: ctx.accounts.authority.to_account_info(),
+                    update_authority: ctx.accounts.update_authority.to_account_info(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                    rent: ctx.accounts.rent.to_account_info(),
+                },
+                &[&[
+                    b"mint".as_ref(),
+                    &[*ctx.bumps.get("mint").unwrap()],
+                ]],
+            ),
+            DataV2 {
+                name: name.clone(),
+                symbol: symbol.clone(),
+                uri: uri.clone(),
+                seller_fee_basis_points: 0,
+                creators: None,
+                collection: None,
+                uses: None,
+            },
+            true,
+            false,
+            None,
+        )?;
+
+        emit!(TokenMetadataCreatedEvent {
+            mint: ctx.accounts.mint.key(),
+            update_authority: ctx.accounts.update_authority.key(),
+            name,
+            symbol,
+            uri,
+        });
+
+        // Split the supply into the tokenomics buckets; treasury takes any rounding remainder
+        let allocation_config = &mut ctx.accounts.allocation_config;
+        allocation_config.authority = ctx.accounts.authority.key();
+        allocation_config.mint = ctx.accounts.mint.key();
+        allocation_config.total_supply = total_supply;
+        allocation_config.governance_program = Pubkey::default();
+        allocation_config.governance = Pubkey::default();
+        allocation_config.treasury = Pubkey::default();
+        allocation_config.bump = *ctx.bumps.get("allocation_config").unwrap();
+        let mut allocated: u64 = 0;
+        for (bucket, share_bps) in ALLOCATION_SHARES_BPS.iter().enumerate() {
+            let cap = if bucket == ALLOCATION_SHARES_BPS.len() - 1 {
+                total_supply.checked_sub(allocated).unwrap()
+            } else {
+                (total_supply as u128)
+                    .checked_mul(*share_bps as u128)
+                    .unwrap()
+                    .checked_div(10000)
+                    .unwrap() as u64
+            };
+            allocated = allocated.checked_add(cap).unwrap();
+            allocation_config.buckets[bucket] = AllocationBucket { cap, distributed: 0 };
+        }
+
         Ok(())
     }
 
-    // Distribute tokens to initial wallets according to tokenomics
+    // Distribute tokens to initial wallets according to tokenomics, debiting the named bucket
     pub fn distribute_initial_tokens(
         ctx: Context<DistributeTokens>,
+        bucket: u8,
         amount: u64,
     ) -> Result<()> {
+        let allocation_config = &mut ctx.accounts.allocation_config;
+
+        // Never hand out more than the bucket's share of the supply
+        debit_bucket(allocation_config, bucket, amount)?;
+
         // Transfer tokens from authority to the destination account
         token::transfer(
             CpiContext::new(
@@ -52,10 +133,651 @@ pub mod wct_token {
             amount,
         )?;
 
+        emit!(TokensDistributedEvent {
+            bucket,
+            recipient: ctx.accounts.to_token_account.key(),
+            amount,
+            bucket_distributed: allocation_config.buckets[bucket as usize].distributed,
+        });
+
+        Ok(())
+    }
+
+    // Distribute tokens from one bucket to several wallets in a single transaction.
+    // remaining_accounts: the recipients' WCT token accounts, in the same order as `amounts`
+    pub fn distribute_batch<'info>(
+        ctx: Context<'_, '_, '_, 'info, DistributeBatch<'info>>,
+        bucket: u8,
+        amounts: Vec<u64>,
+    ) -> Result<()> {
+        require!(
+            !amounts.is_empty()
+                && amounts.len() <= MAX_BATCH_RECIPIENTS
+                && amounts.len() == ctx.remaining_accounts.len(),
+            TokenError::InvalidBatch
+        );
+
+        // Debit the whole batch up front so it fails before any transfer if the bucket is short
+        let total_amount = amounts
+            .iter()
+            .try_fold(0u64, |total, amount| total.checked_add(*amount))
+            .ok_or(TokenError::MathOverflow)?;
+        let allocation_config = &mut ctx.accounts.allocation_config;
+        debit_bucket(allocation_config, bucket, total_amount)?;
+        let bucket_distributed = allocation_config.buckets[bucket as usize].distributed;
+
+        for (recipient, amount) in ctx.remaining_accounts.iter().zip(amounts.iter()) {
+            // Recipients must be WCT token accounts
+            let recipient_token_account = Account::<TokenAccount>::try_from(recipient)?;
+            require!(
+                recipient_token_account.mint == ctx.accounts.mint.key(),
+                TokenError::InvalidBatch
+            );
+
+            token::transfer(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    token::Transfer {
+                        from: ctx.accounts.from_token_account.to_account_info(),
+                        to: recipient.clone(),
+                        authority: ctx.accounts.authority.to_account_info(),
+                    },
+                ),
+                *amount,
+            )?;
+
+            emit!(TokensDistributedEvent {
+                bucket,
+                recipient: recipient.key(),
+                amount: *amount,
+                bucket_distributed,
+            });
+        }
+
+        Ok(())
+    }
+
+    // Set the governance realm that may revoke vesting schedules and approve metadata updates,
+    // and the treasury token account that receives revoked tokens. The allocation authority can
+    // do this once; after that only the current governance PDA can change it.
+    pub fn set_allocation_governance(
+        ctx: Context<SetAllocationGovernance>,
+        governance_program: Pubkey,
+        governance: Pubkey,
+        treasury: Pubkey,
+    ) -> Result<()> {
+        require!(
+            governance_program != Pubkey::default() && governance != Pubkey::default(),
+            TokenError::InvalidGovernance
+        );
+
+        let allocation_config = &mut ctx.accounts.allocation_config;
+        allocation_config.governance_program = governance_program;
+        allocation_config.governance = governance;
+        allocation_config.treasury = treasury;
+
+        emit!(AllocationGovernanceUpdatedEvent {
+            governance_program,
+            governance,
+            treasury,
+        });
+
+        Ok(())
+    }
+
+    // Lock part of a bucket in an escrow that releases to `beneficiary` linearly from
+    // `start_timestamp` to `end_timestamp`, with nothing claimable before `cliff_timestamp`.
+    // Revocable schedules can have their unvested tokens clawed back by governance.
+    // `schedule_id` tells apart the schedules of one beneficiary.
+    pub fn create_vesting(
+        ctx: Context<CreateVesting>,
+        beneficiary: Pubkey,
+        schedule_id: u64,
+        bucket: u8,
+        amount: u64,
+        start_timestamp: i64,
+        cliff_timestamp: i64,
+        end_timestamp: i64,
+        revocable: bool,
+    ) -> Result<()> {
+        require!(
+            amount > 0
+                && start_timestamp <= cliff_timestamp
+                && cliff_timestamp <= end_timestamp
+                && start_timestamp < end_timestamp,
+            TokenError::InvalidVestingSchedule
+        );
+
+        // Vested tokens count against the bucket when they're locked up
+        debit_bucket(&mut ctx.accounts.allocation_config, bucket, amount)?;
+
+        let vesting_schedule = &mut ctx.accounts.vesting_schedule;
+        vesting_schedule.mint = ctx.accounts.mint.key();
+        vesting_schedule.beneficiary = beneficiary;
+        vesting_schedule.schedule_id = schedule_id;
+        vesting_schedule.escrow = ctx.accounts.escrow.key();
+        vesting_schedule.bucket = bucket;
+        vesting_schedule.total_amount = amount;
+        vesting_schedule.claimed_amount = 0;
+        vesting_schedule.start_timestamp = start_timestamp;
+        vesting_schedule.cliff_timestamp = cliff_timestamp;
+        vesting_schedule.end_timestamp = end_timestamp;
+        vesting_schedule.revocable = revocable;
+        vesting_schedule.revoked_at = 0;
+        vesting_schedule.bump = *ctx.bumps.get("vesting_schedule").unwrap();
+
+        // Move the tokens into the escrow
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.from_token_account.to_account_info(),
+                    to: ctx.accounts.escrow.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        emit!(VestingCreatedEvent {
+            beneficiary,
+            schedule_id,
+            bucket,
+            amount,
+            start_timestamp,
+            cliff_timestamp,
+            end_timestamp,
+            revocable,
+        });
+
+        Ok(())
+    }
+
+    // Release whatever has vested so far to the beneficiary
+    pub fn claim_vested(ctx: Context<ClaimVested>) -> Result<()> {
+        let vesting_schedule = &mut ctx.accounts.vesting_schedule;
+        let now = Clock::get()?.unix_timestamp;
+
+        let amount = vested_amount(vesting_schedule, now)
+            .checked_sub(vesting_schedule.claimed_amount)
+            .unwrap();
+        require!(amount > 0, TokenError::NothingVested);
+        vesting_schedule.claimed_amount = vesting_schedule.claimed_amount.checked_add(amount).unwrap();
+
+        // The escrow is owned by the schedule PDA
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.escrow.to_account_info(),
+                    to: ctx.accounts.beneficiary_token_account.to_account_info(),
+                    authority: vesting_schedule.to_account_info(),
+                },
+                &[&[
+                    b"vesting".as_ref(),
+                    vesting_schedule.mint.as_ref(),
+                    vesting_schedule.beneficiary.as_ref(),
+                    &vesting_schedule.schedule_id.to_le_bytes(),
+                    &[vesting_schedule.bump],
+                ]],
+            ),
+            amount,
+        )?;
+
+        emit!(VestedTokensClaimedEvent {
+            beneficiary: vesting_schedule.beneficiary,
+            schedule_id: vesting_schedule.schedule_id,
+            amount,
+            claimed_amount: vesting_schedule.claimed_amount,
+            total_amount: vesting_schedule.total_amount,
+        });
+
+        Ok(())
+    }
+
+    // Cancel a revocable schedule, returning the unvested tokens to the treasury.
+    // Whatever had vested stays claimable by the beneficiary.
+    pub fn revoke_vesting(ctx: Context<RevokeVesting>) -> Result<()> {
+        let vesting_schedule = &mut ctx.accounts.vesting_schedule;
+        let now = Clock::get()?.unix_timestamp;
+
+        require!(vesting_schedule.revocable, TokenError::VestingNotRevocable);
+        require!(vesting_schedule.revoked_at == 0, TokenError::VestingAlreadyRevoked);
+
+        // Freeze the schedule at what has vested so far
+        let vested = vested_amount(vesting_schedule, now);
+        let unvested = vesting_schedule.total_amount.checked_sub(vested).unwrap();
+        vesting_schedule.total_amount = vested;
+        vesting_schedule.revoked_at = now;
+
+        // The revoked tokens stay charged to the schedule's bucket: they go to the treasury
+        // rather than back to the allocation wallet, so the bucket can't hand them out again
+
+        if unvested > 0 {
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    token::Transfer {
+                        from: ctx.accounts.escrow.to_account_info(),
+                        to: ctx.accounts.treasury.to_account_info(),
+                        authority: vesting_schedule.to_account_info(),
+                    },
+                    &[&[
+                        b"vesting".as_ref(),
+                        vesting_schedule.mint.as_ref(),
+                        vesting_schedule.beneficiary.as_ref(),
+                        &vesting_schedule.schedule_id.to_le_bytes(),
+                        &[vesting_schedule.bump],
+                    ]],
+                ),
+                unvested,
+            )?;
+        }
+
+        emit!(VestingRevokedEvent {
+            beneficiary: vesting_schedule.beneficiary,
+            schedule_id: vesting_schedule.schedule_id,
+            vested_amount: vested,
+            revoked_amount: unvested,
+            revoked_at: now,
+        });
+
+        Ok(())
+    }
+
+    // Fund an airdrop from a bucket and publish the Merkle root of its claims. Each leaf is
+    // keccak(index || wallet || amount); any number of distributions can be open at once.
+    // Claims close at `claim_deadline`, after which governance can sweep what is left.
+    pub fn create_distribution(
+        ctx: Context<CreateDistribution>,
+        distribution_id: u64,
+        num_recipients: u32,
+        bucket: u8,
+        merkle_root: [u8; 32],
+        total_amount: u64,
+        claim_deadline: i64,
+    ) -> Result<()> {
+        require!(
+            num_recipients > 0
+                && num_recipients <= MerkleDistribution::MAX_RECIPIENTS
+                && total_amount > 0,
+            TokenError::InvalidDistribution
+        );
+        require!(
+            claim_deadline > Clock::get()?.unix_timestamp,
+            TokenError::InvalidClaimDeadline
+        );
+
+        // Airdropped tokens count against the bucket when the distribution is funded
+        debit_bucket(&mut ctx.accounts.allocation_config, bucket, total_amount)?;
+
+        let distribution = &mut ctx.accounts.distribution;
+        distribution.mint = ctx.accounts.mint.key();
+        distribution.distribution_id = distribution_id;
+        distribution.vault = ctx.accounts.vault.key();
+        distribution.bucket = bucket;
+        distribution.merkle_root = merkle_root;
+        distribution.total_amount = total_amount;
+        distribution.claimed_amount = 0;
+        distribution.num_recipients = num_recipients;
+        distribution.claimed_count = 0;
+        distribution.claim_deadline = claim_deadline;
+        distribution.bump = *ctx.bumps.get("distribution").unwrap();
+        distribution.claimed_bitmap = vec![0; MerkleDistribution::bitmap_len(num_recipients)];
+
+        // Fund the vault
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.from_token_account.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                },
+            ),
+            total_amount,
+        )?;
+
+        emit!(DistributionCreatedEvent {
+            distribution: distribution.key(),
+            distribution_id,
+            bucket,
+            merkle_root,
+            total_amount,
+            num_recipients,
+            claim_deadline,
+        });
+
+        Ok(())
+    }
+
+    // Claim an airdrop allocation into the claimant's associated token account
+    pub fn claim_airdrop(
+        ctx: Context<ClaimAirdrop>,
+        index: u32,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        let distribution = &mut ctx.accounts.distribution;
+        let claimant = &ctx.accounts.claimant;
+
+        require!(
+            Clock::get()?.unix_timestamp < distribution.claim_deadline,
+            TokenError::AirdropExpired
+        );
+        require!(index < distribution.num_recipients, TokenError::InvalidClaimIndex);
+        require!(!distribution.is_claimed(index), TokenError::AirdropAlreadyClaimed);
+
+        // Verify the claim is part of the published tree
+        let leaf = keccak::hashv(&[
+            &index.to_le_bytes(),
+            claimant.key().as_ref(),
+            &amount.to_le_bytes(),
+        ])
+        .0;
+        require!(
+            verify_merkle_proof(&proof, distribution.merkle_root, leaf),
+            TokenError::InvalidMerkleProof
+        );
+
+        // Never pay out more than was funded, even if the tree is wrong
+        let claimed_amount = distribution.claimed_amount.checked_add(amount).unwrap();
+        require!(claimed_amount <= distribution.total_amount, TokenError::DistributionExhausted);
+
+        distribution.set_claimed(index);
+        distribution.claimed_amount = claimed_amount;
+        distribution.claimed_count = distribution.claimed_count.checked_add(1).unwrap();
+
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.vault.to_account_info(),
+                    to: ctx.accounts.claimant_token_account.to_account_info(),
+                    authority: distribution.to_account_info(),
+                },
+                &[&[
+                    b"distribution".as_ref(),
+                    distribution.mint.as_ref(),
+                    &distribution.distribution_id.to_le_bytes(),
+                    &[distribution.bump],
+                ]],
+            ),
+            amount,
+        )?;
+
+        emit!(AirdropClaimedEvent {
+            distribution: distribution.key(),
+            index,
+            claimant: claimant.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+
+    // Return an expired airdrop's unclaimed tokens to the treasury (governance PDA only).
+    // They stay charged to the distribution's bucket.
+    pub fn sweep_distribution(ctx: Context<SweepDistribution>) -> Result<()> {
+        let distribution = &ctx.accounts.distribution;
+
+        require!(
+            Clock::get()?.unix_timestamp >= distribution.claim_deadline,
+            TokenError::AirdropNotExpired
+        );
+
+        let amount = ctx.accounts.vault.amount;
+        if amount > 0 {
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    token::Transfer {
+                        from: ctx.accounts.vault.to_account_info(),
+                        to: ctx.accounts.treasury.to_account_info(),
+                        authority: distribution.to_account_info(),
+                    },
+                    &[&[
+                        b"distribution".as_ref(),
+                        distribution.mint.as_ref(),
+                        &distribution.distribution_id.to_le_bytes(),
+                        &[distribution.bump],
+                    ]],
+                ),
+                amount,
+            )?;
+        }
+
+        emit!(DistributionSweptEvent {
+            distribution: distribution.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+
+    // Burn tokens from the signer's account and record the burn in the mint's burn stats
+    pub fn burn_tokens(ctx: Context<BurnTokens>, amount: u64) -> Result<()> {
+        require!(amount > 0, TokenError::InvalidBurnAmount);
+
+        token::burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::Burn {
+                    mint: ctx.accounts.mint.to_account_info(),
+                    from: ctx.accounts.from_token_account.to_account_info(),
+                    authority: ctx.accounts.owner.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        let clock = Clock::get()?;
+        let burn_stats = &mut ctx.accounts.burn_stats;
+        burn_stats.mint = ctx.accounts.mint.key();
+        burn_stats.total_burned = burn_stats.total_burned.checked_add(amount).unwrap();
+        burn_stats.burn_count = burn_stats.burn_count.checked_add(1).unwrap();
+        burn_stats.last_burner = ctx.accounts.owner.key();
+        burn_stats.last_burn_amount = amount;
+        burn_stats.last_burn_at = clock.unix_timestamp;
+        burn_stats.bump = *ctx.bumps.get("burn_stats").unwrap();
+
+        emit!(TokensBurnedEvent {
+            burner: ctx.accounts.owner.key(),
+            amount,
+            total_burned: burn_stats.total_burned,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // Permanently revoke the mint authority so no tokens can ever be minted beyond the
+    // genesis supply (allocation authority only, irreversible)
+    pub fn finalize_supply(ctx: Context<FinalizeSupply>) -> Result<()> {
+        let mint = &ctx.accounts.mint;
+
+        require!(mint.mint_authority.is_some(), TokenError::SupplyAlreadyFinalized);
+
+        token::set_authority(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::SetAuthority {
+                    current_authority: mint.to_account_info(),
+                    account_or_mint: mint.to_account_info(),
+                },
+                &[&[
+                    b"mint".as_ref(),
+                    &[*ctx.bumps.get("mint").unwrap()],
+                ]],
+            ),
+            AuthorityType::MintTokens,
+            None,
+        )?;
+
+        emit!(SupplyFinalizedEvent {
+            mint: mint.key(),
+            supply: mint.supply,
+            finalized_by: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // Change the token's name, symbol and URI as approved by an executed governance proposal.
+    // The metadata's update authority must be the mint PDA. Anyone can apply an approved update,
+    // and each proposal can only be applied once.
+    pub fn update_metadata(
+        ctx: Context<UpdateMetadata>,
+        name: String,
+        symbol: String,
+        uri: String,
+    ) -> Result<()> {
+        let mint = &ctx.accounts.mint;
+
+        // assert_proposal_approved(Other, action_hash) fails unless the proposal was executed
+        // and approved exactly this update, as carried in its UpdateTokenMetadata payload
+        let action_hash = metadata_action_hash(&mint.key(), &name, &symbol, &uri);
+        let mut data = hash(b"global:assert_proposal_approved").to_bytes()[..8].to_vec();
+        data.push(OTHER_PROPOSAL_TYPE);
+        data.extend_from_slice(&action_hash);
+
+        let ix = Instruction {
+            program_id: ctx.accounts.governance_program.key(),
+            accounts: vec![
+                AccountMeta::new_readonly(ctx.accounts.governance.key(), false),
+                AccountMeta::new_readonly(ctx.accounts.proposal.key(), false),
+            ],
+            data,
+        };
+        invoke(
+            &ix,
+            &[
+                ctx.accounts.governance.to_account_info(),
+                ctx.accounts.proposal.to_account_info(),
+            ],
+        )?;
+
+        metadata::update_metadata_accounts_v2(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_metadata_program.to_account_info(),
+                UpdateMetadataAccountsV2 {
+                    metadata: ctx.accounts.metadata.to_account_info(),
+                    update_authority: mint.to_account_info(),
+                },
+                &[&[
+                    b"mint".as_ref(),
+                    &[*ctx.bumps.get("mint").unwrap()],
+                ]],
+            ),
+            None,
+            Some(DataV2 {
+                name: name.clone(),
+                symbol: symbol.clone(),
+                uri: uri.clone(),
+                seller_fee_basis_points: 0,
+                creators: None,
+                collection: None,
+                uses: None,
+            }),
+            None,
+            None,
+        )?;
+
+        let clock = Clock::get()?;
+        let receipt = &mut ctx.accounts.receipt;
+        receipt.proposal = ctx.accounts.proposal.key();
+        receipt.applied_at = clock.unix_timestamp;
+        receipt.bump = *ctx.bumps.get("receipt").unwrap();
+
+        emit!(MetadataUpdatedEvent {
+            proposal: ctx.accounts.proposal.key(),
+            name,
+            symbol,
+            uri,
+            timestamp: clock.unix_timestamp,
+        });
+
         Ok(())
     }
 }
 
+// Allocation buckets, in the order of AllocationConfig.buckets
+pub const BUCKET_COMMUNITY: u8 = 0;
+pub const BUCKET_DEVELOPMENT: u8 = 1;
+pub const BUCKET_TEAM: u8 = 2;
+pub const BUCKET_LIQUIDITY: u8 = 3;
+pub const BUCKET_TREASURY: u8 = 4;
+
+// Each bucket's share of the total supply in basis points: 60/15/10/10/5%
+pub const ALLOCATION_SHARES_BPS: [u16; 5] = [6000, 1500, 1000, 1000, 500];
+
+// Most recipients distribute_batch accepts, keeping the transaction within account limits
+pub const MAX_BATCH_RECIPIENTS: usize = 20;
+
+// Borsh index of ProposalType::Other in the governance program, the type metadata update
+// proposals (ProposalPayload::UpdateTokenMetadata) are created with
+pub const OTHER_PROPOSAL_TYPE: u8 = 2;
+
+// Helper function to compute how much of a schedule has vested by `now`
+fn vested_amount(vesting_schedule: &VestingSchedule, now: i64) -> u64 {
+    // A revoked schedule's total was cut down to what had vested when it was revoked
+    if vesting_schedule.revoked_at != 0 {
+        return vesting_schedule.total_amount;
+    }
+    if now < vesting_schedule.cliff_timestamp {
+        return 0;
+    }
+    if now >= vesting_schedule.end_timestamp {
+        return vesting_schedule.total_amount;
+    }
+    (vesting_schedule.total_amount as u128)
+        .checked_mul((now - vesting_schedule.start_timestamp) as u128)
+        .unwrap()
+        .checked_div((vesting_schedule.end_timestamp - vesting_schedule.start_timestamp) as u128)
+        .unwrap() as u64
+}
+
+// Helper function to compute the action hash a governance proposal must carry to approve
+// a metadata update
+fn metadata_action_hash(mint: &Pubkey, name: &str, symbol: &str, uri: &str) -> [u8; 32] {
+    hashv(&[
+        b"update_metadata".as_ref(),
+        mint.as_ref(),
+        name.as_bytes(),
+        &[0],
+        symbol.as_bytes(),
+        &[0],
+        uri.as_bytes(),
+    ])
+    .to_bytes()
+}
+
+// Helper function to verify a Merkle proof using sorted-pair keccak hashing
+fn verify_merkle_proof(proof: &[[u8; 32]], root: [u8; 32], leaf: [u8; 32]) -> bool {
+    let mut computed_hash = leaf;
+    for proof_element in proof {
+        computed_hash = if computed_hash <= *proof_element {
+            keccak::hashv(&[&computed_hash, proof_element]).0
+        } else {
+            keccak::hashv(&[proof_element, &computed_hash]).0
+        };
+    }
+    computed_hash == root
+}
+
+// Helper function to count `amount` against a bucket, failing if it would exceed the bucket's cap
+fn debit_bucket(allocation_config: &mut AllocationConfig, bucket: u8, amount: u64) -> Result<()> {
+    let bucket = allocation_config
+        .buckets
+        .get_mut(bucket as usize)
+        .ok_or(TokenError::InvalidBucket)?;
+    let distributed = bucket.distributed.checked_add(amount).unwrap();
+    require!(distributed <= bucket.cap, TokenError::BucketCapExceeded);
+    bucket.distributed = distributed;
+    Ok(())
+}
+
 #[derive(Accounts)]
 pub struct InitializeToken<'info> {
     #[account(
@@ -76,12 +798,34 @@ pub struct InitializeToken<'info> {
     )]
     pub authority_token_account: Account<'info, TokenAccount>,
     
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + AllocationConfig::LEN,
+        seeds = [b"allocation_config", mint.key().as_ref()],
+        bump,
+    )]
+    pub allocation_config: Account<'info, AllocationConfig>,
+    
+    /// CHECK: The mint's metadata PDA, created by the Token Metadata program
+    #[account(
+        mut,
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), mint.key().as_ref()],
+        bump,
+        seeds::program = token_metadata_program.key(),
+    )]
+    pub metadata: UncheckedAccount<'info>,
+    
+    /// CHECK: Any account may be given update authority over the metadata
+    pub update_authority: UncheckedAccount<'info>,
+    
     #[account(mut)]
     pub authority: Signer<'info>,
     
     pub system_program: Program<'info, System>,
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_metadata_program: Program<'info, Metadata>,
     pub rent: Sysvar<'info, Rent>,
 }
 
@@ -89,6 +833,14 @@ pub struct InitializeToken<'info> {
 pub struct DistributeTokens<'info> {
     pub mint: Account<'info, Mint>,
     
+    #[account(
+        mut,
+        seeds = [b"allocation_config", mint.key().as_ref()],
+        bump = allocation_config.bump,
+        constraint = allocation_config.authority == authority.key() @ TokenError::Unauthorized,
+    )]
+    pub allocation_config: Account<'info, AllocationConfig>,
+    
     #[account(
         mut,
         constraint = from_token_account.mint == mint.key(),
@@ -106,26 +858,748 @@ pub struct DistributeTokens<'info> {
     pub token_program: Program<'info, Token>,
 }
 
-// File: scripts/deploy.ts
-import * as anchor from '@project-serum/anchor';
-import { Program } from '@project-serum/anchor';
-import {
-  createAssociatedTokenAccount,
-  getAssociatedTokenAddress,
-  TOKEN_PROGRAM_ID,
-  ASSOCIATED_TOKEN_PROGRAM_ID,
-} from '@solana/spl-token';
-import { WctToken } from '../target/types/wct_token';
-
-async function main() {
-  // Configure the client to use the local cluster
-  const provider = anchor.Provider.env();
-  anchor.setProvider(provider);
+#[derive(Accounts)]
+pub struct DistributeBatch<'info> {
+    pub mint: Account<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"allocation_config", mint.key().as_ref()],
+        bump = allocation_config.bump,
+        constraint = allocation_config.authority == authority.key() @ TokenError::Unauthorized,
+    )]
+    pub allocation_config: Account<'info, AllocationConfig>,
+    
+    #[account(
+        mut,
+        constraint = from_token_account.mint == mint.key(),
+        constraint = from_token_account.owner == authority.key(),
+    )]
+    pub from_token_account: Account<'info, TokenAccount>,
+    
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
 
-  const program = anchor.workspace.WctToken as Program<WctToken>;
-  const authority = provider.wallet.publicKey;
+#[derive(Accounts)]
+pub struct SetAllocationGovernance<'info> {
+    pub mint: Account<'info, Mint>,
+    
+    // The allocation authority while no governance is set, afterwards only the governance PDA
+    #[account(
+        mut,
+        seeds = [b"allocation_config", mint.key().as_ref()],
+        bump = allocation_config.bump,
+        constraint = if allocation_config.governance == Pubkey::default() {
+            allocation_config.authority == authority.key()
+        } else {
+            allocation_config.governance == authority.key()
+        } @ TokenError::Unauthorized,
+    )]
+    pub allocation_config: Account<'info, AllocationConfig>,
+    
+    pub authority: Signer<'info>,
+}
 
-  // Derive PDA for the mint
+#[derive(Accounts)]
+#[instruction(beneficiary: Pubkey, schedule_id: u64)]
+pub struct CreateVesting<'info> {
+    pub mint: Account<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"allocation_config", mint.key().as_ref()],
+        bump = allocation_config.bump,
+        constraint = allocation_config.authority == authority.key() @ TokenError::Unauthorized,
+    )]
+    pub allocation_config: Account<'info, AllocationConfig>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + VestingSchedule::LEN,
+        seeds = [b"vesting", mint.key().as_ref(), beneficiary.as_ref(), &schedule_id.to_le_bytes()],
+        bump,
+    )]
+    pub vesting_schedule: Account<'info, VestingSchedule>,
+    
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"vesting_escrow", vesting_schedule.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = vesting_schedule,
+    )]
+    pub escrow: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = from_token_account.mint == mint.key(),
+        constraint = from_token_account.owner == authority.key(),
+    )]
+    pub from_token_account: Account<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimVested<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"vesting",
+            vesting_schedule.mint.as_ref(),
+            beneficiary.key().as_ref(),
+            &vesting_schedule.schedule_id.to_le_bytes()
+        ],
+        bump = vesting_schedule.bump,
+    )]
+    pub vesting_schedule: Account<'info, VestingSchedule>,
+    
+    #[account(
+        mut,
+        constraint = escrow.key() == vesting_schedule.escrow,
+    )]
+    pub escrow: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = beneficiary_token_account.mint == vesting_schedule.mint,
+    )]
+    pub beneficiary_token_account: Account<'info, TokenAccount>,
+    
+    pub beneficiary: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeVesting<'info> {
+    #[account(
+        seeds = [b"allocation_config", vesting_schedule.mint.as_ref()],
+        bump = allocation_config.bump,
+        constraint = allocation_config.governance != Pubkey::default()
+            && allocation_config.governance == governance.key() @ TokenError::Unauthorized,
+    )]
+    pub allocation_config: Account<'info, AllocationConfig>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"vesting",
+            vesting_schedule.mint.as_ref(),
+            vesting_schedule.beneficiary.as_ref(),
+            &vesting_schedule.schedule_id.to_le_bytes()
+        ],
+        bump = vesting_schedule.bump,
+    )]
+    pub vesting_schedule: Account<'info, VestingSchedule>,
+    
+    #[account(
+        mut,
+        constraint = escrow.key() == vesting_schedule.escrow,
+    )]
+    pub escrow: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = treasury.key() == allocation_config.treasury,
+    )]
+    pub treasury: Account<'info, TokenAccount>,
+    
+    // Governance PDA, signing through an executed proposal
+    pub governance: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(distribution_id: u64, num_recipients: u32)]
+pub struct CreateDistribution<'info> {
+    pub mint: Account<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"allocation_config", mint.key().as_ref()],
+        bump = allocation_config.bump,
+        constraint = allocation_config.authority == authority.key() @ TokenError::Unauthorized,
+    )]
+    pub allocation_config: Account<'info, AllocationConfig>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + MerkleDistribution::LEN + MerkleDistribution::bitmap_len(num_recipients),
+        seeds = [b"distribution", mint.key().as_ref(), &distribution_id.to_le_bytes()],
+        bump,
+    )]
+    pub distribution: Account<'info, MerkleDistribution>,
+    
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"distribution_vault", distribution.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = distribution,
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = from_token_account.mint == mint.key(),
+        constraint = from_token_account.owner == authority.key(),
+    )]
+    pub from_token_account: Account<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimAirdrop<'info> {
+    #[account(address = distribution.mint)]
+    pub mint: Account<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"distribution", mint.key().as_ref(), &distribution.distribution_id.to_le_bytes()],
+        bump = distribution.bump,
+    )]
+    pub distribution: Account<'info, MerkleDistribution>,
+    
+    #[account(
+        mut,
+        constraint = vault.key() == distribution.vault,
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        init_if_needed,
+        payer = claimant,
+        associated_token::mint = mint,
+        associated_token::authority = claimant,
+    )]
+    pub claimant_token_account: Account<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub claimant: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct SweepDistribution<'info> {
+    #[account(
+        seeds = [b"allocation_config", distribution.mint.as_ref()],
+        bump = allocation_config.bump,
+        constraint = allocation_config.governance != Pubkey::default()
+            && allocation_config.governance == governance.key() @ TokenError::Unauthorized,
+    )]
+    pub allocation_config: Account<'info, AllocationConfig>,
+    
+    #[account(
+        seeds = [b"distribution", distribution.mint.as_ref(), &distribution.distribution_id.to_le_bytes()],
+        bump = distribution.bump,
+    )]
+    pub distribution: Account<'info, MerkleDistribution>,
+    
+    #[account(
+        mut,
+        constraint = vault.key() == distribution.vault,
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = treasury.key() == allocation_config.treasury,
+    )]
+    pub treasury: Account<'info, TokenAccount>,
+    
+    // Governance PDA, signing through an executed proposal
+    pub governance: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct BurnTokens<'info> {
+    #[account(mut)]
+    pub mint: Account<'info, Mint>,
+    
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + BurnStats::LEN,
+        seeds = [b"burn_stats", mint.key().as_ref()],
+        bump,
+    )]
+    pub burn_stats: Account<'info, BurnStats>,
+    
+    #[account(
+        mut,
+        constraint = from_token_account.mint == mint.key(),
+        constraint = from_token_account.owner == owner.key(),
+    )]
+    pub from_token_account: Account<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeSupply<'info> {
+    #[account(
+        mut,
+        seeds = [b"mint"],
+        bump,
+    )]
+    pub mint: Account<'info, Mint>,
+    
+    #[account(
+        seeds = [b"allocation_config", mint.key().as_ref()],
+        bump = allocation_config.bump,
+        constraint = allocation_config.authority == authority.key() @ TokenError::Unauthorized,
+    )]
+    pub allocation_config: Account<'info, AllocationConfig>,
+    
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateMetadata<'info> {
+    #[account(
+        seeds = [b"mint"],
+        bump,
+    )]
+    pub mint: Account<'info, Mint>,
+    
+    #[account(
+        seeds = [b"allocation_config", mint.key().as_ref()],
+        bump = allocation_config.bump,
+        constraint = allocation_config.governance != Pubkey::default()
+            && allocation_config.governance == governance.key() @ TokenError::Unauthorized,
+        constraint = allocation_config.governance_program == governance_program.key() @ TokenError::Unauthorized,
+    )]
+    pub allocation_config: Account<'info, AllocationConfig>,
+    
+    /// CHECK: The mint's metadata PDA, owned by the Token Metadata program
+    #[account(
+        mut,
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), mint.key().as_ref()],
+        bump,
+        seeds::program = token_metadata_program.key(),
+    )]
+    pub metadata: UncheckedAccount<'info>,
+    
+    /// CHECK: Governance program, verified against the allocation config
+    #[account(executable)]
+    pub governance_program: UncheckedAccount<'info>,
+    
+    /// CHECK: Governance realm, verified against the allocation config
+    #[account(
+        constraint = *governance.owner == governance_program.key() @ TokenError::Unauthorized,
+    )]
+    pub governance: UncheckedAccount<'info>,
+    
+    /// CHECK: The approving proposal, validated by the governance program
+    pub proposal: UncheckedAccount<'info>,
+    
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + MetadataUpdateReceipt::LEN,
+        seeds = [b"metadata_update", proposal.key().as_ref()],
+        bump,
+    )]
+    pub receipt: Account<'info, MetadataUpdateReceipt>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+    pub token_metadata_program: Program<'info, Metadata>,
+}
+
+#[account]
+pub struct AllocationConfig {
+    pub authority: Pubkey,                 // Wallet holding the genesis supply
+    pub mint: Pubkey,                      // WCT mint
+    pub total_supply: u64,                 // Supply minted at initialization
+    pub buckets: [AllocationBucket; 5],    // Community, development, team, liquidity, treasury
+    pub governance: Pubkey,                // Governance PDA allowed to revoke vesting
+    pub treasury: Pubkey,                  // Token account receiving revoked vesting and swept airdrops
+    pub governance_program: Pubkey,        // Program owning the governance realm
+    pub bump: u8,                          // PDA bump
+}
+
+impl AllocationConfig {
+    pub const LEN: usize = 32 + 32 + 8 + AllocationBucket::LEN * 5 + 32 + 32 + 32 + 1;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct AllocationBucket {
+    pub cap: u64,                          // Most tokens the bucket may distribute
+    pub distributed: u64,                  // Tokens distributed from the bucket so far
+}
+
+impl AllocationBucket {
+    pub const LEN: usize = 8 + 8;
+}
+
+#[account]
+pub struct VestingSchedule {
+    pub mint: Pubkey,                      // WCT mint
+    pub beneficiary: Pubkey,               // Wallet the tokens vest to
+    pub schedule_id: u64,                  // Identifier chosen by the allocation authority, unique per beneficiary
+    pub escrow: Pubkey,                    // Token account holding the unvested tokens
+    pub bucket: u8,                        // Allocation bucket the tokens came from
+    pub total_amount: u64,                 // Tokens locked in the schedule
+    pub claimed_amount: u64,               // Tokens released to the beneficiary so far
+    pub start_timestamp: i64,              // When vesting starts
+    pub cliff_timestamp: i64,              // Nothing can be claimed before this
+    pub end_timestamp: i64,                // When everything has vested
+    pub revocable: bool,                   // Whether governance can claw back unvested tokens
+    pub revoked_at: i64,                   // When the schedule was revoked (0 if never)
+    pub bump: u8,                          // PDA bump
+}
+
+impl VestingSchedule {
+    pub const LEN: usize = 32 + 32 + 8 + 32 + 1 + 8 + 8 + 8 + 8 + 8 + 1 + 8 + 1;
+}
+
+#[account]
+pub struct MerkleDistribution {
+    pub mint: Pubkey,                      // WCT mint
+    pub distribution_id: u64,              // Identifier chosen by the allocation authority
+    pub vault: Pubkey,                     // Token account holding the unclaimed airdrop
+    pub bucket: u8,                        // Allocation bucket that funded the airdrop
+    pub merkle_root: [u8; 32],             // Root of keccak(index || wallet || amount) leaves
+    pub total_amount: u64,                 // Tokens funded into the vault
+    pub claimed_amount: u64,               // Tokens claimed so far
+    pub num_recipients: u32,               // Leaves in the tree
+    pub claimed_count: u32,                // Leaves claimed so far
+    pub claim_deadline: i64,               // Claims close at this time and the rest can be swept
+    pub bump: u8,                          // PDA bump
+    pub claimed_bitmap: Vec<u8>,           // One bit per leaf index, set once claimed
+}
+
+impl MerkleDistribution {
+    // Keeps the bitmap within the 10KB limit for accounts created through CPI
+    pub const MAX_RECIPIENTS: u32 = 64_000;
+    // Size without the bitmap bytes
+    pub const LEN: usize = 32 + 8 + 32 + 1 + 32 + 8 + 8 + 4 + 4 + 8 + 1 + 4;
+
+    pub fn bitmap_len(num_recipients: u32) -> usize {
+        (num_recipients as usize + 7) / 8
+    }
+
+    pub fn is_claimed(&self, index: u32) -> bool {
+        self.claimed_bitmap[index as usize / 8] & (1 << (index % 8)) != 0
+    }
+
+    pub fn set_claimed(&mut self, index: u32) {
+        self.claimed_bitmap[index as usize / 8] |= 1 << (index % 8);
+    }
+}
+
+#[account]
+pub struct BurnStats {
+    pub mint: Pubkey,                      // WCT mint
+    pub total_burned: u64,                 // Tokens burned through burn_tokens
+    pub burn_count: u64,                   // Number of burns
+    pub last_burner: Pubkey,               // Wallet behind the most recent burn
+    pub last_burn_amount: u64,             // Size of the most recent burn
+    pub last_burn_at: i64,                 // When the most recent burn happened
+    pub bump: u8,                          // PDA bump
+}
+
+impl BurnStats {
+    pub const LEN: usize = 32 + 8 + 8 + 32 + 8 + 8 + 1;
+}
+
+#[account]
+pub struct MetadataUpdateReceipt {
+    pub proposal: Pubkey,                  // Proposal whose approval was applied
+    pub applied_at: i64,                   // When the update was applied
+    pub bump: u8,                          // PDA bump
+}
+
+impl MetadataUpdateReceipt {
+    pub const LEN: usize = 32 + 8 + 1;
+}
+
+#[event]
+pub struct MetadataUpdatedEvent {
+    pub proposal: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TokenMetadataCreatedEvent {
+    pub mint: Pubkey,
+    pub update_authority: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+}
+
+#[event]
+pub struct TokensDistributedEvent {
+    pub bucket: u8,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub bucket_distributed: u64,
+}
+
+#[error_code]
+pub enum TokenError {
+    #[msg("Unknown allocation bucket.")]
+    InvalidBucket,
+    #[msg("Distribution would exceed the bucket's share of the supply.")]
+    BucketCapExceeded,
+    #[msg("Signer is not the allocation authority.")]
+    Unauthorized,
+    #[msg("Vesting needs a positive amount and start <= cliff <= end, with start before end.")]
+    InvalidVestingSchedule,
+    #[msg("Nothing has vested since the last claim.")]
+    NothingVested,
+    #[msg("Vesting schedule is not revocable.")]
+    VestingNotRevocable,
+    #[msg("Vesting schedule has already been revoked.")]
+    VestingAlreadyRevoked,
+    #[msg("Distribution needs between 1 and 64000 recipients and a positive amount.")]
+    InvalidDistribution,
+    #[msg("Claim index is outside the distribution.")]
+    InvalidClaimIndex,
+    #[msg("Airdrop allocation has already been claimed.")]
+    AirdropAlreadyClaimed,
+    #[msg("Invalid Merkle proof.")]
+    InvalidMerkleProof,
+    #[msg("Claim would exceed the tokens funded into the distribution.")]
+    DistributionExhausted,
+    #[msg("Claim deadline must be in the future.")]
+    InvalidClaimDeadline,
+    #[msg("Airdrop claims have closed.")]
+    AirdropExpired,
+    #[msg("Airdrop claims are still open.")]
+    AirdropNotExpired,
+    #[msg("Burn amount must be positive.")]
+    InvalidBurnAmount,
+    #[msg("Supply has already been finalized.")]
+    SupplyAlreadyFinalized,
+    #[msg("Batch needs 1 to 20 amounts, one per recipient WCT token account.")]
+    InvalidBatch,
+    #[msg("Governance program and realm must be set.")]
+    InvalidGovernance,
+    #[msg("Arithmetic overflow.")]
+    MathOverflow,
+}
+
+#[event]
+pub struct VestingCreatedEvent {
+    pub beneficiary: Pubkey,
+    pub schedule_id: u64,
+    pub bucket: u8,
+    pub amount: u64,
+    pub start_timestamp: i64,
+    pub cliff_timestamp: i64,
+    pub end_timestamp: i64,
+    pub revocable: bool,
+}
+
+#[event]
+pub struct VestedTokensClaimedEvent {
+    pub beneficiary: Pubkey,
+    pub schedule_id: u64,
+    pub amount: u64,
+    pub claimed_amount: u64,
+    pub total_amount: u64,
+}
+
+#[event]
+pub struct VestingRevokedEvent {
+    pub beneficiary: Pubkey,
+    pub schedule_id: u64,
+    pub vested_amount: u64,
+    pub revoked_amount: u64,
+    pub revoked_at: i64,
+}
+
+#[event]
+pub struct DistributionCreatedEvent {
+    pub distribution: Pubkey,
+    pub distribution_id: u64,
+    pub bucket: u8,
+    pub merkle_root: [u8; 32],
+    pub total_amount: u64,
+    pub num_recipients: u32,
+    pub claim_deadline: i64,
+}
+
+#[event]
+pub struct AirdropClaimedEvent {
+    pub distribution: Pubkey,
+    pub index: u32,
+    pub claimant: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct DistributionSweptEvent {
+    pub distribution: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct TokensBurnedEvent {
+    pub burner: Pubkey,
+    pub amount: u64,
+    pub total_burned: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SupplyFinalizedEvent {
+    pub mint: Pubkey,
+    pub supply: u64,
+    pub finalized_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AllocationGovernanceUpdatedEvent {
+    pub governance_program: Pubkey,
+    pub governance: Pubkey,
+    pub treasury: Pubkey,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vesting_schedule(total_amount: u64) -> VestingSchedule {
+        let mut vesting_schedule = VestingSchedule::deserialize(&mut &[0u8; VestingSchedule::LEN][..]).unwrap();
+        vesting_schedule.total_amount = total_amount;
+        vesting_schedule.start_timestamp = 1000;
+        vesting_schedule.cliff_timestamp = 1250;
+        vesting_schedule.end_timestamp = 2000;
+        vesting_schedule.revocable = true;
+        vesting_schedule
+    }
+
+    fn leaf(index: u32, wallet: &Pubkey, amount: u64) -> [u8; 32] {
+        keccak::hashv(&[&index.to_le_bytes(), wallet.as_ref(), &amount.to_le_bytes()]).0
+    }
+
+    fn parent(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+        if a <= b {
+            keccak::hashv(&[&a, &b]).0
+        } else {
+            keccak::hashv(&[&b, &a]).0
+        }
+    }
+
+    #[test]
+    fn vesting_unlocks_linearly_after_the_cliff() {
+        let vesting_schedule = vesting_schedule(1000);
+        assert_eq!(vested_amount(&vesting_schedule, 1249), 0);
+        assert_eq!(vested_amount(&vesting_schedule, 1250), 250);
+        assert_eq!(vested_amount(&vesting_schedule, 1500), 500);
+        assert_eq!(vested_amount(&vesting_schedule, 2000), 1000);
+        assert_eq!(vested_amount(&vesting_schedule, 5000), 1000);
+    }
+
+    #[test]
+    fn revoked_vesting_stops_at_what_had_vested() {
+        let mut vesting_schedule = vesting_schedule(1000);
+
+        // revoke_vesting cuts the total down to what has vested and records the time
+        vesting_schedule.total_amount = vested_amount(&vesting_schedule, 1500);
+        vesting_schedule.revoked_at = 1500;
+        assert_eq!(vested_amount(&vesting_schedule, 1500), 500);
+        assert_eq!(vested_amount(&vesting_schedule, 5000), 500);
+    }
+
+    #[test]
+    fn bucket_cap_counts_everything_handed_out() {
+        let mut allocation_config = AllocationConfig::deserialize(&mut &[0u8; AllocationConfig::LEN][..]).unwrap();
+        allocation_config.buckets[0].cap = 1000;
+
+        debit_bucket(&mut allocation_config, 0, 600).unwrap();
+        assert!(debit_bucket(&mut allocation_config, 0, 401).is_err());
+        debit_bucket(&mut allocation_config, 0, 400).unwrap();
+        assert_eq!(allocation_config.buckets[0].distributed, 1000);
+        assert!(debit_bucket(&mut allocation_config, 5, 1).is_err());
+    }
+
+    #[test]
+    fn airdrop_claims_need_a_proof_and_are_claimed_once() {
+        let alice = Pubkey::new_unique();
+        let bob = Pubkey::new_unique();
+        let alice_leaf = leaf(0, &alice, 100);
+        let bob_leaf = leaf(1, &bob, 200);
+        let root = parent(alice_leaf, bob_leaf);
+
+        assert!(verify_merkle_proof(&[bob_leaf], root, alice_leaf));
+        assert!(verify_merkle_proof(&[alice_leaf], root, bob_leaf));
+
+        // Claiming more, or as someone else, doesn't match a leaf in the tree
+        assert!(!verify_merkle_proof(&[bob_leaf], root, leaf(0, &alice, 101)));
+        assert!(!verify_merkle_proof(&[bob_leaf], root, leaf(0, &bob, 100)));
+
+        let mut distribution = MerkleDistribution::deserialize(&mut &[0u8; MerkleDistribution::LEN][..]).unwrap();
+        distribution.num_recipients = 10;
+        distribution.claimed_bitmap = vec![0; MerkleDistribution::bitmap_len(10)];
+        assert_eq!(distribution.claimed_bitmap.len(), 2);
+        distribution.set_claimed(9);
+        assert!(distribution.is_claimed(9));
+        assert!(!distribution.is_claimed(1));
+        assert!(!distribution.is_claimed(8));
+    }
+}
+
+// File: scripts/deploy.ts
+import * as anchor from '@project-serum/anchor';
+import { Program } from '@project-serum/anchor';
+import {
+  createAssociatedTokenAccount,
+  getAssociatedTokenAddress,
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+} from '@solana/spl-token';
+import { WctToken } from '../target/types/wct_token';
+
+const TOKEN_METADATA_PROGRAM_ID = new anchor.web3.PublicKey(
+  'metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s'
+);
+
+async function main() {
+  // Configure the client to use the local cluster
+  const provider = anchor.Provider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.WctToken as Program<WctToken>;
+  const authority = provider.wallet.publicKey;
+
+  // Derive PDA for the mint
   const [mint, mintBump] = await anchor.web3.PublicKey.findProgramAddress(
     [Buffer.from('mint')],
     program.programId
@@ -133,6 +1607,18 @@ async function main() {
 
   console.log('Mint address:', mint.toString());
 
+  // Derive PDA for the allocation buckets
+  const [allocationConfig] = await anchor.web3.PublicKey.findProgramAddress(
+    [Buffer.from('allocation_config'), mint.toBuffer()],
+    program.programId
+  );
+
+  // Derive the Metaplex metadata PDA for the mint
+  const [metadata] = await anchor.web3.PublicKey.findProgramAddress(
+    [Buffer.from('metadata'), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer()],
+    TOKEN_METADATA_PROGRAM_ID
+  );
+
   // Initialize the token with total supply of 100M tokens
   // With 9 decimals, 100M tokens = 100,000,000 * 10^9
   const totalSupply = new anchor.BN(100_000_000).mul(new anchor.BN(10 ** 9));
@@ -140,7 +1626,7 @@ async function main() {
   console.log('Initializing token with total supply:', totalSupply.toString());
   
   await program.methods
-    .initializeToken(totalSupply)
+    .initializeToken(totalSupply, 'Wiki Contribution Token', 'WCT', 'YOUR_METADATA_URI') // Replace with your hosted metadata JSON
     .accounts({
       mint,
       authority,
@@ -149,9 +1635,14 @@ async function main() {
         authority,
         false
       ),
+      allocationConfig,
+      metadata,
+      // The mint PDA holds update authority so governance can change the metadata by vote
+      updateAuthority: mint,
       systemProgram: anchor.web3.SystemProgram.programId,
       tokenProgram: TOKEN_PROGRAM_ID,
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
       rent: anchor.web3.SYSVAR_RENT_PUBKEY,
     })
     .rpc();
@@ -177,15 +1668,17 @@ async function main() {
     communityWallet
   );
   
-  // 60% of total supply
+  // 60% of total supply, the whole community bucket
   const communityAmount = totalSupply.mul(new anchor.BN(60)).div(new anchor.BN(100));
+  const COMMUNITY_BUCKET = 0;
   
   console.log('Distributing to community wallet:', communityAmount.toString());
   
   await program.methods
-    .distributeInitialTokens(communityAmount)
+    .distributeInitialTokens(COMMUNITY_BUCKET, communityAmount)
     .accounts({
       mint,
+      allocationConfig,
       fromTokenAccount: await getAssociatedTokenAddress(
         mint,
         authority,
@@ -2,7 +2,6 @@
 import * as anchor from '@project-serum/anchor';
 import { Program } from '@project-serum/anchor';
 import {
-  createAssociatedTokenAccount,
   getAssociatedTokenAddress,
   TOKEN_PROGRAM_ID,
   ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -45,87 +44,58 @@ async function main() {
     }
   }
 
-  // Find the staking pool PDA
+  // Find the staking pool PDA (pool 0 is the main pool for the mint)
+  const poolId = new anchor.BN(0);
   const [stakingPoolPDA] = await anchor.web3.PublicKey.findProgramAddress(
     [
       Buffer.from('staking_pool'),
       tokenMint.toBuffer(),
+      poolId.toArrayLike(Buffer, 'le', 8),
     ],
     program.programId
   );
   
   console.log('Staking Pool PDA:', stakingPoolPDA.toString());
 
-  // Find the staking vault PDA
-  const [stakingVaultPDA] = await anchor.web3.PublicKey.findProgramAddress(
-    [
-      Buffer.from('stake_vault'),
-      tokenMint.toBuffer(),
-      stakingPoolPDA.toBuffer(),
-    ],
+  // The pool creates its vaults and receipt mint during initialize
+  const stakingVault = await getAssociatedTokenAddress(tokenMint, stakingPoolPDA, true);
+  const [treasuryTokenAccount] = await anchor.web3.PublicKey.findProgramAddress(
+    [Buffer.from('treasury'), stakingPoolPDA.toBuffer()],
     program.programId
   );
-  
-  console.log('Staking Vault PDA:', stakingVaultPDA.toString());
-
-  // Create or get the treasury token account
-  let treasuryWallet;
-  
-  try {
-    // Try to find treasury wallet in config
-    if (tokenConfig.communityTreasury) {
-      treasuryWallet = new anchor.web3.PublicKey(tokenConfig.communityTreasury);
-      console.log('Using treasury wallet from config:', treasuryWallet.toString());
-    } else {
-      throw new Error('Treasury wallet not found in config');
-    }
-  } catch (error) {
-    // Generate a new wallet for treasury
-    treasuryWallet = anchor.web3.Keypair.generate().publicKey;
-    console.log('Generated new treasury wallet:', treasuryWallet.toString());
-    
-    // Update config if it exists
-    if (tokenConfig) {
-      tokenConfig.treasuryWallet = treasuryWallet.toString();
-      fs.writeFileSync('./wallet-config.json', JSON.stringify(tokenConfig, null, 2));
-      console.log('Updated wallet config with treasury wallet address');
-    }
-  }
-  
-  // Fund treasury wallet with SOL for rent exemption
-  await fundAccountWithSol(provider, treasuryWallet);
-  
-  // Get or create treasury token account
-  const treasuryTokenAccount = await getOrCreateAssociatedTokenAccount(
-    provider,
-    tokenMint,
-    treasuryWallet
+  const [rewardsVault] = await anchor.web3.PublicKey.findProgramAddress(
+    [Buffer.from('rewards_vault'), stakingPoolPDA.toBuffer()],
+    program.programId
   );
-  
-  console.log('Treasury Token Account:', treasuryTokenAccount.toString());
-
-  // Create or get the staking vault token account
-  const stakingVaultTokenAccount = await getOrCreateAssociatedTokenAccount(
-    provider,
-    tokenMint,
-    stakingVaultPDA,
-    true
+  const [receiptMint] = await anchor.web3.PublicKey.findProgramAddress(
+    [Buffer.from('receipt_mint'), stakingPoolPDA.toBuffer()],
+    program.programId
+  );
+  const [tierConfig] = await anchor.web3.PublicKey.findProgramAddress(
+    [Buffer.from('tier_config'), stakingPoolPDA.toBuffer()],
+    program.programId
   );
   
-  console.log('Staking Vault Token Account:', stakingVaultTokenAccount.toString());
+  console.log('Staking Vault:', stakingVault.toString());
+  console.log('Treasury Token Account:', treasuryTokenAccount.toString());
+  console.log('Rewards Vault:', rewardsVault.toString());
 
   // Initialize staking program
   try {
     console.log('Initializing staking program...');
     
     const tx = await program.methods
-      .initialize()
+      .initialize(poolId)
       .accounts({
         stakingPool: stakingPoolPDA,
+        tierConfig,
         authority,
         tokenMint,
+        rewardMint: tokenMint,
         treasuryTokenAccount,
-        stakingVault: stakingVaultTokenAccount,
+        stakingVault,
+        rewardsVault,
+        receiptMint,
         systemProgram: anchor.web3.SystemProgram.programId,
         tokenProgram: TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -141,9 +111,10 @@ async function main() {
       programId: program.programId.toString(),
       tokenMint: tokenMint.toString(),
       stakingPool: stakingPoolPDA.toString(),
-      stakingVault: stakingVaultTokenAccount.toString(),
-      treasuryWallet: treasuryWallet.toString(),
+      stakingVault: stakingVault.toString(),
       treasuryTokenAccount: treasuryTokenAccount.toString(),
+      rewardsVault: rewardsVault.toString(),
+      receiptMint: receiptMint.toString(),
       authority: authority.toString(),
       initialized: true,
       initializationTx: tx,
@@ -166,104 +137,11 @@ async function main() {
 
   console.log('\nStaking program deployment completed successfully!');
   console.log('Next steps:');
-  console.log('1. Fund the treasury account with tokens for staking rewards');
+  console.log('1. Fund the rewards vault with fund_rewards');
   console.log('2. Update your frontend to include the StakingComponent');
   console.log('3. Integrate the staking program with your contribution system');
 }
 
-/**
- * Fund an account with SOL for rent exemption
- */
-async function fundAccountWithSol(
-  provider: anchor.AnchorProvider,
-  account: anchor.web3.PublicKey
-): Promise<string> {
-  const LAMPORTS_TO_SEND = anchor.web3.LAMPORTS_PER_SOL * 0.1; // 0.1 SOL
-  
-  try {
-    // Check if the account already has SOL
-    const balance = await provider.connection.getBalance(account);
-    if (balance >= LAMPORTS_TO_SEND) {
-      console.log(`Account ${account.toString()} already has enough SOL: ${balance / anchor.web3.LAMPORTS_PER_SOL} SOL`);
-      return 'Account already funded';
-    }
-    
-    // Create a transfer transaction
-    const transaction = new anchor.web3.Transaction().add(
-      anchor.web3.SystemProgram.transfer({
-        fromPubkey: provider.wallet.publicKey,
-        toPubkey: account,
-        lamports: LAMPORTS_TO_SEND,
-      })
-    );
-    
-    // Send the transaction
-    const signature = await provider.sendAndConfirm(transaction);
-    console.log(`Funded account ${account.toString()} with 0.1 SOL. Transaction: ${signature}`);
-    return signature;
-  } catch (error) {
-    console.error(`Error funding account ${account.toString()}:`, error);
-    throw error;
-  }
-}
-
-/**
- * Get or create an associated token account
- */
-async function getOrCreateAssociatedTokenAccount(
-  provider: anchor.AnchorProvider,
-  mint: anchor.web3.PublicKey,
-  owner: anchor.web3.PublicKey,
-  isPDA: boolean = false
-): Promise<anchor.web3.PublicKey> {
-  const associatedTokenAddress = await getAssociatedTokenAddress(mint, owner, isPDA);
-  
-  try {
-    // Check if the account already exists
-    await provider.connection.getAccountInfo(associatedTokenAddress);
-    console.log(`Token account ${associatedTokenAddress.toString()} already exists`);
-    return associatedTokenAddress;
-  } catch (error) {
-    console.log(`Creating token account for ${owner.toString()}...`);
-    
-    // Create the token account
-    const transaction = new anchor.web3.Transaction().add(
-      await createAssociatedTokenAccountInstruction(
-        provider.wallet.publicKey,
-        associatedTokenAddress,
-        owner,
-        mint,
-        isPDA
-      )
-    );
-    
-    const signature = await provider.sendAndConfirm(transaction);
-    console.log(`Created token account ${associatedTokenAddress.toString()}. Transaction: ${signature}`);
-    return associatedTokenAddress;
-  }
-}
-
-/**
- * Create an instruction to create an associated token account
- */
-async function createAssociatedTokenAccountInstruction(
-  payer: anchor.web3.PublicKey,
-  associatedToken: anchor.web3.PublicKey,
-  owner: anchor.web3.PublicKey,
-  mint: anchor.web3.PublicKey,
-  isPDA: boolean = false
-): Promise<anchor.web3.TransactionInstruction> {
-  return anchor.web3.SystemProgram.createAccount({
-    fromPubkey: payer,
-    newAccountPubkey: associatedToken,
-    space: 165,
-    lamports: await anchor.web3.Token.getMinBalanceRentForExemptAccount(
-      payer.connection
-    ),
-    programId: TOKEN_PROGRAM_ID,
-  });
-}
-
 /**
  * Update frontend environment variables
  */
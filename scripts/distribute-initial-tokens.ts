@@ -33,6 +33,12 @@ async function main() {
 
   console.log('Mint address:', mint.toString());
 
+  // Derive PDA for the allocation buckets
+  const [allocationConfig] = await anchor.web3.PublicKey.findProgramAddress(
+    [Buffer.from('allocation_config'), mint.toBuffer()],
+    program.programId
+  );
+
   // With 9 decimals, 100M tokens = 100,000,000 * 10^9
   const DECIMALS = 9;
   const DECIMAL_MULTIPLIER = new anchor.BN(10 ** DECIMALS);
@@ -129,6 +135,15 @@ async function main() {
     communityTreasury: TOTAL_SUPPLY.mul(new anchor.BN(5)).div(new anchor.BN(100)),
   };
 
+  // On-chain allocation bucket each allocation is debited from
+  const buckets: Record<string, number> = {
+    communityRewards: 0,
+    developmentFund: 1,
+    teamAllocation: 2,
+    liquidityPool: 3,
+    communityTreasury: 4,
+  };
+
   // Log the distribution plan
   console.log('\nToken Distribution Plan:');
   for (const [key, amount] of Object.entries(allocations)) {
@@ -194,9 +209,10 @@ async function main() {
       console.log(`Distributing ${amount.div(DECIMAL_MULTIPLIER).toString()} WCT to ${key}...`);
       
       const signature = await program.methods
-        .distributeInitialTokens(amount)
+        .distributeInitialTokens(buckets[key], amount)
         .accounts({
           mint,
+          allocationConfig,
           fromTokenAccount: authorityTokenAccount,
           toTokenAccount: tokenAccount,
           authority,
@@ -60,6 +60,15 @@ async function main() {
 
   console.log('Mint address:', mint.toString());
 
+  // Derive PDA for the allocation buckets
+  const [allocationConfig] = await anchor.web3.PublicKey.findProgramAddress(
+    [Buffer.from('allocation_config'), mint.toBuffer()],
+    program.programId
+  );
+
+  // Contribution rewards are paid out of the community rewards bucket
+  const COMMUNITY_BUCKET = 0;
+
   // Create logs directory if it doesn't exist
   if (!fs.existsSync(DISTRIBUTION_LOG_DIR)) {
     fs.mkdirSync(DISTRIBUTION_LOG_DIR, { recursive: true });
@@ -158,9 +167,10 @@ async function main() {
         
         // Distribute tokens
         const signature = await program.methods
-          .distributeInitialTokens(rawTokenAmount)
+          .distributeInitialTokens(COMMUNITY_BUCKET, rawTokenAmount)
           .accounts({
             mint,
+            allocationConfig,
             fromTokenAccount: authorityTokenAccount,
             toTokenAccount: recipientTokenAccount,
             authority,